@@ -40,4 +40,9 @@ module howzit::howzit {
         }
     }
 
+    // Reads the Counter struct without updating it
+    public entry fun probe_4(_account: &signer) acquires Counter {
+        let _count = borrow_global<Counter>(@howzit).count;
+    }
+
 }
\ No newline at end of file
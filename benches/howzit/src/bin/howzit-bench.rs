@@ -1,5 +1,10 @@
 use anyhow::Context;
 use aptos_sdk::rest_client::{AptosBaseUrl, Client};
+use howzit::calibrate::{Calibration, CalibrationConfig};
+use howzit::funding::MasterAccountFunder;
+use howzit::manifest::{RunConfig, RunManifest};
+use howzit::metrics::{MetricsScrapeConfig, MetricsScraper};
+use howzit::records::RecordSink;
 use howzit::Howzit;
 use std::io::Write;
 use std::{env, path::PathBuf};
@@ -21,6 +26,8 @@ pub async fn main() -> Result<(), anyhow::Error> {
 		.unwrap_or("https://aptos.devnet.suzuka.movementlabs.xyz".to_string());
 	let faucet_url = std::env::var("FAUCET_URL")
 		.unwrap_or("https://faucet.devnet.suzuka.movementlabs.xyz".to_string());
+	// the faucet may sit behind another proxy than the node
+	let faucet_token = std::env::var("FAUCET_AUTH_TOKEN").unwrap_or(token.clone());
 	let bench_output_file =
 		std::env::var("BENCH_OUTPUT_FILE").unwrap_or("howzit_bench_output.dat".to_string());
 
@@ -28,20 +35,76 @@ pub async fn main() -> Result<(), anyhow::Error> {
 		.header("Authorization", format!("Bearer {}", token).as_str())?;
 	let rest_client = rest_client_builder.build();
 
+	let sequence_strategy =
+		std::env::var("HOWZIT_SEQUENCE_STRATEGY").unwrap_or("local".to_string());
 	let howzit = Howzit::generate(
 		crate_path_buf.join("howzit"),
 		rest_client.clone(),
 		faucet_url.parse()?,
-		token,
-	);
+		faucet_token,
+	)
+	.with_sequence_strategy(sequence_strategy.parse()?);
+
+	// fund the accounts from a pre-funded master account instead of the faucet if configured
+	let howzit = match MasterAccountFunder::try_from_env(rest_client.clone()).await? {
+		Some(funder) => {
+			tracing::info!("Funding the accounts from {}", funder.address().await);
+			howzit.with_funding_source(funder)
+		}
+		None => howzit,
+	};
 
 	howzit.build_and_publish().await?;
 
+	// search the max sustainable TPS instead of running the fixed load if configured
+	if let Some(config) = CalibrationConfig::try_from_env()? {
+		let calibration = howzit.calibrate(&config).await?;
+		tracing::info!(
+			"Max sustainable TPS: {}, p99 latency: {}ms",
+			calibration.max_sustainable_tps,
+			calibration.p99_latency_ms
+		);
+		calibration.write(&Calibration::path_for(bench_output_file.as_ref()))?;
+		return Ok(());
+	}
+
+	// record every transaction of the run if configured
+	let (howzit, record_writer) = match RecordSink::try_from_env(bench_output_file.as_ref())? {
+		Some((record_sink, record_writer)) => {
+			(howzit.with_record_sink(record_sink), Some(record_writer))
+		}
+		None => (howzit, None),
+	};
+
 	// fund the accounts in an orderly manner
 	let n = std::env::var("HOWZIT_N").unwrap_or("64".to_string()).parse::<usize>()?;
 	let l = std::env::var("HOWZIT_L").unwrap_or("3000".to_string()).parse::<u64>()?;
 	let k = std::env::var("HOWZIT_K").unwrap_or("64".to_string()).parse::<u64>()?;
 
+	let chain_id = rest_client
+		.get_index()
+		.await
+		.context("Failed to get chain ID")?
+		.inner()
+		.chain_id;
+	let module_address = howzit.wallet.read().await.address().to_hex_literal();
+	let mut manifest = RunManifest::start(
+		RunConfig {
+			rest_url,
+			faucet_url,
+			bench_output_file: bench_output_file.clone(),
+			sequence_strategy,
+			n,
+			l,
+			k,
+		},
+		module_address,
+		chain_id,
+	);
+
+	// scrape the node metrics alongside the run if configured
+	let scraper = MetricsScrapeConfig::try_from_env()?.map(MetricsScraper::spawn);
+
 	for epoch in 0..l {
 		let mut futures = Vec::with_capacity(n);
 		// run the load
@@ -61,6 +124,7 @@ pub async fn main() -> Result<(), anyhow::Error> {
 			match result {
 				Ok(result) => {
 					for transaction_result in result {
+						manifest.record(transaction_result.0);
 						file.write_all(
 							format!(
 								"{:?},{:?},{:?},{:?}\n",
@@ -79,6 +143,26 @@ pub async fn main() -> Result<(), anyhow::Error> {
 				}
 			}
 		}
+		if let Some(scraper) = &scraper {
+			scraper.update_stats(manifest.stats.clone());
+		}
+	}
+
+	if let Some(scraper) = scraper {
+		MetricsScraper::write(
+			&scraper.stop(),
+			&MetricsScraper::path_for(bench_output_file.as_ref()),
+		)?;
+	}
+
+	manifest.finish();
+	manifest.write(&RunManifest::path_for(bench_output_file.as_ref()))?;
+
+	// the writer finishes once the last sink is dropped
+	drop(howzit);
+	if let Some(record_writer) = record_writer {
+		let count = record_writer.finish().await?;
+		tracing::info!("Wrote {} transaction records", count);
 	}
 
 	Ok(())
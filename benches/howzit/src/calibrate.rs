@@ -0,0 +1,282 @@
+use crate::latency::percentile;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Thresholds and bounds of the search for the max sustainable TPS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationConfig {
+	/// Rate of the first probing round.
+	pub initial_tps: u64,
+	/// The search stops doubling at this rate.
+	pub max_tps: u64,
+	pub round_duration: Duration,
+	/// A round with a higher failure ratio isn't sustainable.
+	pub max_error_rate: f64,
+	/// A round with a higher p99 latency isn't sustainable.
+	pub max_p99_latency_ms: u64,
+	/// The binary search stops when the bounds are this close.
+	pub resolution_tps: u64,
+}
+
+impl Default for CalibrationConfig {
+	fn default() -> Self {
+		CalibrationConfig {
+			initial_tps: 1,
+			max_tps: 4096,
+			round_duration: Duration::from_secs(10),
+			max_error_rate: 0.01,
+			max_p99_latency_ms: 5000,
+			resolution_tps: 1,
+		}
+	}
+}
+
+impl CalibrationConfig {
+	/// Reads the calibration configuration from the environment.
+	/// Returns None if `HOWZIT_CALIBRATE` isn't set, the fixed load is then run.
+	pub fn try_from_env() -> Result<Option<Self>, anyhow::Error> {
+		if std::env::var("HOWZIT_CALIBRATE").is_err() {
+			return Ok(None);
+		}
+		let default = CalibrationConfig::default();
+		let round_secs = env_or("HOWZIT_CALIBRATE_ROUND_SECS", default.round_duration.as_secs())?;
+		let config = CalibrationConfig {
+			initial_tps: env_or("HOWZIT_CALIBRATE_INITIAL_TPS", default.initial_tps)?,
+			max_tps: env_or("HOWZIT_CALIBRATE_MAX_TPS", default.max_tps)?,
+			round_duration: Duration::from_secs(round_secs),
+			max_error_rate: env_or("HOWZIT_CALIBRATE_MAX_ERROR_RATE", default.max_error_rate)?,
+			max_p99_latency_ms: env_or(
+				"HOWZIT_CALIBRATE_MAX_P99_LATENCY_MS",
+				default.max_p99_latency_ms,
+			)?,
+			resolution_tps: env_or("HOWZIT_CALIBRATE_RESOLUTION_TPS", default.resolution_tps)?,
+		};
+		if config.initial_tps == 0 || config.resolution_tps == 0 || round_secs == 0 {
+			return Err(anyhow::anyhow!(
+				"calibration initial TPS, resolution and round duration must be greater than 0"
+			));
+		}
+		Ok(Some(config))
+	}
+}
+
+fn env_or<T>(name: &str, default: T) -> Result<T, anyhow::Error>
+where
+	T: std::str::FromStr,
+	T::Err: std::error::Error + Send + Sync + 'static,
+{
+	match std::env::var(name) {
+		Ok(value) => value.parse().with_context(|| format!("{name} is not valid: {value}")),
+		Err(_) => Ok(default),
+	}
+}
+
+/// Outcome of a probing round at a target rate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProbeRound {
+	pub target_tps: u64,
+	pub successes: u64,
+	pub failures: u64,
+	/// Successful transactions per second over the round.
+	pub observed_tps: f64,
+	pub error_rate: f64,
+	/// p99 latency of the successful transactions, 0 if there is none.
+	pub p99_latency_ms: u64,
+	pub sustainable: bool,
+}
+
+impl ProbeRound {
+	/// Summarizes the `(success, start_ms, end_ms)` results of a round.
+	pub fn from_results(
+		target_tps: u64,
+		round_duration: Duration,
+		results: &[(bool, u64, u64)],
+		config: &CalibrationConfig,
+	) -> Self {
+		let mut latencies: Vec<u64> = results
+			.iter()
+			.filter(|(success, _, _)| *success)
+			.map(|(_, start, end)| end.saturating_sub(*start))
+			.collect();
+		latencies.sort_unstable();
+		let successes = latencies.len() as u64;
+		let failures = results.len() as u64 - successes;
+		let p99_latency_ms = percentile(&latencies, 99);
+		let error_rate = match results.len() {
+			0 => 1.0,
+			len => failures as f64 / len as f64,
+		};
+		let sustainable = successes > 0
+			&& error_rate <= config.max_error_rate
+			&& p99_latency_ms <= config.max_p99_latency_ms;
+		ProbeRound {
+			target_tps,
+			successes,
+			failures,
+			observed_tps: successes as f64 / round_duration.as_secs_f64(),
+			error_rate,
+			p99_latency_ms,
+			sustainable,
+		}
+	}
+}
+
+/// Result of a calibration: the max sustainable TPS and every round it was found from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+	/// 0 if even the initial rate isn't sustainable.
+	pub max_sustainable_tps: u64,
+	/// p99 latency observed at the max sustainable TPS.
+	pub p99_latency_ms: u64,
+	pub trace: Vec<ProbeRound>,
+}
+
+impl Calibration {
+	/// Path of the calibration associated with the bench output file.
+	pub fn path_for(bench_output_file: &Path) -> PathBuf {
+		let mut path = bench_output_file.as_os_str().to_owned();
+		path.push(".calibration.json");
+		PathBuf::from(path)
+	}
+
+	pub fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+		let json = serde_json::to_string_pretty(self)?;
+		std::fs::write(path, json)
+			.with_context(|| format!("failed to write calibration to {}", path.display()))
+	}
+}
+
+/// Searches the max sustainable TPS: the rate is doubled from `initial_tps` until a round isn't
+/// sustainable, then binary searched between the last sustainable and the first failing rates.
+/// `run_round` runs the load at the given rate for the round duration and returns the
+/// `(success, start_ms, end_ms)` result of each transaction.
+pub async fn calibrate_with<F, Fut>(
+	config: &CalibrationConfig,
+	mut run_round: F,
+) -> Result<Calibration, anyhow::Error>
+where
+	F: FnMut(u64) -> Fut,
+	Fut: Future<Output = Result<Vec<(bool, u64, u64)>, anyhow::Error>>,
+{
+	let mut trace = Vec::new();
+
+	// Last sustainable round and first failing rate.
+	let mut best: Option<ProbeRound> = None;
+	let mut failing = None;
+	let mut tps = config.initial_tps.min(config.max_tps);
+	loop {
+		let round = probe(config, &mut run_round, tps, &mut trace).await?;
+		if !round.sustainable {
+			failing = Some(tps);
+			break;
+		}
+		best = Some(round);
+		if tps >= config.max_tps {
+			break;
+		}
+		tps = tps.saturating_mul(2).min(config.max_tps);
+	}
+
+	if let (Some(mut high), Some(mut low)) = (failing, best.as_ref().map(|round| round.target_tps))
+	{
+		while high - low > config.resolution_tps {
+			let tps = low + (high - low) / 2;
+			let round = probe(config, &mut run_round, tps, &mut trace).await?;
+			if round.sustainable {
+				low = tps;
+				best = Some(round);
+			} else {
+				high = tps;
+			}
+		}
+	}
+
+	Ok(Calibration {
+		max_sustainable_tps: best.as_ref().map_or(0, |round| round.target_tps),
+		p99_latency_ms: best.as_ref().map_or(0, |round| round.p99_latency_ms),
+		trace,
+	})
+}
+
+// Runs a round at `tps` and records it in the trace.
+async fn probe<F, Fut>(
+	config: &CalibrationConfig,
+	run_round: &mut F,
+	tps: u64,
+	trace: &mut Vec<ProbeRound>,
+) -> Result<ProbeRound, anyhow::Error>
+where
+	F: FnMut(u64) -> Fut,
+	Fut: Future<Output = Result<Vec<(bool, u64, u64)>, anyhow::Error>>,
+{
+	let results = run_round(tps).await?;
+	let round = ProbeRound::from_results(tps, config.round_duration, &results, config);
+	tracing::info!("Calibration round: {:?}", round);
+	trace.push(round.clone());
+	Ok(round)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Every transaction above the cap fails, the others succeed with a 100ms latency.
+	fn capped_round(
+		tps: u64,
+		cap: u64,
+		round_secs: u64,
+	) -> Result<Vec<(bool, u64, u64)>, anyhow::Error> {
+		Ok((0..tps * round_secs)
+			.map(|i| {
+				let start = i * 1000 / tps;
+				let success = i % tps < cap;
+				(success, start, if success { start + 100 } else { start })
+			})
+			.collect())
+	}
+
+	#[tokio::test]
+	async fn test_calibration_converges_near_the_cap() -> Result<(), anyhow::Error> {
+		let config = CalibrationConfig {
+			round_duration: Duration::from_secs(2),
+			max_error_rate: 0.05,
+			..CalibrationConfig::default()
+		};
+		let cap = 300;
+		let calibration =
+			calibrate_with(&config, |tps| async move { capped_round(tps, cap, 2) }).await?;
+
+		// Up to 5% of the transactions can fail: the max sustainable rate is cap / 0.95.
+		assert!(
+			(cap..=316).contains(&calibration.max_sustainable_tps),
+			"calibrated {} TPS for a cap of {}",
+			calibration.max_sustainable_tps,
+			cap
+		);
+		assert_eq!(calibration.p99_latency_ms, 100);
+		// Doubling from 1 to 512, then the binary search.
+		let doubling: Vec<u64> =
+			calibration.trace.iter().take(10).map(|round| round.target_tps).collect();
+		assert_eq!(doubling, vec![1, 2, 4, 8, 16, 32, 64, 128, 256, 512]);
+		assert!(!calibration.trace[9].sustainable);
+		assert!(calibration.trace.len() <= 10 + 9);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_calibration_reports_zero_if_nothing_is_sustainable() -> Result<(), anyhow::Error>
+	{
+		let config = CalibrationConfig {
+			round_duration: Duration::from_secs(1),
+			..CalibrationConfig::default()
+		};
+		let calibration =
+			calibrate_with(&config, |tps| async move { capped_round(tps, 0, 1) }).await?;
+		assert_eq!(calibration.max_sustainable_tps, 0);
+		assert_eq!(calibration.trace.len(), 1);
+		Ok(())
+	}
+}
@@ -0,0 +1,151 @@
+use anyhow::Context;
+use aptos_sdk::{
+	coin_client::CoinClient,
+	rest_client::{Client, FaucetClient},
+	types::{account_address::AccountAddress, LocalAccount},
+};
+use futures::future::BoxFuture;
+use tokio::sync::Mutex;
+
+/// Funds the accounts used by the load.
+pub trait FundingSource: Send + Sync {
+	/// Funds `address` with `amount` octas, the account is created if it doesn't exist.
+	fn fund(
+		&self,
+		address: AccountAddress,
+		amount: u64,
+	) -> BoxFuture<'_, Result<(), anyhow::Error>>;
+}
+
+impl FundingSource for FaucetClient {
+	fn fund(
+		&self,
+		address: AccountAddress,
+		amount: u64,
+	) -> BoxFuture<'_, Result<(), anyhow::Error>> {
+		Box::pin(async move {
+			FaucetClient::fund(self, address, amount).await.context("Faucet funding failed")
+		})
+	}
+}
+
+/// Funds the accounts with transfers from a pre-funded master account, for the environments
+/// without a faucet or where it's rate limited.
+pub struct MasterAccountFunder {
+	rest_client: Client,
+	private_key: String,
+	master_account: Mutex<LocalAccount>,
+}
+
+impl MasterAccountFunder {
+	/// Loads the master account from its hex encoded private key.
+	pub async fn try_new(rest_client: Client, private_key: String) -> Result<Self, anyhow::Error> {
+		let master_account = load_account(&rest_client, &private_key).await?;
+		Ok(MasterAccountFunder {
+			rest_client,
+			private_key,
+			master_account: Mutex::new(master_account),
+		})
+	}
+
+	/// Returns the funder if `HOWZIT_MASTER_PRIVATE_KEY` is set, the faucet is used otherwise.
+	pub async fn try_from_env(rest_client: Client) -> Result<Option<Self>, anyhow::Error> {
+		match std::env::var("HOWZIT_MASTER_PRIVATE_KEY") {
+			Ok(private_key) => {
+				Ok(Some(MasterAccountFunder::try_new(rest_client, private_key).await?))
+			}
+			Err(_) => Ok(None),
+		}
+	}
+
+	pub async fn address(&self) -> AccountAddress {
+		self.master_account.lock().await.address()
+	}
+
+	async fn transfer(
+		&self,
+		master_account: &mut LocalAccount,
+		address: AccountAddress,
+		amount: u64,
+	) -> Result<(), anyhow::Error> {
+		let pending = CoinClient::new(&self.rest_client)
+			.transfer(master_account, address, amount, None)
+			.await
+			.context("Failed to submit the funding transfer")?;
+		self.rest_client
+			.wait_for_transaction(&pending)
+			.await
+			.context("Failed to wait for the funding transfer")?;
+		Ok(())
+	}
+}
+
+impl FundingSource for MasterAccountFunder {
+	fn fund(
+		&self,
+		address: AccountAddress,
+		amount: u64,
+	) -> BoxFuture<'_, Result<(), anyhow::Error>> {
+		Box::pin(async move {
+			// The transfers are serialized to keep the master sequence number in order.
+			let mut master_account = self.master_account.lock().await;
+			let result = self.transfer(&mut master_account, address, amount).await;
+			if result.is_err() {
+				// The failed transfer consumed a local sequence number, sync it with the chain.
+				*master_account = load_account(&self.rest_client, &self.private_key).await?;
+			}
+			result
+		})
+	}
+}
+
+/// Loads the account of `private_key` with its on chain sequence number.
+async fn load_account(
+	rest_client: &Client,
+	private_key: &str,
+) -> Result<LocalAccount, anyhow::Error> {
+	let address = LocalAccount::from_private_key(private_key, 0)
+		.context("Invalid master account private key")?
+		.address();
+	let sequence_number = rest_client
+		.get_account(address)
+		.await
+		.context("Failed to get the master account")?
+		.inner()
+		.sequence_number;
+	LocalAccount::from_private_key(private_key, sequence_number)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use aptos_sdk::rest_client::AptosBaseUrl;
+
+	// Runs against the node of `HOWZIT_TEST_REST_URL` with the master account of
+	// `HOWZIT_MASTER_PRIVATE_KEY`, skipped if they aren't set.
+	#[tokio::test]
+	async fn test_master_account_funds_a_load_account() -> Result<(), anyhow::Error> {
+		let Ok(rest_url) = std::env::var("HOWZIT_TEST_REST_URL") else {
+			return Ok(());
+		};
+		let rest_client = Client::builder(AptosBaseUrl::Custom(rest_url.parse()?)).build();
+		let Some(funder) = MasterAccountFunder::try_from_env(rest_client.clone()).await? else {
+			return Ok(());
+		};
+		let coin_client = CoinClient::new(&rest_client);
+		let master_balance = coin_client.get_account_balance(&funder.address().await).await?;
+
+		let alice = LocalAccount::generate(&mut rand::rngs::OsRng);
+		let funding_source: &dyn FundingSource = &funder;
+		funding_source.fund(alice.address(), 1_000_000).await?;
+		funding_source.fund(alice.address(), 1_000_000).await?;
+
+		assert_eq!(coin_client.get_account_balance(&alice.address()).await?, 2_000_000);
+		// The master paid the transfers and their gas.
+		assert!(
+			coin_client.get_account_balance(&funder.address().await).await?
+				< master_balance - 2_000_000
+		);
+		Ok(())
+	}
+}
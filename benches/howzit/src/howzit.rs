@@ -1,7 +1,16 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::{collections::HashMap, ops::RangeInclusive};
 
 use crate::build_and_publish_package;
+use crate::calibrate::{calibrate_with, Calibration, CalibrationConfig};
+use crate::funding::FundingSource;
+use crate::latency::{LatencyRecorder, ProbeTimer, RunReport};
+use crate::pacing::{run_at_tps_with, TpsReport};
+use crate::pool::{AccountPool, PoolAccount};
+use crate::probe::{Probe, ProbeSet};
+use crate::records::{RecordSink, TransactionRecord};
+use crate::retry::{submit_with_retry, RetryPolicy};
+use crate::sequence::{SequenceStrategy, SequenceTracker};
 use anyhow::Context;
 use aptos_framework::BuildOptions;
 use aptos_sdk::move_types::language_storage::TypeTag;
@@ -9,99 +18,46 @@ use aptos_sdk::types::account_address::AccountAddress;
 use aptos_sdk::{
 	coin_client::{CoinClient, TransferOptions},
 	move_types::{identifier::Identifier, language_storage::ModuleId},
-	rest_client::{Client, FaucetClient},
+	rest_client::{Client, FaucetClient, PendingTransaction, Transaction},
 	transaction_builder::TransactionBuilder,
-	types::{chain_id::ChainId, transaction::EntryFunction, LocalAccount},
+	types::{
+		chain_id::ChainId,
+		transaction::{EntryFunction, SignedTransaction},
+		LocalAccount,
+	},
 };
 use aptos_types::transaction::TransactionPayload;
+use futures::StreamExt;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use url::Url;
 
-#[derive(Debug, Clone)]
-pub enum Probe {
-	Probe1,
-	Probe2,
-	Probe3,
-}
-
-impl Probe {
-	// associate constant for the probe range
-	const PROBE_RANGE: RangeInclusive<u32> = 0..=3;
-
-	/// Generates a pseudorandom probe such that lower values are exponentially more likely
-	pub fn generate_exponential<R>(rng: &mut R) -> Self
-	where
-		R: rand::Rng,
-	{
-		// generate a random number between 0 and 2^PROBE_RANGE.end
-		let max = 2u32.pow(*Self::PROBE_RANGE.end());
-		let random = rng.gen_range(0, max);
-
-		// subtract the random number from 2^PROBE_RANGE.end to invert the distribution
-		let inverse = 2u32.pow(*Self::PROBE_RANGE.end()) - random;
-
-		// take the log base 2 of the result to get the probe
-		// we'll use the leading zeros trick here
-		let probe = 32 - (inverse.leading_zeros() as u32);
-
-		probe.into()
-	}
-}
-
-impl From<u32> for Probe {
-	fn from(value: u32) -> Self {
-		let value = (value % Self::PROBE_RANGE.end()) + Self::PROBE_RANGE.start();
-		match value {
-			1 => Probe::Probe1,
-			2 => Probe::Probe2,
-			3 => Probe::Probe3,
-			_ => Probe::Probe1,
-		}
-	}
-}
-
-impl TryInto<Identifier> for Probe {
-	type Error = anyhow::Error;
-
-	fn try_into(self) -> Result<Identifier, Self::Error> {
-		match self {
-			Probe::Probe1 => Ok(Identifier::new("probe_1")?),
-			Probe::Probe2 => Ok(Identifier::new("probe_2")?),
-			Probe::Probe3 => Ok(Identifier::new("probe_3")?),
-		}
-	}
-}
+#[derive(Clone)]
 pub struct Howzit {
 	pub howzit_package_path: PathBuf,
 	pub wallet: Arc<RwLock<LocalAccount>>,
 	pub rest_client: Client,
-	faucet_client_url: Url,
-	pub faucet_client: FaucetClient,
-	pub faucet_auth_token: String,
-}
-
-impl Clone for Howzit {
-	fn clone(&self) -> Self {
-		Howzit {
-			howzit_package_path: self.howzit_package_path.clone(),
-			wallet: self.wallet.clone(),
-			rest_client: self.rest_client.clone(),
-			faucet_client_url: self.faucet_client_url.clone(),
-			faucet_client: FaucetClient::new_from_rest_client(
-				self.faucet_client_url.clone(),
-				self.rest_client.clone(),
-			)
-			.with_auth_token(self.faucet_auth_token.clone()),
-			faucet_auth_token: self.faucet_auth_token.clone(),
-		}
-	}
+	/// Funds the publisher and the load accounts.
+	pub funding_source: Arc<dyn FundingSource>,
+	pub sequence_strategy: SequenceStrategy,
+	/// Entry functions of the howzit module the probe calls pick from.
+	pub probes: ProbeSet,
+	/// Receives a record per submitted transaction if set.
+	pub record_sink: Option<RecordSink>,
+	/// Load accounts of `call_probes_concurrent`, empty until funded.
+	pub account_pool: Arc<AccountPool>,
+	/// Latencies of the probe calls.
+	pub latencies: LatencyRecorder,
+	pub retry_policy: RetryPolicy,
+	/// Submissions retried after a rejection.
+	pub retries: Arc<AtomicU64>,
 }
 
 impl Howzit {
-	/// Generates a new Howzit instance with a random wallet
+	/// Generates a new Howzit instance with a random wallet, funded by the faucet
 	pub fn generate(
 		howzit_package_path: PathBuf,
 		rest_client: Client,
@@ -113,10 +69,75 @@ impl Howzit {
 			howzit_package_path,
 			wallet: Arc::new(RwLock::new(wallet)),
 			rest_client: rest_client.clone(),
-			faucet_client_url: faucet_client_url.clone(),
-			faucet_client: FaucetClient::new_from_rest_client(faucet_client_url, rest_client)
-				.with_auth_token(faucet_auth_token.clone()),
-			faucet_auth_token,
+			funding_source: Arc::new(
+				FaucetClient::new_from_rest_client(faucet_client_url, rest_client)
+					.with_auth_token(faucet_auth_token),
+			),
+			sequence_strategy: SequenceStrategy::default(),
+			probes: ProbeSet::default(),
+			record_sink: None,
+			account_pool: Arc::new(AccountPool::default()),
+			latencies: LatencyRecorder::default(),
+			retry_policy: RetryPolicy::default(),
+			retries: Arc::new(AtomicU64::new(0)),
+		}
+	}
+
+	/// Funds the accounts from `funding_source` instead of the faucet
+	pub fn with_funding_source(mut self, funding_source: impl FundingSource + 'static) -> Self {
+		self.funding_source = Arc::new(funding_source);
+		self
+	}
+
+	/// Sets how the sequence numbers of the load accounts are kept in sync with the chain
+	pub fn with_sequence_strategy(mut self, sequence_strategy: SequenceStrategy) -> Self {
+		self.sequence_strategy = sequence_strategy;
+		self
+	}
+
+	/// Picks the probes of the calls from `probes`
+	pub fn with_probes(mut self, probes: ProbeSet) -> Self {
+		self.probes = probes;
+		self
+	}
+
+	/// Records every submitted transaction in `record_sink`
+	pub fn with_record_sink(mut self, record_sink: RecordSink) -> Self {
+		self.record_sink = Some(record_sink);
+		self
+	}
+
+	/// Funds `size` load accounts for `call_probes_concurrent` to spread the probe calls over
+	pub async fn with_account_pool(mut self, size: usize) -> Result<Self, anyhow::Error> {
+		let accounts: Vec<_> =
+			(0..size).map(|_| LocalAccount::generate(&mut rand::rngs::OsRng)).collect();
+		tracing::info!("Funding {} pool accounts", size);
+		futures::future::try_join_all(
+			accounts
+				.iter()
+				.map(|account| self.funding_source.fund(account.address(), 10_000_000_000)),
+		)
+		.await
+		.context("failed to fund the account pool")?;
+		self.account_pool = Arc::new(AccountPool::new(accounts, self.sequence_strategy));
+		Ok(self)
+	}
+
+	/// Retries the rejected submissions per `retry_policy`
+	pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+		self.retry_policy = retry_policy;
+		self
+	}
+
+	/// Latency summary of the probe calls made so far, the raw samples can be written with
+	/// `latencies.write_csv`.
+	pub fn run_report(&self) -> RunReport {
+		RunReport { retries: self.retries.load(Ordering::Relaxed), ..self.latencies.report() }
+	}
+
+	fn record(&self, record: TransactionRecord) {
+		if let Some(record_sink) = &self.record_sink {
+			record_sink.record(record);
 		}
 	}
 
@@ -131,7 +152,7 @@ impl Howzit {
 		build_and_publish_package(
 			&mut *wallet,
 			self.rest_client.clone(),
-			&self.faucet_client,
+			self.funding_source.as_ref(),
 			self.howzit_package_path.clone(),
 			build_options,
 		)
@@ -154,7 +175,7 @@ impl Howzit {
 		let alice = LocalAccount::generate(&mut rand::rngs::OsRng);
 
 		tracing::info!("Funding account");
-		match self.faucet_client.fund(alice.address(), 10_000_000_000).await {
+		match self.funding_source.fund(alice.address(), 10_000_000_000).await {
 			Ok(_) => {
 				successes += 1;
 			}
@@ -166,36 +187,35 @@ impl Howzit {
 		}
 
 		tracing::info!("Calling probe function");
+		let mut sequence = SequenceTracker::new(self.sequence_strategy, alice.sequence_number());
 		let mut transactions = Vec::new();
 		for _ in 0..count {
-			let probe = Probe::generate_exponential(&mut rand::rngs::OsRng);
-			let transaction_builder = TransactionBuilder::new(
-				TransactionPayload::EntryFunction(EntryFunction::new(
-					ModuleId::new(wallet.address(), Identifier::new("howzit")?),
-					probe.clone().try_into()?,
-					vec![],
-					vec![],
-				)),
-				SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 60,
-				ChainId::new(chain_id),
-			)
-			.sender(alice.address())
-			.sequence_number(alice.sequence_number());
-			let signed_txn = alice.sign_with_transaction_builder(transaction_builder);
-
-			match self.rest_client.submit(&signed_txn).await {
+			let probe = self.probes.generate(&mut rand::rngs::OsRng);
+			let submitted_at = chrono::Utc::now();
+			let mut timer = ProbeTimer::start();
+			match self
+				.submit_probe(probe, &alice, &mut sequence, wallet.address(), chain_id)
+				.await
+			{
 				Ok(txn_hash) => {
-					transactions.push(txn_hash.into_inner());
+					timer.submitted();
+					transactions.push((probe.function.clone(), submitted_at, timer, txn_hash));
 				}
 				Err(e) => {
 					tracing::error!("Failed to submit transaction: {:?}", e);
+					self.record(TransactionRecord::rejected(
+						Some(probe.function.to_string()),
+						submitted_at.timestamp_millis() as u64,
+						e.to_string(),
+					));
 					failures += 1;
 				}
 			}
 		}
 
-		for txn_hash in transactions {
-			match self.rest_client.wait_for_transaction(&txn_hash).await {
+		for (probe_name, submitted_at, timer, txn_hash) in transactions {
+			let result = self.rest_client.wait_for_transaction(&txn_hash).await;
+			match &result {
 				Ok(_) => {
 					successes += 1;
 				}
@@ -204,11 +224,155 @@ impl Howzit {
 					failures += 1;
 				}
 			}
+			self.latencies.record(Some(probe_name.to_string()), timer, result.is_ok());
+			self.record(confirmation_record(
+				Some(probe_name.to_string()),
+				&txn_hash,
+				submitted_at,
+				result.as_ref().map(|response| response.inner()).map_err(|e| e.to_string()),
+			));
 		}
 
 		Ok((successes, failures))
 	}
 
+	/// Calls `count` generated probe functions from the account pool, with at most
+	/// `concurrency` calls in flight.
+	pub async fn call_probes_concurrent(
+		&self,
+		count: u64,
+		concurrency: usize,
+	) -> Result<(u64, u64), anyhow::Error> {
+		let (module_address, chain_id) = self.pool_probe_target().await?;
+
+		tracing::info!("Calling probe functions from {} accounts", self.account_pool.len());
+		let results: Vec<_> = futures::stream::iter(0..count)
+			.map(|i| self.call_pool_probe(i as usize, module_address, chain_id))
+			.buffer_unordered(concurrency.max(1))
+			.collect()
+			.await;
+
+		let mut successes = 0;
+		for result in results {
+			if result? {
+				successes += 1;
+			}
+		}
+		Ok((successes, count - successes))
+	}
+
+	/// Calls generated probe functions from the account pool at `target_tps` for `duration`,
+	/// the rate ramps up over the first tenth of the run.
+	pub async fn run_at_tps(
+		&self,
+		target_tps: u64,
+		duration: Duration,
+	) -> Result<TpsReport, anyhow::Error> {
+		let (module_address, chain_id) = self.pool_probe_target().await?;
+		run_at_tps_with(target_tps, duration, duration / 10, |i| {
+			let howzit = self.clone();
+			async move { howzit.call_pool_probe(i as usize, module_address, chain_id).await }
+		})
+		.await
+	}
+
+	/// The howzit module address and the chain ID the pool accounts call the probes with.
+	async fn pool_probe_target(&self) -> Result<(AccountAddress, u8), anyhow::Error> {
+		if self.account_pool.is_empty() {
+			return Err(anyhow::anyhow!(
+				"the account pool is empty, fund it with with_account_pool"
+			));
+		}
+		let chain_id = self
+			.rest_client
+			.get_index()
+			.await
+			.context("failed to get chain ID")?
+			.inner()
+			.chain_id;
+		Ok((self.wallet.read().await.address(), chain_id))
+	}
+
+	/// Calls a generated probe function from the pool account of the `i`th call.
+	/// Returns whether the transaction landed.
+	async fn call_pool_probe(
+		&self,
+		i: usize,
+		module_address: AccountAddress,
+		chain_id: u8,
+	) -> Result<bool, anyhow::Error> {
+		// The account is held until the submission so its sequence numbers are used in order.
+		let (probe_name, submitted_at, timer, pending) = {
+			let mut pool_account = self.account_pool.lock(i).await;
+			let PoolAccount { account, sequence } = &mut *pool_account;
+			let probe = self.probes.generate(&mut rand::rngs::OsRng);
+			let submitted_at = chrono::Utc::now();
+			let mut timer = ProbeTimer::start();
+			match self.submit_probe(probe, account, sequence, module_address, chain_id).await {
+				Ok(txn_hash) => {
+					timer.submitted();
+					(probe.function.clone(), submitted_at, timer, txn_hash)
+				}
+				Err(e) => {
+					tracing::error!("Failed to submit transaction: {:?}", e);
+					self.record(TransactionRecord::rejected(
+						Some(probe.function.to_string()),
+						submitted_at.timestamp_millis() as u64,
+						e.to_string(),
+					));
+					return Ok(false);
+				}
+			}
+		};
+
+		let result = self.rest_client.wait_for_transaction(&pending).await;
+		if let Err(e) = &result {
+			tracing::error!("Failed to wait for transaction: {:?}", e);
+		}
+		self.latencies.record(Some(probe_name.to_string()), timer, result.is_ok());
+		self.record(confirmation_record(
+			Some(probe_name.to_string()),
+			&pending,
+			submitted_at,
+			result.as_ref().map(|response| response.inner()).map_err(|e| e.to_string()),
+		));
+		Ok(result.is_ok())
+	}
+
+	/// Submits a call of `probe` from `sender`, the rejections are retried per the retry policy.
+	async fn submit_probe(
+		&self,
+		probe: &Probe,
+		sender: &LocalAccount,
+		sequence: &mut SequenceTracker,
+		module_address: AccountAddress,
+		chain_id: u8,
+	) -> Result<PendingTransaction, anyhow::Error> {
+		let (result, retries) = submit_with_retry(
+			&self.retry_policy,
+			sequence,
+			|| self.chain_sequence_number(sender.address()),
+			|sequence_number| async move {
+				let signed_txn =
+					probe_transaction(probe, sender, module_address, sequence_number, chain_id)?;
+				Ok(self.rest_client.submit(&signed_txn).await?.into_inner())
+			},
+		)
+		.await;
+		self.retries.fetch_add(retries as u64, Ordering::Relaxed);
+		result
+	}
+
+	async fn chain_sequence_number(&self, address: AccountAddress) -> Result<u64, anyhow::Error> {
+		Ok(self
+			.rest_client
+			.get_account(address)
+			.await
+			.context("failed to get account sequence number")?
+			.inner()
+			.sequence_number)
+	}
+
 	pub async fn call_transfers(&self, count: u64) -> Result<Vec<(bool, u64, u64)>, anyhow::Error> {
 		let mut results = Arc::new(RwLock::new(Vec::new()));
 		let mut latencies = Arc::new(RwLock::new(HashMap::new()));
@@ -219,7 +383,7 @@ impl Howzit {
 
 		tracing::info!("Funding Alice");
 		let start_time = chrono::Utc::now();
-		match self.faucet_client.fund(alice.address(), 10_000_000_000).await {
+		match self.funding_source.fund(alice.address(), 10_000_000_000).await {
 			Ok(_) => {
 				let end_time = chrono::Utc::now();
 				let mut results = results.write().await;
@@ -248,7 +412,7 @@ impl Howzit {
 		}
 		tracing::info!("Funding Bob");
 		let start_time = chrono::Utc::now();
-		match self.faucet_client.fund(bob.address(), 10_000_000_000).await {
+		match self.funding_source.fund(bob.address(), 10_000_000_000).await {
 			Ok(_) => {
 				let end_time = chrono::Utc::now();
 				let mut results = results.write().await;
@@ -288,6 +452,11 @@ impl Howzit {
 				Err(e) => {
 					let start_time = chrono::Utc::now();
 					tracing::error!("Failed to submit transaction: {:?}", e);
+					self.record(TransactionRecord::rejected(
+						None,
+						start_time.timestamp_millis() as u64,
+						e.to_string(),
+					));
 					results.write().await.push((
 						false,
 						// start timestamp
@@ -304,8 +473,24 @@ impl Howzit {
 			let rest_client = self.rest_client.clone();
 			let results = results.clone();
 			let latencies = latencies.clone();
+			let record_sink = self.record_sink.clone();
 			let fut = async move {
-				match rest_client.wait_for_transaction(&txn_hash).await {
+				let result = rest_client.wait_for_transaction(&txn_hash).await;
+				if let Some(record_sink) = &record_sink {
+					let submitted_at = latencies
+						.read()
+						.await
+						.get(&txn_hash.hash)
+						.copied()
+						.ok_or(anyhow::anyhow!("Missing latency for transaction"))?;
+					record_sink.record(confirmation_record(
+						None,
+						&txn_hash,
+						submitted_at,
+						result.as_ref().map(|response| response.inner()).map_err(|e| e.to_string()),
+					));
+				}
+				match result {
 					Ok(_) => {
 						let mut latencies = latencies.write().await;
 						let start = latencies
@@ -348,6 +533,39 @@ impl Howzit {
 		Ok(results.to_owned())
 	}
 
+	/// Runs transfers at `target_tps` for `duration`: every second, a load account is funded
+	/// and submits `target_tps` transfers.
+	pub async fn probe_round(
+		&self,
+		target_tps: u64,
+		duration: Duration,
+	) -> Result<Vec<(bool, u64, u64)>, anyhow::Error> {
+		let mut interval = tokio::time::interval(Duration::from_secs(1));
+		let mut futures = Vec::with_capacity(duration.as_secs() as usize);
+		for _ in 0..duration.as_secs() {
+			interval.tick().await;
+			let howzit = self.clone();
+			futures.push(tokio::spawn(async move { howzit.call_transfers(target_tps).await }));
+		}
+
+		let mut results = Vec::new();
+		for result in futures::future::try_join_all(futures).await? {
+			match result {
+				Ok(result) => results.extend(result),
+				Err(e) => tracing::error!("Error: {:?}", e),
+			}
+		}
+		Ok(results)
+	}
+
+	/// Finds the max sustainable TPS by running short probing rounds at increasing rates.
+	pub async fn calibrate(
+		&self,
+		config: &CalibrationConfig,
+	) -> Result<Calibration, anyhow::Error> {
+		calibrate_with(config, |tps| self.probe_round(tps, config.round_duration)).await
+	}
+
 	pub async fn call_transfers_batch(&self, count: u64) -> Result<(u64, u64), anyhow::Error> {
 		let mut successes = 0;
 		let mut failures = 0;
@@ -356,7 +574,7 @@ impl Howzit {
 		let bob = LocalAccount::generate(&mut rand::rngs::OsRng);
 
 		tracing::info!("Funding Alice");
-		match self.faucet_client.fund(alice.address(), 10_000_000_000).await {
+		match self.funding_source.fund(alice.address(), 10_000_000_000).await {
 			Ok(_) => {
 				successes += 1;
 			}
@@ -367,7 +585,7 @@ impl Howzit {
 			}
 		}
 		tracing::info!("Funding Bob");
-		match self.faucet_client.fund(bob.address(), 10_000_000_000).await {
+		match self.funding_source.fund(bob.address(), 10_000_000_000).await {
 			Ok(_) => {
 				successes += 1;
 			}
@@ -432,3 +650,111 @@ impl Howzit {
 		Ok((successes, failures))
 	}
 }
+
+/// Signs a call of `probe` of the howzit module published at `module_address`.
+fn probe_transaction(
+	probe: &Probe,
+	sender: &LocalAccount,
+	module_address: AccountAddress,
+	sequence_number: u64,
+	chain_id: u8,
+) -> Result<SignedTransaction, anyhow::Error> {
+	let transaction_builder = TransactionBuilder::new(
+		TransactionPayload::EntryFunction(EntryFunction::new(
+			ModuleId::new(module_address, Identifier::new("howzit")?),
+			probe.function.clone(),
+			vec![],
+			vec![],
+		)),
+		SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 60,
+		ChainId::new(chain_id),
+	)
+	.sender(sender.address())
+	.sequence_number(sequence_number);
+	Ok(sender.sign_transaction(transaction_builder.build()))
+}
+
+/// Record of a submitted transaction once it's confirmed or failed.
+fn confirmation_record(
+	probe: Option<String>,
+	pending: &PendingTransaction,
+	submitted_at: chrono::DateTime<chrono::Utc>,
+	result: Result<&Transaction, String>,
+) -> TransactionRecord {
+	let confirmed_at_ms = chrono::Utc::now().timestamp_millis() as u64;
+	let (confirmed_at_ms, gas_used, error) = match result {
+		Ok(transaction) => (
+			Some(confirmed_at_ms),
+			transaction.transaction_info().ok().map(|info| u64::from(info.gas_used)),
+			None,
+		),
+		Err(error) => (None, None, Some(error)),
+	};
+	TransactionRecord {
+		probe,
+		hash: Some(pending.hash.to_string()),
+		submitted_at_ms: submitted_at.timestamp_millis() as u64,
+		confirmed_at_ms,
+		gas_used,
+		success: error.is_none(),
+		error,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::funding::MasterAccountFunder;
+	use aptos_sdk::rest_client::AptosBaseUrl;
+
+	// Submits each probe of the manifest to the node of `HOWZIT_TEST_REST_URL` with the master
+	// account of `HOWZIT_MASTER_PRIVATE_KEY`, skipped if they aren't set.
+	#[tokio::test]
+	async fn test_manifest_probes_are_submitted() -> Result<(), anyhow::Error> {
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().join("probes.json");
+		std::fs::write(
+			&path,
+			r#"{"probes": [
+				{"function": "probe_1", "weight": 8},
+				{"function": "probe_2", "weight": 4},
+				{"function": "probe_3", "weight": 1},
+				{"function": "probe_4", "weight": 8}
+			]}"#,
+		)?;
+		let probes = ProbeSet::load(&path)?;
+		let functions: Vec<_> =
+			probes.probes().iter().map(|probe| probe.function.to_string()).collect();
+		assert_eq!(functions, vec!["probe_1", "probe_2", "probe_3", "probe_4"]);
+
+		let Ok(rest_url) = std::env::var("HOWZIT_TEST_REST_URL") else {
+			return Ok(());
+		};
+		let rest_client = Client::builder(AptosBaseUrl::Custom(rest_url.parse()?)).build();
+		let Some(funder) = MasterAccountFunder::try_from_env(rest_client.clone()).await? else {
+			return Ok(());
+		};
+		let howzit = Howzit::generate(
+			PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("howzit"),
+			rest_client.clone(),
+			"http://localhost:8081".parse()?,
+			String::new(),
+		)
+		.with_funding_source(funder)
+		.with_probes(probes);
+		howzit.build_and_publish().await?;
+
+		let alice = LocalAccount::generate(&mut rand::rngs::OsRng);
+		howzit.funding_source.fund(alice.address(), 1_000_000_000).await?;
+		let chain_id = rest_client.get_index().await?.inner().chain_id;
+		let module_address = howzit.wallet.read().await.address();
+		for (sequence_number, probe) in howzit.probes.probes().iter().enumerate() {
+			let signed_txn =
+				probe_transaction(probe, &alice, module_address, sequence_number as u64, chain_id)?;
+			let pending = rest_client.submit(&signed_txn).await?.into_inner();
+			// Fails if the transaction was committed but aborted.
+			rest_client.wait_for_transaction(&pending).await?;
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,214 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Latencies of a probe call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencySample {
+	pub probe: Option<String>,
+	/// From the submission until the node accepted the transaction.
+	pub submit_ms: u64,
+	/// From the submission until the transaction was committed, None if it wasn't.
+	pub confirmation_ms: Option<u64>,
+}
+
+/// Times a probe call from its submission.
+#[derive(Debug, Clone, Copy)]
+pub struct ProbeTimer {
+	started_at: Instant,
+	submit: Duration,
+}
+
+impl ProbeTimer {
+	pub fn start() -> Self {
+		ProbeTimer { started_at: Instant::now(), submit: Duration::ZERO }
+	}
+
+	/// Marks the transaction as accepted by the node.
+	pub fn submitted(&mut self) {
+		self.submit = self.started_at.elapsed();
+	}
+}
+
+/// Collects the latency samples of a run, cloned into every load task.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyRecorder {
+	samples: Arc<Mutex<Vec<LatencySample>>>,
+}
+
+impl LatencyRecorder {
+	/// Records a submitted probe once it's committed, or failed if `confirmed` is false.
+	pub fn record(&self, probe: Option<String>, timer: ProbeTimer, confirmed: bool) {
+		let sample = LatencySample {
+			probe,
+			submit_ms: timer.submit.as_millis() as u64,
+			confirmation_ms: confirmed.then(|| timer.started_at.elapsed().as_millis() as u64),
+		};
+		self.samples.lock().expect("latency samples lock poisoned").push(sample);
+	}
+
+	pub fn samples(&self) -> Vec<LatencySample> {
+		self.samples.lock().expect("latency samples lock poisoned").clone()
+	}
+
+	pub fn report(&self) -> RunReport {
+		let samples = self.samples();
+		let submit: Vec<u64> = samples.iter().map(|sample| sample.submit_ms).collect();
+		let confirmation: Vec<u64> =
+			samples.iter().filter_map(|sample| sample.confirmation_ms).collect();
+		RunReport {
+			submit: LatencySummary::from_latencies(submit),
+			confirmation: LatencySummary::from_latencies(confirmation),
+			..RunReport::default()
+		}
+	}
+
+	/// Path of the raw samples associated with the bench output file.
+	pub fn path_for(bench_output_file: &Path) -> PathBuf {
+		let mut path = bench_output_file.as_os_str().to_owned();
+		path.push(".latency.csv");
+		PathBuf::from(path)
+	}
+
+	/// Writes the raw samples as CSV, a missing confirmation latency is left empty.
+	pub fn write_csv(&self, path: &Path) -> Result<(), anyhow::Error> {
+		let mut file = std::fs::File::create(path)
+			.with_context(|| format!("failed to create latency file {}", path.display()))?;
+		writeln!(file, "probe,submit_ms,confirmation_ms")?;
+		for sample in self.samples() {
+			writeln!(
+				file,
+				"{},{},{}",
+				sample.probe.unwrap_or_default(),
+				sample.submit_ms,
+				sample.confirmation_ms.map(|ms| ms.to_string()).unwrap_or_default()
+			)?;
+		}
+		Ok(())
+	}
+}
+
+/// Distribution of latencies, in milliseconds. All 0 if there is no sample.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencySummary {
+	pub count: u64,
+	pub min_ms: u64,
+	pub max_ms: u64,
+	pub p50_ms: u64,
+	pub p90_ms: u64,
+	pub p99_ms: u64,
+}
+
+impl LatencySummary {
+	pub fn from_latencies(mut latencies: Vec<u64>) -> Self {
+		latencies.sort_unstable();
+		LatencySummary {
+			count: latencies.len() as u64,
+			min_ms: latencies.first().copied().unwrap_or(0),
+			max_ms: latencies.last().copied().unwrap_or(0),
+			p50_ms: percentile(&latencies, 50),
+			p90_ms: percentile(&latencies, 90),
+			p99_ms: percentile(&latencies, 99),
+		}
+	}
+}
+
+/// Nearest rank `p`th percentile of sorted latencies, 0 if there is none.
+pub fn percentile(sorted: &[u64], p: usize) -> u64 {
+	match sorted.len() {
+		0 => 0,
+		len => sorted[(len * p).div_ceil(100) - 1],
+	}
+}
+
+/// Latencies of the probe calls of a run: until the node accepted the transactions, and until
+/// they were committed. The submit latency includes the retries.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunReport {
+	pub submit: LatencySummary,
+	pub confirmation: LatencySummary,
+	/// Submissions retried after a rejection, only counted by Howzit.
+	pub retries: u64,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// A rest client accepting the `i`th transaction after `submit_delays[i]` and committing it
+	/// `confirmation_delays[i]` later, None if it fails.
+	struct MockClient {
+		submit_delays: Vec<u64>,
+		confirmation_delays: Vec<Option<u64>>,
+	}
+
+	impl MockClient {
+		async fn submit(&self, i: usize) {
+			tokio::time::sleep(Duration::from_millis(self.submit_delays[i])).await;
+		}
+
+		async fn wait_for_transaction(&self, i: usize) -> Result<(), anyhow::Error> {
+			let delay = self.confirmation_delays[i].ok_or(anyhow::anyhow!("failed"))?;
+			tokio::time::sleep(Duration::from_millis(delay)).await;
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn test_summary_percentiles() {
+		let summary = LatencySummary::from_latencies((1..=200).rev().collect());
+		assert_eq!(
+			summary,
+			LatencySummary {
+				count: 200,
+				min_ms: 1,
+				max_ms: 200,
+				p50_ms: 100,
+				p90_ms: 180,
+				p99_ms: 198,
+			}
+		);
+		assert_eq!(LatencySummary::from_latencies(vec![]), LatencySummary::default());
+	}
+
+	#[tokio::test]
+	async fn test_report_separates_submit_and_confirmation() -> Result<(), anyhow::Error> {
+		let client = MockClient {
+			submit_delays: vec![10, 10, 10, 10, 50],
+			confirmation_delays: vec![Some(100), Some(100), Some(100), Some(300), None],
+		};
+		let recorder = LatencyRecorder::default();
+		for i in 0..5 {
+			let mut timer = ProbeTimer::start();
+			client.submit(i).await;
+			timer.submitted();
+			let confirmed = client.wait_for_transaction(i).await.is_ok();
+			recorder.record(Some(format!("probe_{}", i)), timer, confirmed);
+		}
+
+		// The sleeps last at least their delay, allow for the scheduling.
+		let within = |ms: u64, expected: u64| (expected..expected + 30).contains(&ms);
+		let report = recorder.report();
+		assert_eq!(report.submit.count, 5);
+		assert!(within(report.submit.p50_ms, 10), "{:?}", report.submit);
+		assert!(within(report.submit.max_ms, 50), "{:?}", report.submit);
+		// The failed transaction has no confirmation latency.
+		assert_eq!(report.confirmation.count, 4);
+		assert!(within(report.confirmation.min_ms, 110), "{:?}", report.confirmation);
+		assert!(within(report.confirmation.p50_ms, 110), "{:?}", report.confirmation);
+		assert!(within(report.confirmation.p99_ms, 310), "{:?}", report.confirmation);
+
+		let dir = tempfile::tempdir()?;
+		let path = LatencyRecorder::path_for(&dir.path().join("howzit_bench_output.dat"));
+		recorder.write_csv(&path)?;
+		let csv = std::fs::read_to_string(&path)?;
+		let lines: Vec<&str> = csv.lines().collect();
+		assert_eq!(lines.len(), 6);
+		assert_eq!(lines[0], "probe,submit_ms,confirmation_ms");
+		assert!(lines[5].starts_with("probe_4,") && lines[5].ends_with(','));
+		Ok(())
+	}
+}
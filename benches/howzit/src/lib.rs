@@ -1,16 +1,29 @@
+pub mod calibrate;
+pub mod funding;
 pub mod howzit;
+pub mod latency;
+pub mod manifest;
+pub mod metrics;
+pub mod pacing;
+pub mod pool;
+pub mod probe;
+pub mod records;
+pub mod retry;
+pub mod sequence;
 pub use howzit::*;
+pub use probe::{Probe, ProbeSet};
 
 use std::path::PathBuf;
 
 use anyhow::Context;
 use aptos_framework::{BuildOptions, BuiltPackage};
 use aptos_sdk::{
-	rest_client::{Client, FaucetClient},
+	rest_client::Client,
 	transaction_builder::TransactionBuilder,
 	types::{chain_id::ChainId, LocalAccount},
 };
 use aptos_types::transaction::TransactionPayload;
+use funding::FundingSource;
 use std::time::{SystemTime, UNIX_EPOCH};
 // use std::str::FromStr;
 
@@ -23,7 +36,7 @@ pub struct PackagePublicationData {
 pub async fn build_and_publish_package(
 	wallet: &mut LocalAccount,
 	rest_client: Client,
-	faucet_client: &FaucetClient,
+	funding_source: &dyn FundingSource,
 	package_path: PathBuf,
 	options: BuildOptions,
 ) -> Result<(), anyhow::Error> {
@@ -38,7 +51,7 @@ pub async fn build_and_publish_package(
 	);
 
 	// fund the account
-	faucet_client
+	funding_source
 		.fund(wallet.address(), 10_000_000_000)
 		.await
 		.context("Failed to fund account")?;
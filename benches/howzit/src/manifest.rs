@@ -0,0 +1,144 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Parameters a Howzit run was started with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunConfig {
+	pub rest_url: String,
+	pub faucet_url: String,
+	pub bench_output_file: String,
+	pub sequence_strategy: String,
+	/// Number of concurrent load accounts per epoch.
+	pub n: usize,
+	/// Number of epochs.
+	pub l: u64,
+	/// Number of transfers per load account.
+	pub k: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunStats {
+	pub successes: u64,
+	pub failures: u64,
+}
+
+/// Describes how a result set was produced, written as JSON next to the bench output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunManifest {
+	pub config: RunConfig,
+	pub module_address: String,
+	pub chain_id: u8,
+	pub crate_version: String,
+	pub git_revision: Option<String>,
+	pub start_timestamp_ms: u64,
+	pub end_timestamp_ms: Option<u64>,
+	pub stats: RunStats,
+}
+
+impl RunManifest {
+	/// Starts the manifest of a run against the howzit module published at `module_address`.
+	pub fn start(config: RunConfig, module_address: String, chain_id: u8) -> Self {
+		RunManifest {
+			config,
+			module_address,
+			chain_id,
+			crate_version: env!("CARGO_PKG_VERSION").to_string(),
+			git_revision: git_revision(),
+			start_timestamp_ms: chrono::Utc::now().timestamp_millis() as u64,
+			end_timestamp_ms: None,
+			stats: RunStats::default(),
+		}
+	}
+
+	/// Records the outcome of a transaction.
+	pub fn record(&mut self, success: bool) {
+		if success {
+			self.stats.successes += 1;
+		} else {
+			self.stats.failures += 1;
+		}
+	}
+
+	pub fn finish(&mut self) {
+		self.end_timestamp_ms = Some(chrono::Utc::now().timestamp_millis() as u64);
+	}
+
+	/// Path of the manifest associated with the bench output file.
+	pub fn path_for(bench_output_file: &Path) -> PathBuf {
+		let mut path = bench_output_file.as_os_str().to_owned();
+		path.push(".manifest.json");
+		PathBuf::from(path)
+	}
+
+	pub fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+		let json = serde_json::to_string_pretty(self)?;
+		std::fs::write(path, json)
+			.with_context(|| format!("failed to write run manifest to {}", path.display()))
+	}
+}
+
+/// Revision of the source tree Howzit was run from, if it is a git checkout.
+fn git_revision() -> Option<String> {
+	let output = std::process::Command::new("git")
+		.args(["rev-parse", "HEAD"])
+		.current_dir(env!("CARGO_MANIFEST_DIR"))
+		.output()
+		.ok()?;
+	if !output.status.success() {
+		return None;
+	}
+	String::from_utf8(output.stdout)
+		.ok()
+		.map(|revision| revision.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_manifest_contains_run_fields() -> Result<(), anyhow::Error> {
+		let dir = tempfile::tempdir()?;
+		let output = dir.path().join("howzit_bench_output.dat");
+		let config = RunConfig {
+			rest_url: "http://localhost:30731".to_string(),
+			faucet_url: "http://localhost:30732".to_string(),
+			bench_output_file: output.display().to_string(),
+			sequence_strategy: "local".to_string(),
+			n: 2,
+			l: 1,
+			k: 3,
+		};
+
+		let mut manifest = RunManifest::start(config.clone(), "0xcafe".to_string(), 27);
+		manifest.record(true);
+		manifest.record(true);
+		manifest.record(false);
+		manifest.finish();
+		let path = RunManifest::path_for(&output);
+		manifest.write(&path)?;
+
+		assert_eq!(path, dir.path().join("howzit_bench_output.dat.manifest.json"));
+		let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+		for field in [
+			"config",
+			"module_address",
+			"chain_id",
+			"crate_version",
+			"git_revision",
+			"start_timestamp_ms",
+			"end_timestamp_ms",
+			"stats",
+		] {
+			assert!(json.get(field).is_some(), "missing manifest field {field}");
+		}
+		let read: RunManifest = serde_json::from_value(json)?;
+		assert_eq!(read.config, config);
+		assert_eq!(read.module_address, "0xcafe");
+		assert_eq!(read.chain_id, 27);
+		assert_eq!(read.stats, RunStats { successes: 2, failures: 1 });
+		assert!(read.end_timestamp_ms.unwrap() >= read.start_timestamp_ms);
+		Ok(())
+	}
+}
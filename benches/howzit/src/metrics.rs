@@ -0,0 +1,254 @@
+use crate::manifest::RunStats;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Gauges scraped by default: mempool size, process CPU time and last committed block time.
+pub const DEFAULT_METRIC_NAMES: &[&str] = &[
+	"aptos_core_mempool_index_size",
+	"process_cpu_seconds_total",
+	"aptos_consensus_last_committed_block_timestamp_usecs",
+];
+
+/// Where and what to scrape from the node during a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricsScrapeConfig {
+	/// Url of the node Prometheus endpoint, e.g. `http://localhost:9464/metrics`.
+	pub url: String,
+	pub metric_names: Vec<String>,
+	pub interval: Duration,
+}
+
+impl MetricsScrapeConfig {
+	/// Reads the scrape configuration from the environment.
+	/// Returns None if `HOWZIT_METRICS_URL` isn't set, scraping is then disabled.
+	pub fn try_from_env() -> Result<Option<Self>, anyhow::Error> {
+		let url = match std::env::var("HOWZIT_METRICS_URL") {
+			Ok(url) => url,
+			Err(_) => return Ok(None),
+		};
+		let metric_names = match std::env::var("HOWZIT_METRICS_NAMES") {
+			Ok(names) => names
+				.split(',')
+				.map(|name| name.trim().to_string())
+				.filter(|name| !name.is_empty())
+				.collect(),
+			Err(_) => DEFAULT_METRIC_NAMES.iter().map(|name| name.to_string()).collect(),
+		};
+		let interval_ms = std::env::var("HOWZIT_METRICS_INTERVAL_MS")
+			.unwrap_or("5000".to_string())
+			.parse::<u64>()
+			.context("HOWZIT_METRICS_INTERVAL_MS must be a number of milliseconds")?;
+		Ok(Some(MetricsScrapeConfig {
+			url,
+			metric_names,
+			interval: Duration::from_millis(interval_ms),
+		}))
+	}
+}
+
+/// A point of the combined time series: the node gauges and the Howzit stats at that time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSample {
+	pub timestamp_ms: u64,
+	pub stats: RunStats,
+	/// Scraped gauges, a metric missing from the endpoint output is absent.
+	pub metrics: BTreeMap<String, f64>,
+}
+
+/// Periodically scrapes the node metrics in the background.
+pub struct MetricsScraper {
+	samples: Arc<Mutex<Vec<MetricSample>>>,
+	stats: Arc<Mutex<RunStats>>,
+	handle: tokio::task::JoinHandle<()>,
+}
+
+impl MetricsScraper {
+	pub fn spawn(config: MetricsScrapeConfig) -> Self {
+		let samples = Arc::new(Mutex::new(Vec::new()));
+		let stats = Arc::new(Mutex::new(RunStats::default()));
+		let handle = tokio::spawn(scrape_loop(config, samples.clone(), stats.clone()));
+		MetricsScraper { samples, stats, handle }
+	}
+
+	/// Updates the Howzit stats recorded with the next samples.
+	pub fn update_stats(&self, stats: RunStats) {
+		*self.stats.lock().expect("metrics stats lock poisoned") = stats;
+	}
+
+	pub fn samples(&self) -> Vec<MetricSample> {
+		self.samples.lock().expect("metrics samples lock poisoned").clone()
+	}
+
+	/// Stops scraping and returns the recorded samples.
+	pub fn stop(self) -> Vec<MetricSample> {
+		self.handle.abort();
+		self.samples()
+	}
+
+	/// Path of the time series associated with the bench output file.
+	pub fn path_for(bench_output_file: &Path) -> PathBuf {
+		let mut path = bench_output_file.as_os_str().to_owned();
+		path.push(".metrics.jsonl");
+		PathBuf::from(path)
+	}
+
+	/// Writes the samples as JSON lines.
+	pub fn write(samples: &[MetricSample], path: &Path) -> Result<(), anyhow::Error> {
+		let mut file = std::fs::File::create(path)
+			.with_context(|| format!("failed to create metrics file {}", path.display()))?;
+		for sample in samples {
+			writeln!(file, "{}", serde_json::to_string(sample)?)?;
+		}
+		Ok(())
+	}
+}
+
+async fn scrape_loop(
+	config: MetricsScrapeConfig,
+	samples: Arc<Mutex<Vec<MetricSample>>>,
+	stats: Arc<Mutex<RunStats>>,
+) {
+	let client = reqwest::Client::new();
+	let mut interval = tokio::time::interval(config.interval);
+	// Only warn on the first failure of a series, the endpoint can be missing for the whole run.
+	let mut reachable = true;
+	loop {
+		interval.tick().await;
+		let metrics = match scrape(&client, &config.url).await {
+			Ok(body) => {
+				reachable = true;
+				parse_metrics(&body, &config.metric_names)
+			}
+			Err(e) => {
+				if reachable {
+					tracing::warn!("Failed to scrape metrics from {}: {:?}", config.url, e);
+				}
+				reachable = false;
+				continue;
+			}
+		};
+		let stats = stats.lock().expect("metrics stats lock poisoned").clone();
+		let sample = MetricSample {
+			timestamp_ms: chrono::Utc::now().timestamp_millis() as u64,
+			stats,
+			metrics,
+		};
+		samples.lock().expect("metrics samples lock poisoned").push(sample);
+	}
+}
+
+async fn scrape(client: &reqwest::Client, url: &str) -> Result<String, anyhow::Error> {
+	let response = client.get(url).send().await?.error_for_status()?;
+	Ok(response.text().await?)
+}
+
+/// Extracts the selected metrics from a Prometheus text exposition.
+/// Labelled series of the same metric are summed.
+pub fn parse_metrics(body: &str, metric_names: &[String]) -> BTreeMap<String, f64> {
+	let mut metrics = BTreeMap::new();
+	for line in body.lines().map(str::trim) {
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		let name_end = line.find(|c: char| c == '{' || c.is_whitespace()).unwrap_or(line.len());
+		let name = &line[..name_end];
+		if !metric_names.iter().any(|metric_name| metric_name == name) {
+			continue;
+		}
+		// The value follows the labels, an optional timestamp can follow the value.
+		let rest = match line[name_end..].rfind('}') {
+			Some(labels_end) => &line[name_end + labels_end + 1..],
+			None => &line[name_end..],
+		};
+		if let Some(value) = rest.split_whitespace().next().and_then(|v| v.parse::<f64>().ok()) {
+			*metrics.entry(name.to_string()).or_insert(0.0) += value;
+		}
+	}
+	metrics
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+	const METRICS_BODY: &str = "\
+# HELP aptos_core_mempool_index_size Size of a mempool index
+# TYPE aptos_core_mempool_index_size gauge
+aptos_core_mempool_index_size{index=\"system_ttl\"} 12
+aptos_core_mempool_index_size{index=\"expiration\"} 30
+process_cpu_seconds_total 4.5
+unselected_metric 1
+";
+
+	async fn serve_metrics() -> Result<String, anyhow::Error> {
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+		let addr = listener.local_addr()?;
+		tokio::spawn(async move {
+			while let Ok((mut stream, _)) = listener.accept().await {
+				let mut request = [0; 1024];
+				let _ = stream.read(&mut request).await;
+				let response = format!(
+					"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+					METRICS_BODY.len(),
+					METRICS_BODY
+				);
+				let _ = stream.write_all(response.as_bytes()).await;
+			}
+		});
+		Ok(format!("http://{}/metrics", addr))
+	}
+
+	#[tokio::test]
+	async fn test_scraper_records_samples() -> Result<(), anyhow::Error> {
+		let url = serve_metrics().await?;
+		let scraper = MetricsScraper::spawn(MetricsScrapeConfig {
+			url,
+			metric_names: vec![
+				"aptos_core_mempool_index_size".to_string(),
+				"process_cpu_seconds_total".to_string(),
+				"missing_metric".to_string(),
+			],
+			interval: Duration::from_millis(10),
+		});
+		scraper.update_stats(RunStats { successes: 3, failures: 1 });
+
+		let mut samples = Vec::new();
+		for _ in 0..100 {
+			samples = scraper.samples();
+			if samples.len() >= 2 {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(20)).await;
+		}
+		let samples_len = scraper.stop().len();
+
+		assert!(samples_len >= 2, "expected at least 2 samples, got {}", samples_len);
+		let last = samples.last().unwrap();
+		assert_eq!(last.stats, RunStats { successes: 3, failures: 1 });
+		assert_eq!(last.metrics.get("aptos_core_mempool_index_size"), Some(&42.0));
+		assert_eq!(last.metrics.get("process_cpu_seconds_total"), Some(&4.5));
+		assert!(!last.metrics.contains_key("missing_metric"));
+		assert!(!last.metrics.contains_key("unselected_metric"));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_missing_endpoint_records_nothing() -> Result<(), anyhow::Error> {
+		// Bind then drop a listener to get a port nothing listens on.
+		let addr = tokio::net::TcpListener::bind("127.0.0.1:0").await?.local_addr()?;
+		let scraper = MetricsScraper::spawn(MetricsScrapeConfig {
+			url: format!("http://{}/metrics", addr),
+			metric_names: vec!["process_cpu_seconds_total".to_string()],
+			interval: Duration::from_millis(10),
+		});
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		assert!(scraper.stop().is_empty());
+		Ok(())
+	}
+}
@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Longest wait for a token before the rate is updated, so a slow start doesn't sleep through
+/// the ramp up.
+const RATE_UPDATE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Hands out tokens at `rate` per second, up to `burst` saved while no one takes them.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+	rate: f64,
+	burst: f64,
+	tokens: f64,
+	refilled_at: Instant,
+}
+
+impl TokenBucket {
+	pub fn new(rate: f64, burst: f64, now: Instant) -> Self {
+		TokenBucket { rate, burst: burst.max(1.0), tokens: 1.0, refilled_at: now }
+	}
+
+	pub fn set_rate(&mut self, rate: f64) {
+		self.rate = rate;
+	}
+
+	fn refill(&mut self, now: Instant) {
+		let elapsed = now.saturating_duration_since(self.refilled_at).as_secs_f64();
+		self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+		self.refilled_at = now;
+	}
+
+	/// Takes a token at `now`, or returns how long until one is available.
+	pub fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+		self.refill(now);
+		if self.tokens >= 1.0 {
+			self.tokens -= 1.0;
+			return Ok(());
+		}
+		Err(Duration::from_secs_f64((1.0 - self.tokens) / self.rate.max(f64::EPSILON)))
+	}
+
+	/// Waits for a token without blocking the runtime.
+	pub async fn take(&mut self) {
+		while let Err(wait) = self.try_take(Instant::now()) {
+			tokio::time::sleep(wait).await;
+		}
+	}
+}
+
+/// Outcome of a run at a target rate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TpsReport {
+	pub target_tps: u64,
+	/// Submissions per second once the ramp up is over.
+	pub achieved_tps: f64,
+	pub submitted: u64,
+	pub successes: u64,
+	pub failures: u64,
+}
+
+/// Submits at `target_tps` for `duration`, the rate rising linearly from 0 over `ramp_up`.
+/// `submit` submits the `i`th call and resolves once it landed or failed; the calls are
+/// spawned, so a slow one doesn't hold back the next.
+pub async fn run_at_tps_with<F, Fut>(
+	target_tps: u64,
+	duration: Duration,
+	ramp_up: Duration,
+	mut submit: F,
+) -> Result<TpsReport, anyhow::Error>
+where
+	F: FnMut(u64) -> Fut,
+	Fut: Future<Output = Result<bool, anyhow::Error>> + Send + 'static,
+{
+	let target = target_tps as f64;
+	let start = Instant::now();
+	let steady_at = start + ramp_up.min(duration);
+	let end = start + duration;
+	// Up to 50ms of submissions can be made at once to catch up with the sleep granularity.
+	let mut bucket = TokenBucket::new(target, target / 20.0, start);
+
+	let mut calls = tokio::task::JoinSet::new();
+	let mut submitted = 0;
+	let mut steady_submitted = 0;
+	loop {
+		let now = Instant::now();
+		if now >= end {
+			break;
+		}
+		if now < steady_at {
+			let ramp = now.duration_since(start).as_secs_f64() / ramp_up.as_secs_f64();
+			bucket.set_rate((target * ramp).max(1.0));
+		} else {
+			bucket.set_rate(target);
+		}
+		if let Err(wait) = bucket.try_take(now) {
+			tokio::time::sleep(wait.min(RATE_UPDATE_INTERVAL)).await;
+			continue;
+		}
+		calls.spawn(submit(submitted));
+		submitted += 1;
+		if now >= steady_at {
+			steady_submitted += 1;
+		}
+	}
+	let steady_secs = end.saturating_duration_since(steady_at).as_secs_f64();
+
+	let mut successes = 0;
+	while let Some(result) = calls.join_next().await {
+		match result? {
+			Ok(true) => successes += 1,
+			Ok(false) => {}
+			Err(e) => tracing::error!("Failed to submit probe: {:?}", e),
+		}
+	}
+
+	let report = TpsReport {
+		target_tps,
+		achieved_tps: if steady_secs > 0.0 { steady_submitted as f64 / steady_secs } else { 0.0 },
+		submitted,
+		successes,
+		failures: submitted - successes,
+	};
+	tracing::info!("Target TPS: {}, achieved TPS: {:.1}", target_tps, report.achieved_tps);
+	Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_token_bucket_paces_at_the_rate() {
+		let start = Instant::now();
+		let mut bucket = TokenBucket::new(10.0, 2.0, start);
+		assert!(bucket.try_take(start).is_ok());
+		assert_eq!(bucket.try_take(start), Err(Duration::from_millis(100)));
+		assert!(bucket.try_take(start + Duration::from_millis(100)).is_ok());
+		// At most the burst is saved while idle.
+		let later = start + Duration::from_secs(5);
+		assert!(bucket.try_take(later).is_ok());
+		assert!(bucket.try_take(later).is_ok());
+		assert!(bucket.try_take(later).is_err());
+	}
+
+	#[tokio::test]
+	async fn test_achieved_rate_is_near_the_target() -> Result<(), anyhow::Error> {
+		// A client that confirms every call after 50ms.
+		let report =
+			run_at_tps_with(200, Duration::from_secs(2), Duration::from_millis(500), |_| async {
+				tokio::time::sleep(Duration::from_millis(50)).await;
+				Ok(true)
+			})
+			.await?;
+
+		assert!(
+			(180.0..=220.0).contains(&report.achieved_tps),
+			"achieved {} TPS for a target of 200",
+			report.achieved_tps
+		);
+		// The ramp up submits less than the target rate.
+		assert!(report.submitted < 400);
+		assert_eq!(report.successes, report.submitted);
+		assert_eq!(report.failures, 0);
+		Ok(())
+	}
+}
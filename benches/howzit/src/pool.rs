@@ -0,0 +1,91 @@
+use crate::sequence::{SequenceStrategy, SequenceTracker};
+use aptos_sdk::types::{account_address::AccountAddress, LocalAccount};
+use tokio::sync::{Mutex, MutexGuard};
+
+/// A funded load account and the tracker of its next sequence number.
+pub struct PoolAccount {
+	pub account: LocalAccount,
+	pub sequence: SequenceTracker,
+}
+
+/// Funded load accounts the probe calls are spread over, so the submissions aren't ordered
+/// behind a single sequence number.
+#[derive(Default)]
+pub struct AccountPool {
+	accounts: Vec<Mutex<PoolAccount>>,
+}
+
+impl AccountPool {
+	pub fn new(accounts: Vec<LocalAccount>, strategy: SequenceStrategy) -> Self {
+		let accounts = accounts
+			.into_iter()
+			.map(|account| {
+				let sequence = SequenceTracker::new(strategy, account.sequence_number());
+				Mutex::new(PoolAccount { account, sequence })
+			})
+			.collect();
+		AccountPool { accounts }
+	}
+
+	pub fn len(&self) -> usize {
+		self.accounts.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.accounts.is_empty()
+	}
+
+	/// Locks the account of the `i`th call, the calls are assigned round robin.
+	pub async fn lock(&self, i: usize) -> MutexGuard<'_, PoolAccount> {
+		self.accounts[i % self.accounts.len()].lock().await
+	}
+
+	pub async fn addresses(&self) -> Vec<AccountAddress> {
+		let mut addresses = Vec::with_capacity(self.accounts.len());
+		for account in &self.accounts {
+			addresses.push(account.lock().await.account.address());
+		}
+		addresses
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::funding::MasterAccountFunder;
+	use crate::Howzit;
+	use aptos_sdk::rest_client::{AptosBaseUrl, Client};
+	use std::path::PathBuf;
+
+	// Runs against the node of `HOWZIT_TEST_REST_URL` with the master account of
+	// `HOWZIT_MASTER_PRIVATE_KEY`, skipped if they aren't set.
+	#[tokio::test]
+	async fn test_concurrent_probes_all_land() -> Result<(), anyhow::Error> {
+		let Ok(rest_url) = std::env::var("HOWZIT_TEST_REST_URL") else {
+			return Ok(());
+		};
+		let rest_client = Client::builder(AptosBaseUrl::Custom(rest_url.parse()?)).build();
+		let Some(funder) = MasterAccountFunder::try_from_env(rest_client.clone()).await? else {
+			return Ok(());
+		};
+		let howzit = Howzit::generate(
+			PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("howzit"),
+			rest_client.clone(),
+			"http://localhost:8081".parse()?,
+			String::new(),
+		)
+		.with_funding_source(funder);
+		howzit.build_and_publish().await?;
+		let howzit = howzit.with_account_pool(4).await?;
+
+		let (successes, failures) = howzit.call_probes_concurrent(32, 8).await?;
+		assert_eq!((successes, failures), (32, 0));
+
+		// Every submission consumed a sequence number of its pool account.
+		let mut landed = 0;
+		for address in howzit.account_pool.addresses().await {
+			landed += rest_client.get_account(address).await?.inner().sequence_number;
+		}
+		assert_eq!(landed, 32);
+		Ok(())
+	}
+}
@@ -0,0 +1,166 @@
+use anyhow::Context;
+use aptos_sdk::move_types::identifier::Identifier;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::str::FromStr;
+
+/// An entry function of the howzit module called by the load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Probe {
+	pub function: Identifier,
+	/// The probe is picked with its weight over the sum of the weights of its set.
+	pub weight: u32,
+}
+
+impl Probe {
+	pub fn new(function: &str, weight: u32) -> Result<Self, anyhow::Error> {
+		Ok(Probe { function: Identifier::new(function)?, weight })
+	}
+}
+
+impl TryInto<Identifier> for Probe {
+	type Error = anyhow::Error;
+
+	fn try_into(self) -> Result<Identifier, Self::Error> {
+		Ok(self.function)
+	}
+}
+
+/// A probe of the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProbeEntry {
+	pub function: String,
+	pub weight: u32,
+}
+
+/// The probes of a run, as JSON: `{"probes": [{"function": "probe_1", "weight": 4}]}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProbeManifest {
+	pub probes: Vec<ProbeEntry>,
+}
+
+/// The probes a run picks from, to model different workload mixes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeSet {
+	probes: Vec<Probe>,
+	total_weight: u64,
+}
+
+impl ProbeSet {
+	pub fn new(probes: Vec<Probe>) -> Result<Self, anyhow::Error> {
+		let total_weight = probes.iter().map(|probe| probe.weight as u64).sum();
+		if total_weight == 0 {
+			return Err(anyhow::anyhow!("the probe weights must not all be 0"));
+		}
+		Ok(ProbeSet { probes, total_weight })
+	}
+
+	/// `probe_1`, `probe_2`... with the weights in order.
+	pub fn from_weights(weights: &[u32]) -> Result<Self, anyhow::Error> {
+		let probes = weights
+			.iter()
+			.enumerate()
+			.map(|(i, weight)| Probe::new(&format!("probe_{}", i + 1), *weight))
+			.collect::<Result<Vec<_>, _>>()?;
+		ProbeSet::new(probes)
+	}
+
+	/// Loads the probes of a [ProbeManifest].
+	pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+		let json = std::fs::read_to_string(path)
+			.with_context(|| format!("failed to read probe manifest {}", path.display()))?;
+		let manifest: ProbeManifest = serde_json::from_str(&json)
+			.with_context(|| format!("invalid probe manifest {}", path.display()))?;
+		let probes = manifest
+			.probes
+			.iter()
+			.map(|entry| Probe::new(&entry.function, entry.weight))
+			.collect::<Result<Vec<_>, _>>()?;
+		ProbeSet::new(probes)
+	}
+
+	pub fn probes(&self) -> &[Probe] {
+		&self.probes
+	}
+
+	/// Picks a pseudorandom probe according to the weights.
+	pub fn generate<R>(&self, rng: &mut R) -> &Probe
+	where
+		R: rand::Rng,
+	{
+		// pick the probe whose cumulated weights first exceed the random number
+		let mut random = rng.gen_range(0, self.total_weight);
+		for probe in &self.probes {
+			if random < probe.weight as u64 {
+				return probe;
+			}
+			random -= probe.weight as u64;
+		}
+		unreachable!("the random number is below the total weight")
+	}
+}
+
+impl Default for ProbeSet {
+	/// `probe_1` to `probe_3`, lower probes are exponentially more likely.
+	fn default() -> Self {
+		ProbeSet::from_weights(&[4, 2, 1]).expect("default probes are valid")
+	}
+}
+
+impl FromStr for ProbeSet {
+	type Err = anyhow::Error;
+
+	/// Parses `exponential` or `weighted:<w1>,<w2>,...`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s == "exponential" {
+			return Ok(ProbeSet::default());
+		}
+		let weights = s
+			.strip_prefix("weighted:")
+			.ok_or(anyhow::anyhow!("unknown probe distribution: {}", s))?
+			.split(',')
+			.map(|weight| weight.trim().parse::<u32>())
+			.collect::<Result<Vec<_>, _>>()?;
+		ProbeSet::from_weights(&weights)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::SeedableRng;
+
+	#[test]
+	fn test_weighted_probes_follow_the_weights() {
+		let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+		let probes: ProbeSet = "weighted:1,2,7".parse().unwrap();
+		let samples = 30_000;
+		let mut counts = [0u32; 3];
+		for _ in 0..samples {
+			let probe = probes.generate(&mut rng);
+			let i = probes.probes().iter().position(|p| p == probe).unwrap();
+			counts[i] += 1;
+		}
+		for (count, expected) in counts.iter().zip([0.1, 0.2, 0.7]) {
+			let frequency = *count as f64 / samples as f64;
+			assert!((frequency - expected).abs() < 0.02, "{:?} for weights 1,2,7", counts);
+		}
+
+		// A probe without weight is never picked.
+		let probes = ProbeSet::from_weights(&[0, 1, 0]).unwrap();
+		for _ in 0..1_000 {
+			assert_eq!(probes.generate(&mut rng).function.as_str(), "probe_2");
+		}
+	}
+
+	#[test]
+	fn test_parse_distribution() {
+		assert_eq!("exponential".parse::<ProbeSet>().unwrap(), ProbeSet::default());
+		assert_eq!(
+			"weighted:1,2".parse::<ProbeSet>().unwrap(),
+			ProbeSet::from_weights(&[1, 2]).unwrap()
+		);
+		assert!("weighted:0,0,0".parse::<ProbeSet>().is_err());
+		assert!("uniform".parse::<ProbeSet>().is_err());
+	}
+}
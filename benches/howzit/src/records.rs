@@ -0,0 +1,174 @@
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::mpsc;
+
+/// Outcome of a submitted transaction, for the analysis of the tail behavior.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionRecord {
+	/// Called probe function, None for a transfer.
+	pub probe: Option<String>,
+	/// None if the submission failed.
+	pub hash: Option<String>,
+	pub submitted_at_ms: u64,
+	/// None if the transaction wasn't confirmed.
+	pub confirmed_at_ms: Option<u64>,
+	pub gas_used: Option<u64>,
+	pub success: bool,
+	pub error: Option<String>,
+}
+
+impl TransactionRecord {
+	/// Record of a transaction whose submission failed.
+	pub fn rejected(probe: Option<String>, submitted_at_ms: u64, error: String) -> Self {
+		TransactionRecord {
+			probe,
+			hash: None,
+			submitted_at_ms,
+			confirmed_at_ms: None,
+			gas_used: None,
+			success: false,
+			error: Some(error),
+		}
+	}
+}
+
+/// Writes the transaction records as JSON lines in the background, so the load isn't slowed
+/// down by the writes. Cloned into every load task.
+#[derive(Debug, Clone)]
+pub struct RecordSink {
+	sender: mpsc::UnboundedSender<TransactionRecord>,
+}
+
+impl RecordSink {
+	/// Returns the sink if `HOWZIT_RECORDS` is set, the records are then written next to the
+	/// bench output file. Disabled by default, a run produces a record per transaction.
+	pub fn try_from_env(
+		bench_output_file: &Path,
+	) -> Result<Option<(Self, RecordWriter)>, anyhow::Error> {
+		if std::env::var("HOWZIT_RECORDS").is_err() {
+			return Ok(None);
+		}
+		Ok(Some(RecordSink::spawn(&RecordSink::path_for(bench_output_file))?))
+	}
+
+	/// Starts writing the records to `path`, the file is truncated.
+	pub fn spawn(path: &Path) -> Result<(Self, RecordWriter), anyhow::Error> {
+		let file = std::fs::File::create(path)
+			.with_context(|| format!("failed to create records file {}", path.display()))?;
+		let (sender, receiver) = mpsc::unbounded_channel();
+		let handle = tokio::spawn(write_loop(tokio::fs::File::from_std(file), receiver));
+		Ok((RecordSink { sender }, RecordWriter { handle }))
+	}
+
+	pub fn record(&self, record: TransactionRecord) {
+		if self.sender.send(record).is_err() {
+			tracing::warn!("Transaction record dropped, the record writer has stopped");
+		}
+	}
+
+	/// Path of the records associated with the bench output file.
+	pub fn path_for(bench_output_file: &Path) -> PathBuf {
+		let mut path = bench_output_file.as_os_str().to_owned();
+		path.push(".records.jsonl");
+		PathBuf::from(path)
+	}
+}
+
+/// Background task writing the records.
+pub struct RecordWriter {
+	handle: tokio::task::JoinHandle<Result<u64, anyhow::Error>>,
+}
+
+impl RecordWriter {
+	/// Waits until every record of the dropped sinks is written, returns the number of records.
+	pub async fn finish(self) -> Result<u64, anyhow::Error> {
+		self.handle.await?
+	}
+}
+
+async fn write_loop(
+	file: tokio::fs::File,
+	mut receiver: mpsc::UnboundedReceiver<TransactionRecord>,
+) -> Result<u64, anyhow::Error> {
+	let mut writer = BufWriter::new(file);
+	let mut count = 0;
+	while let Some(record) = receiver.recv().await {
+		let mut line = serde_json::to_vec(&record)?;
+		line.push(b'\n');
+		writer.write_all(&line).await?;
+		count += 1;
+	}
+	writer.flush().await?;
+	Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_sink_writes_one_record_per_transaction() -> Result<(), anyhow::Error> {
+		let dir = tempfile::tempdir()?;
+		let path = RecordSink::path_for(&dir.path().join("howzit_bench_output.dat"));
+		let (sink, writer) = RecordSink::spawn(&path)?;
+
+		// Load tasks record concurrently through their clone of the sink.
+		let mut tasks = Vec::new();
+		for task in 0..4u64 {
+			let sink = sink.clone();
+			tasks.push(tokio::spawn(async move {
+				for i in 0..25u64 {
+					let submitted_at_ms = task * 1000 + i;
+					if i % 5 == 0 {
+						sink.record(TransactionRecord::rejected(
+							Some("probe_1".to_string()),
+							submitted_at_ms,
+							"sequence number too old".to_string(),
+						));
+					} else {
+						sink.record(TransactionRecord {
+							probe: None,
+							hash: Some(format!("0x{task:x}{i:02x}")),
+							submitted_at_ms,
+							confirmed_at_ms: Some(submitted_at_ms + 100),
+							gas_used: Some(6),
+							success: true,
+							error: None,
+						});
+					}
+				}
+			}));
+		}
+		futures::future::try_join_all(tasks).await?;
+		drop(sink);
+		assert_eq!(writer.finish().await?, 100);
+
+		let records = std::fs::read_to_string(&path)?
+			.lines()
+			.map(serde_json::from_str)
+			.collect::<Result<Vec<TransactionRecord>, _>>()?;
+		assert_eq!(records.len(), 100);
+		let rejected: Vec<_> = records.iter().filter(|record| !record.success).collect();
+		assert_eq!(rejected.len(), 20);
+		assert!(rejected.iter().all(|record| record.hash.is_none()
+			&& record.confirmed_at_ms.is_none()
+			&& record.probe.as_deref() == Some("probe_1")
+			&& record.error.as_deref() == Some("sequence number too old")));
+		assert!(records
+			.iter()
+			.filter(|record| record.success)
+			.all(|record| record.confirmed_at_ms == Some(record.submitted_at_ms + 100)
+				&& record.gas_used == Some(6)));
+
+		// The fields are written by name.
+		let first_line = std::fs::read_to_string(&path)?.lines().next().unwrap().to_string();
+		for field in
+			["probe", "hash", "submitted_at_ms", "confirmed_at_ms", "gas_used", "success", "error"]
+		{
+			assert!(first_line.contains(&format!("\"{field}\":")), "{first_line}");
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,187 @@
+use crate::sequence::SequenceTracker;
+use std::future::Future;
+use std::time::Duration;
+
+/// Why a submission was rejected, as far as retrying it goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+	/// The sequence number is behind or too far ahead of the account's.
+	SequenceNumber,
+	/// The mempool has no room for the transaction.
+	MempoolFull,
+	Other,
+}
+
+impl Rejection {
+	/// Classifies a rejection from its error message.
+	pub fn classify(error: &anyhow::Error) -> Self {
+		let message = format!("{:#}", error).to_lowercase();
+		if message.contains("sequence_number_too_old")
+			|| message.contains("sequence_number_too_new")
+			|| message.contains("sequence number")
+		{
+			Rejection::SequenceNumber
+		} else if message.contains("mempool_is_full") || message.contains("mempool is full") {
+			Rejection::MempoolFull
+		} else {
+			Rejection::Other
+		}
+	}
+}
+
+/// How many times a rejected submission is retried, and how long to back off when the mempool
+/// is full. The backoff doubles with each retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+	pub max_retries: u32,
+	pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		RetryPolicy { max_retries: 3, backoff: Duration::from_millis(200) }
+	}
+}
+
+/// Submits a transaction with the sequence number of `sequence`, `submit` signing and
+/// submitting it with the given sequence number.
+/// On a sequence number rejection, the sequence number is read from the chain with
+/// `chain_sequence` before retrying; on a full mempool, the retry is backed off.
+/// Returns the result with the number of retries made.
+pub async fn submit_with_retry<T, S, SFut, C, CFut>(
+	policy: &RetryPolicy,
+	sequence: &mut SequenceTracker,
+	mut chain_sequence: C,
+	mut submit: S,
+) -> (Result<T, anyhow::Error>, u32)
+where
+	S: FnMut(u64) -> SFut,
+	SFut: Future<Output = Result<T, anyhow::Error>>,
+	C: FnMut() -> CFut,
+	CFut: Future<Output = Result<u64, anyhow::Error>>,
+{
+	let mut retries = 0;
+	let mut backoff = policy.backoff;
+	loop {
+		if sequence.needs_refresh() {
+			match chain_sequence().await {
+				Ok(chain_sequence) => {
+					sequence.resync(chain_sequence);
+				}
+				Err(e) => return (Err(e), retries),
+			}
+		}
+
+		let error = match submit(sequence.next()).await {
+			Ok(submitted) => {
+				sequence.on_submitted();
+				return (Ok(submitted), retries);
+			}
+			Err(e) => e,
+		};
+		sequence.on_rejected();
+		let rejection = Rejection::classify(&error);
+		if rejection == Rejection::Other || retries >= policy.max_retries {
+			return (Err(error), retries);
+		}
+
+		retries += 1;
+		tracing::warn!("Submission rejected ({:?}), retry {}: {:?}", rejection, retries, error);
+		if rejection == Rejection::MempoolFull {
+			tokio::time::sleep(backoff).await;
+			backoff *= 2;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sequence::SequenceStrategy;
+	use std::sync::Mutex;
+
+	/// A rest client rejecting the submissions with the queued errors, then accepting them.
+	/// The account is at sequence number `chain_sequence`.
+	struct MockClient {
+		rejections: Mutex<Vec<&'static str>>,
+		chain_sequence: u64,
+		submitted: Mutex<Vec<u64>>,
+		resyncs: Mutex<u32>,
+	}
+
+	impl MockClient {
+		fn new(rejections: Vec<&'static str>, chain_sequence: u64) -> Self {
+			MockClient {
+				rejections: Mutex::new(rejections),
+				chain_sequence,
+				submitted: Mutex::new(Vec::new()),
+				resyncs: Mutex::new(0),
+			}
+		}
+
+		async fn get_sequence_number(&self) -> Result<u64, anyhow::Error> {
+			*self.resyncs.lock().unwrap() += 1;
+			Ok(self.chain_sequence)
+		}
+
+		async fn submit(&self, sequence_number: u64) -> Result<u64, anyhow::Error> {
+			self.submitted.lock().unwrap().push(sequence_number);
+			let mut rejections = self.rejections.lock().unwrap();
+			if !rejections.is_empty() {
+				return Err(anyhow::anyhow!(rejections.remove(0)));
+			}
+			Ok(sequence_number)
+		}
+	}
+
+	#[tokio::test]
+	async fn test_sequence_rejection_is_resynced_and_retried() {
+		let client = MockClient::new(
+			vec!["Invalid transaction: Type: Validation Code: SEQUENCE_NUMBER_TOO_OLD"],
+			5,
+		);
+		let mut sequence = SequenceTracker::new(SequenceStrategy::LocalIncrement, 2);
+		let (result, retries) = submit_with_retry(
+			&RetryPolicy::default(),
+			&mut sequence,
+			|| client.get_sequence_number(),
+			|sequence_number| client.submit(sequence_number),
+		)
+		.await;
+
+		assert_eq!(result.unwrap(), 5);
+		assert_eq!(retries, 1);
+		assert_eq!(*client.resyncs.lock().unwrap(), 1);
+		assert_eq!(*client.submitted.lock().unwrap(), vec![2, 5]);
+		assert_eq!(sequence.next(), 6);
+	}
+
+	#[tokio::test]
+	async fn test_retries_are_bounded() {
+		let policy = RetryPolicy { max_retries: 2, backoff: Duration::from_millis(1) };
+		let client = MockClient::new(vec!["Mempool is full"; 3], 0);
+		let mut sequence = SequenceTracker::new(SequenceStrategy::LocalIncrement, 0);
+		let (result, retries) = submit_with_retry(
+			&policy,
+			&mut sequence,
+			|| client.get_sequence_number(),
+			|sequence_number| client.submit(sequence_number),
+		)
+		.await;
+		assert!(result.is_err());
+		assert_eq!(retries, 2);
+		assert_eq!(client.submitted.lock().unwrap().len(), 3);
+
+		// Other rejections aren't retried.
+		let client = MockClient::new(vec!["INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE"], 0);
+		let (result, retries) = submit_with_retry(
+			&policy,
+			&mut sequence,
+			|| client.get_sequence_number(),
+			|sequence_number| client.submit(sequence_number),
+		)
+		.await;
+		assert!(result.is_err());
+		assert_eq!(retries, 0);
+	}
+}
@@ -0,0 +1,137 @@
+use std::str::FromStr;
+
+/// How the local sequence number of a load account is kept in sync with the chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SequenceStrategy {
+	/// Increment locally after each submission, resyncing only when a gap is detected.
+	#[default]
+	LocalIncrement,
+	/// Increment locally and resync from the chain every `n` submissions.
+	RefreshEvery(u64),
+	/// Read the sequence number from the chain before every submission.
+	AlwaysRefresh,
+}
+
+impl FromStr for SequenceStrategy {
+	type Err = anyhow::Error;
+
+	/// Parses `local`, `refresh:<n>` or `always`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"local" => Ok(SequenceStrategy::LocalIncrement),
+			"always" => Ok(SequenceStrategy::AlwaysRefresh),
+			_ => match s.strip_prefix("refresh:") {
+				Some(n) => match n.parse::<u64>()? {
+					0 => Err(anyhow::anyhow!("refresh period must be greater than 0")),
+					n => Ok(SequenceStrategy::RefreshEvery(n)),
+				},
+				None => Err(anyhow::anyhow!("unknown sequence strategy: {}", s)),
+			},
+		}
+	}
+}
+
+/// Tracks the next sequence number of an account according to a [SequenceStrategy].
+#[derive(Debug, Clone)]
+pub struct SequenceTracker {
+	strategy: SequenceStrategy,
+	next: u64,
+	submitted_since_refresh: u64,
+	stale: bool,
+}
+
+impl SequenceTracker {
+	pub fn new(strategy: SequenceStrategy, initial: u64) -> Self {
+		Self { strategy, next: initial, submitted_since_refresh: 0, stale: false }
+	}
+
+	/// The sequence number to use for the next submission.
+	pub fn next(&self) -> u64 {
+		self.next
+	}
+
+	/// Whether the sequence number must be read from the chain before the next submission.
+	pub fn needs_refresh(&self) -> bool {
+		self.stale
+			|| match self.strategy {
+				SequenceStrategy::LocalIncrement => false,
+				SequenceStrategy::RefreshEvery(n) => self.submitted_since_refresh >= n,
+				SequenceStrategy::AlwaysRefresh => true,
+			}
+	}
+
+	/// Records a successful submission.
+	pub fn on_submitted(&mut self) {
+		self.next += 1;
+		self.submitted_since_refresh += 1;
+	}
+
+	/// Records a rejected submission; the local sequence number can no longer be trusted.
+	pub fn on_rejected(&mut self) {
+		self.stale = true;
+	}
+
+	/// Resets the local sequence number to the one read from the chain.
+	/// Returns true if the local sequence number had drifted.
+	pub fn resync(&mut self, chain_sequence: u64) -> bool {
+		let drifted = chain_sequence != self.next;
+		if drifted {
+			tracing::warn!(
+				"Sequence number gap detected, local: {}, chain: {}",
+				self.next,
+				chain_sequence
+			);
+		}
+		self.next = chain_sequence;
+		self.submitted_since_refresh = 0;
+		self.stale = false;
+		drifted
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_local_increment_is_contiguous_and_resyncs_on_gap() {
+		let mut tracker = SequenceTracker::new(SequenceStrategy::LocalIncrement, 7);
+		let mut used = Vec::new();
+		for _ in 0..4 {
+			assert!(!tracker.needs_refresh());
+			used.push(tracker.next());
+			tracker.on_submitted();
+		}
+		assert_eq!(used, vec![7, 8, 9, 10]);
+
+		// the chain only accepted up to 9, the next submission is rejected
+		tracker.on_rejected();
+		assert!(tracker.needs_refresh());
+		assert!(tracker.resync(10));
+		assert!(!tracker.needs_refresh());
+		assert_eq!(tracker.next(), 10);
+	}
+
+	#[test]
+	fn test_refresh_every_n() {
+		let mut tracker = SequenceTracker::new(SequenceStrategy::RefreshEvery(2), 0);
+		tracker.on_submitted();
+		assert!(!tracker.needs_refresh());
+		tracker.on_submitted();
+		assert!(tracker.needs_refresh());
+		assert!(!tracker.resync(2));
+		assert!(!tracker.needs_refresh());
+	}
+
+	#[test]
+	fn test_parse_strategy() {
+		assert_eq!("local".parse::<SequenceStrategy>().unwrap(), SequenceStrategy::LocalIncrement);
+		assert_eq!("always".parse::<SequenceStrategy>().unwrap(), SequenceStrategy::AlwaysRefresh);
+		assert_eq!(
+			"refresh:16".parse::<SequenceStrategy>().unwrap(),
+			SequenceStrategy::RefreshEvery(16)
+		);
+		assert!("refresh:0".parse::<SequenceStrategy>().is_err());
+		assert!("sometimes".parse::<SequenceStrategy>().is_err());
+	}
+}
@@ -1,4 +1,6 @@
 pub mod local;
+pub mod readiness;
+pub mod shutdown;
 
 pub trait SuzukaFullNodeSetupOperations {
 	async fn setup(
@@ -9,4 +11,12 @@ pub trait SuzukaFullNodeSetupOperations {
 		(suzuka_config::Config, tokio::task::JoinHandle<Result<String, anyhow::Error>>),
 		anyhow::Error,
 	>;
+
+	/// Computes the config `setup` would start the services with, without launching them or
+	/// writing anything. The values only known once the services are set up are left as is.
+	async fn plan(
+		&self,
+		dot_movement: dot_movement::DotMovement,
+		config: suzuka_config::Config,
+	) -> Result<suzuka_config::Config, anyhow::Error>;
 }
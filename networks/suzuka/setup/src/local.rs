@@ -95,4 +95,49 @@ impl SuzukaFullNodeSetupOperations for Local {
 		// Placeholder for returning the actual configuration.
 		Ok((config, join_handle))
 	}
+
+	async fn plan(
+		&self,
+		dot_movement: DotMovement,
+		config: suzuka_config::Config,
+	) -> Result<suzuka_config::Config, anyhow::Error> {
+		// the m1_da_light_node and mcr_settlement setups launch services, they are skipped
+		tracing::info!("Dry run, skipping m1_da_light_node_setup and mcr_settlement_setup");
+
+		let config = self.setup_maptos_execution_config(dot_movement.clone(), config).await?;
+		self.setup_da_db_config(dot_movement, config).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_plan_leaves_dot_movement_unchanged() -> Result<(), anyhow::Error> {
+		let dir = tempfile::tempdir()?;
+		let dot_movement = DotMovement::new(dir.path().to_str().unwrap());
+		let config = suzuka_config::Config::default();
+		dot_movement.try_write_config_to_json(&config)?;
+		let written = std::fs::read(dot_movement.get_config_json_path())?;
+
+		let planned = Local::default().plan(dot_movement.clone(), config).await?;
+
+		// The db paths are moved under .movement.
+		assert!(planned.da_db.da_db_path.starts_with(dir.path().to_str().unwrap()));
+		assert!(planned
+			.execution_config
+			.maptos_config
+			.chain
+			.maptos_db_path
+			.unwrap()
+			.starts_with(dir.path()));
+		// Nothing was launched to write keys or state, and the config file is untouched.
+		let entries: Vec<_> = std::fs::read_dir(dir.path())?
+			.map(|entry| entry.map(|entry| entry.file_name()))
+			.collect::<Result<_, _>>()?;
+		assert_eq!(entries, vec!["config.json"]);
+		assert_eq!(std::fs::read(dot_movement.get_config_json_path())?, written);
+		Ok(())
+	}
 }
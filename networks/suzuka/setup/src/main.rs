@@ -4,9 +4,12 @@ use movement_types::application;
 use std::future::Future;
 use std::pin::Pin;
 use suzuka_config::Config;
-use suzuka_full_node_setup::{local::Local, SuzukaFullNodeSetupOperations};
-use tokio::signal::unix::signal;
-use tokio::signal::unix::SignalKind;
+use suzuka_full_node_setup::{
+	local::Local,
+	readiness::{Readiness, ReadinessEndpoint},
+	shutdown::{drain, drain_timeout_from_env, forward_shutdown_signals},
+	SuzukaFullNodeSetupOperations,
+};
 use tokio::sync::watch;
 use tracing::info;
 
@@ -20,30 +23,32 @@ async fn main() -> Result<(), anyhow::Error> {
 		)
 		.init();
 
-	let (stop_tx, mut stop_rx) = watch::channel(());
-	tokio::spawn({
-		let mut sigterm = signal(SignalKind::terminate()).context("can't register to SIGTERM.")?;
-		let mut sigint = signal(SignalKind::interrupt()).context("can't register to SIGKILL.")?;
-		let mut sigquit = signal(SignalKind::quit()).context("can't register to SIGKILL.")?;
-		async move {
-			loop {
-				tokio::select! {
-					_ = sigterm.recv() => (),
-					_ = sigint.recv() => (),
-					_ = sigquit.recv() => (),
-				};
-				tracing::info!("Received terminate Signal");
-				if let Err(err) = stop_tx.send(()) {
-					tracing::warn!("Can't update stop watch channel because :{err}");
-					return Err::<(), anyhow::Error>(anyhow::anyhow!(err));
-				}
-			}
-		}
-	});
-
 	// get the config file
 	let dot_movement = dot_movement::DotMovement::try_from_env()?;
 
+	// only print the config the setup would start from
+	if std::env::args().any(|arg| arg == "--dry-run") {
+		let config = if dot_movement.get_config_json_path().exists() {
+			dot_movement.try_get_config_from_json::<Config>()?
+		} else {
+			Config::default()
+		};
+		config.validate()?;
+		let config = Local::default().plan(dot_movement, config).await?;
+		println!("{}", serde_json::to_string_pretty(&config)?);
+		return Ok(());
+	}
+
+	let (stop_tx, mut stop_rx) = watch::channel(());
+	forward_shutdown_signals(stop_tx)?;
+	let drain_timeout = drain_timeout_from_env()?;
+
+	// not ready until the initial setup completed
+	let mut readiness = match ReadinessEndpoint::try_from_env()? {
+		Some(endpoint) => Some(Readiness::new(endpoint).await?),
+		None => None,
+	};
+
 	let config_file = dot_movement.try_get_or_create_config_file().await?;
 
 	// get a matching godfig object
@@ -55,6 +60,7 @@ async fn main() -> Result<(), anyhow::Error> {
 			tracing::info!("Config: {:?}", config);
 			let config = config.unwrap_or_default();
 			tracing::info!("Config: {:?}", config);
+			config.validate()?;
 
 			// set up sync
 			let sync_task: Pin<Box<dyn Future<Output = Result<(), anyhow::Error>> + Send>> =
@@ -98,19 +104,37 @@ async fn main() -> Result<(), anyhow::Error> {
 		.await?;
 
 	info!("Initial setup complete, orchestrating services.");
+	if let Some(readiness) = readiness.as_mut() {
+		readiness.set_ready().await?;
+	}
 
-	// Use tokio::select! to wait for either the handle or a cancellation signal
+	// the node task runs the services until they finish or it's stopped
+	let (node_stop_tx, mut node_stop_rx) = watch::channel(());
+	let mut node_task = tokio::spawn(async move {
+		tokio::select! {
+			res = anvil_join_handle => {
+				tracing::info!("Anvil task finished.");
+				res??;
+			}
+			_ = node_stop_rx.changed() => {
+				tracing::info!("Node task stopped.");
+			}
+			// sync task
+			_ = sync_task => {
+				tracing::info!("Sync task finished.");
+			}
+		}
+		Ok::<_, anyhow::Error>(())
+	});
+
+	// Use tokio::select! to wait for either the node task or a cancellation signal
 	tokio::select! {
-		res = anvil_join_handle => {
-			tracing::info!("Anvil task finished.");
+		res = &mut node_task => {
 			res??;
 		}
 		_ = stop_rx.changed() => {
-			tracing::info!("Cancellation received, killing anvil task.");
-		}
-		// sync task
-		_ = sync_task => {
-			tracing::info!("Sync task finished.");
+			tracing::info!("Cancellation received, draining the node task.");
+			drain(&node_stop_tx, node_task, drain_timeout).await?;
 		}
 	}
 
@@ -0,0 +1,154 @@
+use anyhow::Context;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Where the setup signals that the node is ready: a file that exists once it is, or a TCP
+/// port that accepts connections once it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadinessEndpoint {
+	File(PathBuf),
+	Tcp(SocketAddr),
+}
+
+impl ReadinessEndpoint {
+	/// Reads the endpoint from `SUZUKA_SETUP_READINESS`, readiness isn't signaled if it's unset.
+	pub fn try_from_env() -> Result<Option<Self>, anyhow::Error> {
+		match std::env::var("SUZUKA_SETUP_READINESS") {
+			Ok(endpoint) => Ok(Some(endpoint.parse()?)),
+			Err(_) => Ok(None),
+		}
+	}
+}
+
+impl FromStr for ReadinessEndpoint {
+	type Err = anyhow::Error;
+
+	/// Parses `file:<path>` or `tcp:<address>`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if let Some(path) = s.strip_prefix("file:") {
+			return Ok(ReadinessEndpoint::File(PathBuf::from(path)));
+		}
+		if let Some(address) = s.strip_prefix("tcp:") {
+			let address = address
+				.parse()
+				.with_context(|| format!("invalid readiness address {}", address))?;
+			return Ok(ReadinessEndpoint::Tcp(address));
+		}
+		Err(anyhow::anyhow!("readiness endpoint must be file:<path> or tcp:<address>, got {}", s))
+	}
+}
+
+/// Readiness of the node, signaled on its endpoint once set.
+pub struct Readiness {
+	endpoint: ReadinessEndpoint,
+	listener_task: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl Readiness {
+	/// Starts not ready, a readiness file left by a previous run is removed.
+	pub async fn new(endpoint: ReadinessEndpoint) -> Result<Self, anyhow::Error> {
+		if let ReadinessEndpoint::File(path) = &endpoint {
+			match tokio::fs::remove_file(path).await {
+				Ok(()) => {}
+				Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+				Err(e) => {
+					return Err(e)
+						.with_context(|| format!("can't remove readiness file {}", path.display()))
+				}
+			}
+		}
+		Ok(Self { endpoint, listener_task: None })
+	}
+
+	pub fn is_ready(&self) -> bool {
+		match &self.endpoint {
+			ReadinessEndpoint::File(path) => path.exists(),
+			ReadinessEndpoint::Tcp(_) => self.listener_task.is_some(),
+		}
+	}
+
+	/// Signals that the node is ready.
+	pub async fn set_ready(&mut self) -> Result<(), anyhow::Error> {
+		if self.is_ready() {
+			return Ok(());
+		}
+		match &self.endpoint {
+			ReadinessEndpoint::File(path) => {
+				tokio::fs::write(path, b"ready")
+					.await
+					.with_context(|| format!("can't write readiness file {}", path.display()))?;
+			}
+			ReadinessEndpoint::Tcp(address) => {
+				let listener = tokio::net::TcpListener::bind(address)
+					.await
+					.with_context(|| format!("can't listen for readiness on {}", address))?;
+				// the probe only checks that the connection is accepted
+				self.listener_task =
+					Some(tokio::spawn(async move { while listener.accept().await.is_ok() {} }));
+			}
+		}
+		tracing::info!("Node ready, signaled on {:?}", self.endpoint);
+		Ok(())
+	}
+}
+
+impl Drop for Readiness {
+	fn drop(&mut self) {
+		if let Some(listener_task) = self.listener_task.take() {
+			listener_task.abort();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::atomic::{AtomicBool, Ordering};
+	use std::time::Duration;
+
+	#[tokio::test]
+	async fn test_ready_only_after_setup() -> Result<(), anyhow::Error> {
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().join("ready");
+		// a file left by a previous run
+		std::fs::write(&path, b"ready")?;
+		let mut readiness = Readiness::new(ReadinessEndpoint::File(path.clone())).await?;
+
+		let setup_done = AtomicBool::new(false);
+		let setup = async {
+			tokio::time::sleep(Duration::from_millis(100)).await;
+			setup_done.store(true, Ordering::SeqCst);
+			readiness.set_ready().await
+		};
+		let poll = async {
+			let mut polls = 0;
+			while !path.exists() {
+				polls += 1;
+				tokio::time::sleep(Duration::from_millis(10)).await;
+			}
+			// the file only appears once the setup completed
+			assert!(setup_done.load(Ordering::SeqCst));
+			polls
+		};
+		let (ready, polls) = tokio::join!(setup, poll);
+		ready?;
+
+		assert!(polls > 0);
+		assert!(readiness.is_ready());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_tcp_readiness_accepts_once_ready() -> Result<(), anyhow::Error> {
+		// bind then drop a listener to get a free port
+		let address = tokio::net::TcpListener::bind("127.0.0.1:0").await?.local_addr()?;
+		let mut readiness = Readiness::new(format!("tcp:{}", address).parse()?).await?;
+		assert!(tokio::net::TcpStream::connect(address).await.is_err());
+
+		readiness.set_ready().await?;
+		assert!(readiness.is_ready());
+		tokio::net::TcpStream::connect(address).await?;
+		Ok(())
+	}
+}
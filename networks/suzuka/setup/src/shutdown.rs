@@ -0,0 +1,171 @@
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// How long a stopped task is given to finish by default, before being aborted.
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Termination requests: SIGTERM, SIGINT and SIGQUIT on unix, Ctrl-C on the other targets.
+pub struct ShutdownSignal {
+	#[cfg(unix)]
+	sigterm: tokio::signal::unix::Signal,
+	#[cfg(unix)]
+	sigint: tokio::signal::unix::Signal,
+	#[cfg(unix)]
+	sigquit: tokio::signal::unix::Signal,
+}
+
+impl ShutdownSignal {
+	#[cfg(unix)]
+	pub fn new() -> Result<Self, anyhow::Error> {
+		use anyhow::Context;
+		use tokio::signal::unix::{signal, SignalKind};
+
+		Ok(Self {
+			sigterm: signal(SignalKind::terminate()).context("can't register to SIGTERM.")?,
+			sigint: signal(SignalKind::interrupt()).context("can't register to SIGINT.")?,
+			sigquit: signal(SignalKind::quit()).context("can't register to SIGQUIT.")?,
+		})
+	}
+
+	#[cfg(not(unix))]
+	pub fn new() -> Result<Self, anyhow::Error> {
+		Ok(Self {})
+	}
+
+	/// Waits for the next termination request.
+	#[cfg(unix)]
+	pub async fn recv(&mut self) -> Result<(), anyhow::Error> {
+		tokio::select! {
+			_ = self.sigterm.recv() => (),
+			_ = self.sigint.recv() => (),
+			_ = self.sigquit.recv() => (),
+		};
+		Ok(())
+	}
+
+	/// Waits for the next termination request.
+	#[cfg(not(unix))]
+	pub async fn recv(&mut self) -> Result<(), anyhow::Error> {
+		tokio::signal::ctrl_c().await?;
+		Ok(())
+	}
+}
+
+/// Updates `stop_tx` on every termination request, until the channel is closed.
+pub fn forward_shutdown_signals(
+	stop_tx: watch::Sender<()>,
+) -> Result<tokio::task::JoinHandle<Result<(), anyhow::Error>>, anyhow::Error> {
+	let mut shutdown_signal = ShutdownSignal::new()?;
+	Ok(tokio::spawn(async move {
+		loop {
+			shutdown_signal.recv().await?;
+			tracing::info!("Received terminate Signal");
+			if let Err(err) = stop_tx.send(()) {
+				tracing::warn!("Can't update stop watch channel because :{err}");
+				return Err(anyhow::anyhow!(err));
+			}
+		}
+	}))
+}
+
+/// Reads the drain timeout from `SUZUKA_SETUP_DRAIN_TIMEOUT_SECS`, or the default if it's unset.
+pub fn drain_timeout_from_env() -> Result<Duration, anyhow::Error> {
+	match std::env::var("SUZUKA_SETUP_DRAIN_TIMEOUT_SECS") {
+		Ok(secs) => Ok(Duration::from_secs(secs.parse().map_err(|e| {
+			anyhow::anyhow!("invalid SUZUKA_SETUP_DRAIN_TIMEOUT_SECS {}: {}", secs, e)
+		})?)),
+		Err(_) => Ok(DEFAULT_DRAIN_TIMEOUT),
+	}
+}
+
+/// Sends a stop to the task with `stop_tx`, then waits up to `timeout` for it to finish.
+/// The task is only aborted if it overruns, in which case None is returned.
+pub async fn drain<T>(
+	stop_tx: &watch::Sender<()>,
+	mut task: tokio::task::JoinHandle<Result<T, anyhow::Error>>,
+	timeout: Duration,
+) -> Result<Option<T>, anyhow::Error> {
+	// the task may have finished already and dropped its receiver
+	let _ = stop_tx.send(());
+	match tokio::time::timeout(timeout, &mut task).await {
+		Ok(res) => Ok(Some(res??)),
+		Err(_) => {
+			tracing::warn!("Task didn't finish within {:?} of the stop, aborting it.", timeout);
+			task.abort();
+			Ok(None)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn test_signal_updates_the_stop_channel() -> Result<(), anyhow::Error> {
+		let (stop_tx, mut stop_rx) = watch::channel(());
+		forward_shutdown_signals(stop_tx)?;
+
+		// The handlers are registered, the signal doesn't terminate the test process.
+		let status = std::process::Command::new("kill")
+			.args(["-QUIT", &std::process::id().to_string()])
+			.status()?;
+		assert!(status.success());
+		tokio::time::timeout(Duration::from_secs(5), stop_rx.changed()).await??;
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_shutdown_during_setup_drains_the_node() -> Result<(), anyhow::Error> {
+		let (stop_tx, mut stop_rx) = watch::channel(());
+		let (node_stop_tx, mut node_stop_rx) = watch::channel(());
+
+		// the shutdown is requested while the setup is running
+		let setup = async {
+			stop_tx.send(())?;
+			tokio::time::sleep(Duration::from_millis(50)).await;
+			Ok::<_, anyhow::Error>(())
+		};
+		setup.await?;
+
+		let node_task = tokio::spawn(async move {
+			node_stop_rx.changed().await?;
+			// finish the pending writes
+			tokio::time::sleep(Duration::from_millis(50)).await;
+			Ok::<_, anyhow::Error>("stopped")
+		});
+		stop_rx.changed().await?;
+		let drained = drain(&node_stop_tx, node_task, Duration::from_secs(5)).await?;
+		assert_eq!(drained, Some("stopped"));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_overrunning_task_is_aborted() -> Result<(), anyhow::Error> {
+		let (node_stop_tx, _node_stop_rx) = watch::channel(());
+		let node_task =
+			tokio::spawn(async { futures::future::pending::<Result<(), anyhow::Error>>().await });
+		let drained = tokio::time::timeout(
+			Duration::from_secs(5),
+			drain(&node_stop_tx, node_task, Duration::from_millis(50)),
+		)
+		.await??;
+		assert_eq!(drained, None);
+		Ok(())
+	}
+
+	#[cfg(not(unix))]
+	#[tokio::test]
+	async fn test_ctrl_c_is_awaited() -> Result<(), anyhow::Error> {
+		let (stop_tx, mut stop_rx) = watch::channel(());
+		let handle = forward_shutdown_signals(stop_tx)?;
+
+		// Without Ctrl-C, the stop channel isn't updated.
+		assert!(tokio::time::timeout(Duration::from_millis(100), stop_rx.changed())
+			.await
+			.is_err());
+		assert!(!handle.is_finished());
+		Ok(())
+	}
+}
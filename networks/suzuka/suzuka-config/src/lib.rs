@@ -43,3 +43,130 @@ impl Default for Config {
 		}
 	}
 }
+
+impl Config {
+	/// Checks the connection urls, the ports and the addresses before the node is set up with
+	/// the config. The error lists every problem found.
+	pub fn validate(&self) -> Result<(), anyhow::Error> {
+		let mut problems = Vec::new();
+
+		let client = &self.execution_config.maptos_config.client;
+		check_url(
+			&mut problems,
+			"movement rpc",
+			&format!(
+				"http://{}:{}",
+				client.maptos_rest_connection_hostname, client.maptos_rest_connection_port
+			),
+			client.maptos_rest_connection_port,
+		);
+		check_url(
+			&mut problems,
+			"movement faucet",
+			&format!(
+				"http://{}:{}",
+				client.maptos_faucet_rest_connection_hostname,
+				client.maptos_faucet_rest_connection_port
+			),
+			client.maptos_faucet_rest_connection_port,
+		);
+		let chain = &self.execution_config.maptos_config.chain;
+		check_url(
+			&mut problems,
+			"movement rpc listen address",
+			&format!(
+				"http://{}:{}",
+				chain.maptos_rest_listen_hostname, chain.maptos_rest_listen_port
+			),
+			chain.maptos_rest_listen_port,
+		);
+
+		let da = &self.m1_da_light_node.m1_da_light_node_config;
+		check_url(
+			&mut problems,
+			"m1 da light node",
+			&format!(
+				"http://{}:{}",
+				da.m1_da_light_node_connection_hostname(),
+				da.m1_da_light_node_connection_port()
+			),
+			da.m1_da_light_node_connection_port(),
+		);
+		check_url(
+			&mut problems,
+			"m1 da light node listen address",
+			&format!("http://{}", da.m1_da_light_node_service()),
+			da.m1_da_light_node_listen_port(),
+		);
+
+		let eth = &self.mcr.eth_connection;
+		check_url(
+			&mut problems,
+			"eth rpc",
+			&self.mcr.eth_rpc_connection_url(),
+			eth.eth_rpc_connection_port,
+		);
+		check_url(
+			&mut problems,
+			"eth ws",
+			&self.mcr.eth_ws_connection_url(),
+			eth.eth_ws_connection_port,
+		);
+		let address = &self.mcr.settle.mcr_contract_address;
+		if !is_eth_address(address) {
+			problems.push(format!(
+				"mcr contract address {:?} isn't a 0x prefixed hex address",
+				address
+			));
+		}
+
+		if problems.is_empty() {
+			return Ok(());
+		}
+		Err(anyhow::anyhow!("invalid config:\n - {}", problems.join("\n - ")))
+	}
+}
+
+// Records a problem if `url` doesn't parse or has no host, or if `port` is 0.
+fn check_url(problems: &mut Vec<String>, name: &str, url: &str, port: u16) {
+	match url::Url::parse(url) {
+		Ok(parsed) => {
+			if parsed.host_str().unwrap_or_default().is_empty() {
+				problems.push(format!("{} url {:?} has no host", name, url));
+			}
+		}
+		Err(e) => problems.push(format!("{} url {:?} is invalid: {}", name, url, e)),
+	}
+	if port == 0 {
+		problems.push(format!("{} port must not be 0", name));
+	}
+}
+
+// Up to 20 bytes of hex after 0x, the address may be left at 0x0 until the contract is deployed.
+fn is_eth_address(address: &str) -> bool {
+	address.strip_prefix("0x").is_some_and(|hex| {
+		!hex.is_empty() && hex.len() <= 40 && hex.chars().all(|c| c.is_ascii_hexdigit())
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_validate_lists_every_problem() {
+		assert!(Config::default().validate().is_ok());
+
+		let mut config = Config::default();
+		config.execution_config.maptos_config.client.maptos_rest_connection_hostname =
+			String::new();
+		config.mcr.eth_connection.eth_rpc_connection_port = 0;
+		config.mcr.settle.mcr_contract_address = "0xnot-an-address".to_string();
+
+		let error = config.validate().unwrap_err().to_string();
+		assert!(error.contains("movement rpc url"), "{}", error);
+		assert!(error.contains("eth rpc port must not be 0"), "{}", error);
+		assert!(error.contains("mcr contract address"), "{}", error);
+		assert!(!error.contains("movement faucet"), "{}", error);
+	}
+}
@@ -0,0 +1,45 @@
+use godfig::env_default;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BalanceConfig {
+	/// Interval in seconds between two checks of the signer balances.
+	#[serde(default = "default_balance_check_interval_secs")]
+	pub balance_check_interval_secs: u64,
+	/// Eth signer balance, in wei, below which an alert is raised. 0 disables the alert.
+	#[serde(default = "default_eth_low_balance_warning")]
+	pub eth_low_balance_warning: u128,
+	/// Eth signer balance, in wei, below which the new completions are paused until
+	/// it's topped up. 0 disables the degraded mode.
+	#[serde(default = "default_eth_degraded_balance")]
+	pub eth_degraded_balance: u128,
+	/// Movement signer balance, in octas, below which an alert is raised. 0 disables the alert.
+	#[serde(default = "default_movement_low_balance_warning")]
+	pub movement_low_balance_warning: u128,
+	/// Movement signer balance, in octas, below which the new completions are paused until
+	/// it's topped up. 0 disables the degraded mode.
+	#[serde(default = "default_movement_degraded_balance")]
+	pub movement_degraded_balance: u128,
+}
+
+env_default!(default_balance_check_interval_secs, "BRIDGE_BALANCE_CHECK_INTERVAL_SECS", u64, 60);
+
+env_default!(default_eth_low_balance_warning, "BRIDGE_ETH_LOW_BALANCE_WARNING", u128, 0);
+
+env_default!(default_eth_degraded_balance, "BRIDGE_ETH_DEGRADED_BALANCE", u128, 0);
+
+env_default!(default_movement_low_balance_warning, "BRIDGE_MOVEMENT_LOW_BALANCE_WARNING", u128, 0);
+
+env_default!(default_movement_degraded_balance, "BRIDGE_MOVEMENT_DEGRADED_BALANCE", u128, 0);
+
+impl Default for BalanceConfig {
+	fn default() -> Self {
+		BalanceConfig {
+			balance_check_interval_secs: default_balance_check_interval_secs(),
+			eth_low_balance_warning: default_eth_low_balance_warning(),
+			eth_degraded_balance: default_eth_degraded_balance(),
+			movement_low_balance_warning: default_movement_low_balance_warning(),
+			movement_degraded_balance: default_movement_degraded_balance(),
+		}
+	}
+}
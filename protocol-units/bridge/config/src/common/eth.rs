@@ -2,6 +2,7 @@ use alloy::signers::local::PrivateKeySigner;
 use godfig::env_default;
 use godfig::env_short_default;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::env;
 
 const DEFAULT_ETH_RPC_CONNECTION_HOSTNAME: &str = "localhost";
@@ -11,6 +12,18 @@ const DEFAULT_ETH_WS_CONNECTION_PORT: u16 = 8545; // same as RPC
 const DEFAULT_ETH_INITIATOR_CONTRACT: &str = "Oxeee";
 const DEFAULT_ETH_COUNTERPARTY_CONTRACT: &str = "0xccc";
 const DEFAULT_ETH_WETH_CONTRACT: &str = "0xe3e3";
+const DEFAULT_ETH_EVENT_SOURCE: &str = "polling";
+const DEFAULT_ETH_ASSET: &str = "eth_and_weth";
+const DEFAULT_ETH_CHECKPOINT_EVERY_BLOCKS: u64 = 100;
+const DEFAULT_ETH_CHECKPOINT_EVERY_SECS: u64 = 60;
+const DEFAULT_ETH_WS_RECONNECT_MAX_RETRIES: u32 = 5;
+const DEFAULT_ETH_WS_RECONNECT_BASE_DELAY_MS: u64 = 500;
+const DEFAULT_ETH_CONFIRMATION_SECS: u64 = 0;
+const DEFAULT_ETH_CONFIRMATION_BLOCKS: u64 = 0;
+const DEFAULT_ETH_HASH_ALGORITHM: &str = "keccak256";
+const DEFAULT_ETH_GAS_PRICE_STRATEGY: &str = "oracle";
+const DEFAULT_ETH_GAS_PRICE_BUMP_PERCENT: u64 = 10;
+const DEFAULT_ETH_GAS_PRICE_MAX_BUMPS: u32 = 3;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EthConfig {
@@ -28,6 +41,12 @@ pub struct EthConfig {
 	pub eth_ws_connection_hostname: String,
 	#[serde(default = "default_eth_ws_connection_port")]
 	pub eth_ws_connection_port: u16,
+	/// Headers added to every RPC request, for endpoints behind an authenticating proxy.
+	#[serde(default)]
+	pub eth_rpc_headers: BTreeMap<String, String>,
+	/// Bearer token of the RPC and WebSocket requests, empty for none.
+	#[serde(default = "default_eth_rpc_bearer_token")]
+	pub eth_rpc_bearer_token: String,
 	#[serde(default)]
 	// Eth chain config.
 	pub eth_chain_id: u64,
@@ -45,6 +64,97 @@ pub struct EthConfig {
 	pub gas_limit: u64,
 	#[serde(default = "default_transaction_send_retries")]
 	pub transaction_send_retries: u32,
+
+	/// How the fees of the transactions are set: `oracle` (estimated by the node), `fixed`
+	/// (legacy transactions at `eth_gas_price_wei`) or `eip1559` (`eth_max_fee_per_gas_wei`
+	/// and `eth_max_priority_fee_per_gas_wei`).
+	#[serde(default = "default_eth_gas_price_strategy")]
+	pub eth_gas_price_strategy: String,
+	#[serde(default = "default_eth_gas_price_wei")]
+	pub eth_gas_price_wei: u64,
+	#[serde(default = "default_eth_max_fee_per_gas_wei")]
+	pub eth_max_fee_per_gas_wei: u64,
+	#[serde(default = "default_eth_max_priority_fee_per_gas_wei")]
+	pub eth_max_priority_fee_per_gas_wei: u64,
+	/// Fee increase, in percent, of a transaction resent after it was rejected as underpriced.
+	#[serde(default = "default_eth_gas_price_bump_percent")]
+	pub eth_gas_price_bump_percent: u64,
+	/// Resends of an underpriced transaction before giving up.
+	#[serde(default = "default_eth_gas_price_max_bumps")]
+	pub eth_gas_price_max_bumps: u32,
+
+	/// Confirmation depth required before acting on an event, by transfer amount.
+	/// An empty list means events are processed as soon as they are observed.
+	#[serde(default)]
+	pub eth_confirmation_tiers: Vec<ConfirmationTier>,
+	/// Confirmation depth of an asset whatever the amount, takes precedence over the tiers.
+	#[serde(default)]
+	pub eth_asset_confirmations: Vec<AssetConfirmation>,
+	/// Seconds of chain time required since the block of an event, measured with the timestamp
+	/// of the latest block. Replaces the block count confirmations if not 0.
+	#[serde(default = "default_eth_confirmation_secs")]
+	pub eth_confirmation_secs: u64,
+	/// Confirmation depth required for every event, on top of the tiers and asset depths.
+	#[serde(default = "default_eth_confirmation_blocks")]
+	pub eth_confirmation_blocks: u64,
+
+	/// Hash function of the contracts hash locks, only `keccak256` is supported by the contracts.
+	#[serde(default = "default_eth_hash_algorithm")]
+	pub eth_hash_algorithm: String,
+
+	/// ERC-20 tokens taking a fee on transfer, with how their transfers are initiated.
+	#[serde(default)]
+	pub eth_fee_on_transfer_tokens: Vec<FeeOnTransferToken>,
+
+	/// How the monitoring observes the contract logs: `polling` or `subscription` (WebSocket).
+	#[serde(default = "default_eth_event_source")]
+	pub eth_event_source: String,
+	/// Reconnect attempts of a dropped WebSocket before falling back to polling.
+	#[serde(default = "default_eth_ws_reconnect_max_retries")]
+	pub eth_ws_reconnect_max_retries: u32,
+	/// Delay before the first reconnect attempt, doubled at each attempt.
+	#[serde(default = "default_eth_ws_reconnect_base_delay_ms")]
+	pub eth_ws_reconnect_base_delay_ms: u64,
+
+	/// Asset the bridge contracts transfer, used to tag their events: `eth_and_weth`, `moveth`
+	/// or `erc20:<token address>`.
+	#[serde(default = "default_eth_asset")]
+	pub eth_asset: String,
+
+	/// File of the last confirmation-safe block whose events were processed, the monitoring
+	/// restarts after it.
+	/// Empty to start from the latest block.
+	#[serde(default = "default_eth_checkpoint_path")]
+	pub eth_checkpoint_path: String,
+	/// The checkpoint is written once it moved this many blocks forward, 0 to ignore.
+	#[serde(default = "default_eth_checkpoint_every_blocks")]
+	pub eth_checkpoint_every_blocks: u64,
+	/// The checkpoint is written this many seconds after the last write, 0 to ignore.
+	#[serde(default = "default_eth_checkpoint_every_secs")]
+	pub eth_checkpoint_every_secs: u64,
+}
+
+/// Minimal number of confirmations required for transfers of at least `min_amount`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfirmationTier {
+	pub min_amount: u64,
+	pub confirmations: u64,
+}
+
+/// Number of confirmations required for the events of `asset`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetConfirmation {
+	pub asset: String,
+	pub confirmations: u64,
+}
+
+/// ERC-20 token taking a fee on transfer: the bridge contract receives less than the amount sent.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeOnTransferToken {
+	pub token: String,
+	/// `reject` the transfers, the ones initiated anyway aren't locked on the counterparty, or
+	/// `adjust` the locked amount to the amount the bridge contract received.
+	pub policy: String,
 }
 
 env_default!(
@@ -68,10 +178,97 @@ env_default!(
 	DEFAULT_ETH_WETH_CONTRACT.to_string()
 );
 
+env_default!(
+	default_eth_event_source,
+	"ETH_EVENT_SOURCE",
+	String,
+	DEFAULT_ETH_EVENT_SOURCE.to_string()
+);
+
+env_default!(
+	default_eth_ws_reconnect_max_retries,
+	"ETH_WS_RECONNECT_MAX_RETRIES",
+	u32,
+	DEFAULT_ETH_WS_RECONNECT_MAX_RETRIES
+);
+
+env_default!(
+	default_eth_ws_reconnect_base_delay_ms,
+	"ETH_WS_RECONNECT_BASE_DELAY_MS",
+	u64,
+	DEFAULT_ETH_WS_RECONNECT_BASE_DELAY_MS
+);
+
+env_default!(default_eth_asset, "ETH_ASSET", String, DEFAULT_ETH_ASSET.to_string());
+
+env_default!(
+	default_eth_confirmation_secs,
+	"ETH_CONFIRMATION_SECS",
+	u64,
+	DEFAULT_ETH_CONFIRMATION_SECS
+);
+
+env_default!(
+	default_eth_confirmation_blocks,
+	"ETH_CONFIRMATION_BLOCKS",
+	u64,
+	DEFAULT_ETH_CONFIRMATION_BLOCKS
+);
+
+env_default!(
+	default_eth_hash_algorithm,
+	"ETH_HASH_ALGORITHM",
+	String,
+	DEFAULT_ETH_HASH_ALGORITHM.to_string()
+);
+
+env_default!(default_eth_checkpoint_path, "ETH_CHECKPOINT_PATH", String, String::new());
+
+env_default!(
+	default_eth_checkpoint_every_blocks,
+	"ETH_CHECKPOINT_EVERY_BLOCKS",
+	u64,
+	DEFAULT_ETH_CHECKPOINT_EVERY_BLOCKS
+);
+
+env_default!(
+	default_eth_checkpoint_every_secs,
+	"ETH_CHECKPOINT_EVERY_SECS",
+	u64,
+	DEFAULT_ETH_CHECKPOINT_EVERY_SECS
+);
+
 env_short_default!(default_gas_limit, u64, 10_000_000_000_000_000 as u64);
 
 env_short_default!(default_transaction_send_retries, u32, 10 as u32);
 
+env_default!(
+	default_eth_gas_price_strategy,
+	"ETH_GAS_PRICE_STRATEGY",
+	String,
+	DEFAULT_ETH_GAS_PRICE_STRATEGY.to_string()
+);
+
+env_default!(default_eth_gas_price_wei, "ETH_GAS_PRICE_WEI", u64, 0);
+
+env_default!(default_eth_max_fee_per_gas_wei, "ETH_MAX_FEE_PER_GAS_WEI", u64, 0);
+
+env_default!(default_eth_max_priority_fee_per_gas_wei, "ETH_MAX_PRIORITY_FEE_PER_GAS_WEI", u64, 0);
+
+env_default!(
+	default_eth_gas_price_bump_percent,
+	"ETH_GAS_PRICE_BUMP_PERCENT",
+	u64,
+	DEFAULT_ETH_GAS_PRICE_BUMP_PERCENT
+);
+
+env_default!(
+	default_eth_gas_price_max_bumps,
+	"ETH_GAS_PRICE_MAX_BUMPS",
+	u32,
+	DEFAULT_ETH_GAS_PRICE_MAX_BUMPS
+);
+
 env_default!(
 	default_eth_rpc_connection_protocol,
 	"ETH_RPC_CONNECTION_PROTOCOL",
@@ -114,6 +311,8 @@ env_default!(
 	DEFAULT_ETH_WS_CONNECTION_PORT
 );
 
+env_default!(default_eth_rpc_bearer_token, "ETH_RPC_BEARER_TOKEN", String, String::new());
+
 env_default!(default_eth_chain_id, "ETH_CHAIN_ID", u64, 0);
 
 pub fn default_signer_private_key() -> String {
@@ -154,6 +353,16 @@ impl EthConfig {
 			self.eth_ws_connection_port
 		)
 	}
+
+	/// Copy of the config without the RPC credentials and the signer key, to be logged.
+	pub fn redacted(&self) -> Self {
+		EthConfig {
+			eth_rpc_headers: super::redact_headers(&self.eth_rpc_headers),
+			eth_rpc_bearer_token: super::redact(&self.eth_rpc_bearer_token),
+			signer_private_key: super::redact(&self.signer_private_key),
+			..self.clone()
+		}
+	}
 }
 
 impl Default for EthConfig {
@@ -166,6 +375,8 @@ impl Default for EthConfig {
 			eth_ws_connection_protocol: default_eth_ws_connection_protocol(),
 			eth_ws_connection_hostname: default_eth_ws_connection_hostname(),
 			eth_ws_connection_port: default_eth_ws_connection_port(),
+			eth_rpc_headers: BTreeMap::new(),
+			eth_rpc_bearer_token: default_eth_rpc_bearer_token(),
 			eth_chain_id: default_eth_chain_id(),
 
 			eth_initiator_contract: default_eth_initiator_contract(),
@@ -175,6 +386,25 @@ impl Default for EthConfig {
 			signer_private_key: default_signer_private_key(),
 			gas_limit: default_gas_limit(),
 			transaction_send_retries: default_transaction_send_retries(),
+			eth_gas_price_strategy: default_eth_gas_price_strategy(),
+			eth_gas_price_wei: default_eth_gas_price_wei(),
+			eth_max_fee_per_gas_wei: default_eth_max_fee_per_gas_wei(),
+			eth_max_priority_fee_per_gas_wei: default_eth_max_priority_fee_per_gas_wei(),
+			eth_gas_price_bump_percent: default_eth_gas_price_bump_percent(),
+			eth_gas_price_max_bumps: default_eth_gas_price_max_bumps(),
+			eth_confirmation_tiers: Vec::new(),
+			eth_asset_confirmations: Vec::new(),
+			eth_confirmation_secs: default_eth_confirmation_secs(),
+			eth_confirmation_blocks: default_eth_confirmation_blocks(),
+			eth_hash_algorithm: default_eth_hash_algorithm(),
+			eth_fee_on_transfer_tokens: Vec::new(),
+			eth_event_source: default_eth_event_source(),
+			eth_ws_reconnect_max_retries: default_eth_ws_reconnect_max_retries(),
+			eth_ws_reconnect_base_delay_ms: default_eth_ws_reconnect_base_delay_ms(),
+			eth_asset: default_eth_asset(),
+			eth_checkpoint_path: default_eth_checkpoint_path(),
+			eth_checkpoint_every_blocks: default_eth_checkpoint_every_blocks(),
+			eth_checkpoint_every_secs: default_eth_checkpoint_every_secs(),
 		}
 	}
 }
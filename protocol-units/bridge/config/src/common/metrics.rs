@@ -0,0 +1,22 @@
+use godfig::env_default;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MetricsConfig {
+	/// Listen address of the Prometheus endpoint, metrics aren't served if empty.
+	#[serde(default = "default_metrics_listen_address")]
+	pub metrics_listen_address: String,
+}
+
+env_default!(
+	default_metrics_listen_address,
+	"BRIDGE_METRICS_LISTEN_ADDRESS",
+	String,
+	String::new()
+);
+
+impl Default for MetricsConfig {
+	fn default() -> Self {
+		MetricsConfig { metrics_listen_address: default_metrics_listen_address() }
+	}
+}
@@ -1,3 +1,30 @@
+pub mod balance;
 pub mod eth;
+pub mod metrics;
 pub mod movement;
+pub mod nudge;
+pub mod rate_limit;
+pub mod refund;
+pub mod slo;
+pub mod store;
+pub mod stuck;
 pub mod testing;
+
+use std::collections::BTreeMap;
+
+/// Value logged in place of a secret.
+pub const REDACTED: &str = "<redacted>";
+
+/// Hide `secret`, an empty one is kept to show it's unset.
+pub fn redact(secret: &str) -> String {
+	if secret.is_empty() {
+		String::new()
+	} else {
+		REDACTED.to_string()
+	}
+}
+
+/// Hide the values of `headers`, keeping their names.
+pub fn redact_headers(headers: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+	headers.keys().map(|name| (name.clone(), REDACTED.to_string())).collect()
+}
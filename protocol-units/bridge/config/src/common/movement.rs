@@ -1,12 +1,19 @@
 use aptos_crypto::{ed25519::Ed25519PrivateKey, Uniform, ValidCryptoMaterialStringExt};
 use godfig::env_default;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 const DEFAULT_MOVEMENT_NATIVE_ADDRESS: &str = "0xface";
 const DEFAULT_MVT_RPC_CONNECTION_HOSTNAME: &str = "127.0.0.1";
 const DEFAULT_MVT_RPC_CONNECTION_PORT: u16 = 8080;
 const DEFAULT_MVT_FAUCET_CONNECTION_HOSTNAME: &str = "127.0.0.1";
 const DEFAULT_MVT_FAUCET_CONNECTION_PORT: u16 = 8081;
+const DEFAULT_MVT_GRPC_CONNECTION_HOSTNAME: &str = "127.0.0.1";
+const DEFAULT_MVT_GRPC_CONNECTION_PORT: u16 = 30734;
+const DEFAULT_MOVEMENT_ASSET: &str = "moveth";
+const DEFAULT_MOVEMENT_EVENT_SOURCE: &str = "polling";
+const DEFAULT_MOVEMENT_CONFIRMATION_VERSIONS: u64 = 0;
+const DEFAULT_MOVEMENT_HASH_ALGORITHM: &str = "keccak256";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MovementConfig {
@@ -21,6 +28,12 @@ pub struct MovementConfig {
 	pub mvt_rpc_connection_hostname: String,
 	#[serde(default = "default_mvt_rpc_connection_port")]
 	pub mvt_rpc_connection_port: u16,
+	/// Headers added to every REST request, for endpoints behind an authenticating proxy.
+	#[serde(default)]
+	pub mvt_rpc_headers: BTreeMap<String, String>,
+	/// Bearer token of the REST requests, empty for none.
+	#[serde(default = "default_mvt_rpc_bearer_token")]
+	pub mvt_rpc_bearer_token: String,
 
 	#[serde(default = "default_mvt_faucet_connection_protocol")]
 	pub mvt_faucet_connection_protocol: String,
@@ -31,6 +44,31 @@ pub struct MovementConfig {
 
 	#[serde(default = "default_mvt_init_network")]
 	pub mvt_init_network: String,
+
+	/// Node indexer gRPC service, used by the `streaming` event source.
+	#[serde(default = "default_mvt_grpc_connection_protocol")]
+	pub mvt_grpc_connection_protocol: String,
+	#[serde(default = "default_mvt_grpc_connection_hostname")]
+	pub mvt_grpc_connection_hostname: String,
+	#[serde(default = "default_mvt_grpc_connection_port")]
+	pub mvt_grpc_connection_port: u16,
+
+	/// How the monitoring observes the module events: `polling` or `streaming` (indexer gRPC).
+	#[serde(default = "default_movement_event_source")]
+	pub movement_event_source: String,
+
+	/// Ledger versions committed after an event before acting on it. While it's non zero the
+	/// monitoring keeps polling, the stream delivers the events as soon as they're committed.
+	#[serde(default = "default_movement_confirmation_versions")]
+	pub movement_confirmation_versions: u64,
+
+	/// Hash function of the bridge modules hash locks, only `keccak256` is supported by the modules.
+	#[serde(default = "default_movement_hash_algorithm")]
+	pub movement_hash_algorithm: String,
+
+	/// Asset the bridge modules transfer, used to tag their events: `eth_and_weth` or `moveth`.
+	#[serde(default = "default_movement_asset")]
+	pub movement_asset: String,
 }
 
 // The default private key
@@ -69,6 +107,8 @@ env_default!(
 	DEFAULT_MVT_RPC_CONNECTION_PORT
 );
 
+env_default!(default_mvt_rpc_bearer_token, "MVT_RPC_BEARER_TOKEN", String, String::new());
+
 env_default!(
 	default_mvt_faucet_connection_protocol,
 	"MVT_FAUCET_CONNECTION_PROTOCOL",
@@ -92,6 +132,50 @@ env_default!(
 
 env_default!(default_mvt_init_network, "MVT_FAUCET_INIT_NETWORK", String, "local".to_string());
 
+env_default!(
+	default_mvt_grpc_connection_protocol,
+	"MVT_GRPC_CONNECTION_PROTOCOL",
+	String,
+	"http".to_string()
+);
+
+env_default!(
+	default_mvt_grpc_connection_hostname,
+	"MVT_GRPC_CONNECTION_HOSTNAME",
+	String,
+	DEFAULT_MVT_GRPC_CONNECTION_HOSTNAME.to_string()
+);
+
+env_default!(
+	default_mvt_grpc_connection_port,
+	"MVT_GRPC_CONNECTION_PORT",
+	u16,
+	DEFAULT_MVT_GRPC_CONNECTION_PORT
+);
+
+env_default!(
+	default_movement_event_source,
+	"MOVEMENT_EVENT_SOURCE",
+	String,
+	DEFAULT_MOVEMENT_EVENT_SOURCE.to_string()
+);
+
+env_default!(
+	default_movement_confirmation_versions,
+	"MOVEMENT_CONFIRMATION_VERSIONS",
+	u64,
+	DEFAULT_MOVEMENT_CONFIRMATION_VERSIONS
+);
+
+env_default!(
+	default_movement_hash_algorithm,
+	"MOVEMENT_HASH_ALGORITHM",
+	String,
+	DEFAULT_MOVEMENT_HASH_ALGORITHM.to_string()
+);
+
+env_default!(default_movement_asset, "MOVEMENT_ASSET", String, DEFAULT_MOVEMENT_ASSET.to_string());
+
 impl MovementConfig {
 	pub fn mvt_rpc_connection_url(&self) -> String {
 		format!(
@@ -110,6 +194,25 @@ impl MovementConfig {
 			self.mvt_faucet_connection_port
 		)
 	}
+
+	pub fn mvt_grpc_connection_url(&self) -> String {
+		format!(
+			"{}://{}:{}",
+			self.mvt_grpc_connection_protocol,
+			self.mvt_grpc_connection_hostname,
+			self.mvt_grpc_connection_port
+		)
+	}
+
+	/// Copy of the config without the RPC credentials, to be logged. The signer key is already
+	/// elided by its `Debug`.
+	pub fn redacted(&self) -> Self {
+		MovementConfig {
+			mvt_rpc_headers: super::redact_headers(&self.mvt_rpc_headers),
+			mvt_rpc_bearer_token: super::redact(&self.mvt_rpc_bearer_token),
+			..self.clone()
+		}
+	}
 }
 
 impl Default for MovementConfig {
@@ -120,10 +223,19 @@ impl Default for MovementConfig {
 			mvt_rpc_connection_protocol: default_mvt_rpc_connection_protocol(),
 			mvt_rpc_connection_hostname: default_mvt_rpc_connection_hostname(),
 			mvt_rpc_connection_port: default_mvt_rpc_connection_port(),
+			mvt_rpc_headers: BTreeMap::new(),
+			mvt_rpc_bearer_token: default_mvt_rpc_bearer_token(),
 			mvt_faucet_connection_protocol: default_mvt_rpc_connection_protocol(),
 			mvt_faucet_connection_hostname: default_mvt_rpc_connection_hostname(),
 			mvt_faucet_connection_port: default_mvt_faucet_connection_port(),
 			mvt_init_network: default_mvt_init_network(),
+			mvt_grpc_connection_protocol: default_mvt_grpc_connection_protocol(),
+			mvt_grpc_connection_hostname: default_mvt_grpc_connection_hostname(),
+			mvt_grpc_connection_port: default_mvt_grpc_connection_port(),
+			movement_event_source: default_movement_event_source(),
+			movement_confirmation_versions: default_movement_confirmation_versions(),
+			movement_hash_algorithm: default_movement_hash_algorithm(),
+			movement_asset: default_movement_asset(),
 		}
 	}
 }
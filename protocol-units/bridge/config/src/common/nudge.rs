@@ -0,0 +1,31 @@
+use godfig::env_default;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NudgeConfig {
+	/// Re-submit the initiator completion when its event isn't observed,
+	/// instead of only waiting for the time lock expiry.
+	#[serde(default = "default_nudge_enabled")]
+	pub nudge_enabled: bool,
+	/// Delay in seconds without progress before a nudge.
+	#[serde(default = "default_nudge_delay_secs")]
+	pub nudge_delay_secs: u64,
+	#[serde(default = "default_nudge_max_attempts")]
+	pub nudge_max_attempts: u32,
+}
+
+env_default!(default_nudge_enabled, "BRIDGE_NUDGE_ENABLED", bool, false);
+
+env_default!(default_nudge_delay_secs, "BRIDGE_NUDGE_DELAY_SECS", u64, 60);
+
+env_default!(default_nudge_max_attempts, "BRIDGE_NUDGE_MAX_ATTEMPTS", u32, 3);
+
+impl Default for NudgeConfig {
+	fn default() -> Self {
+		NudgeConfig {
+			nudge_enabled: default_nudge_enabled(),
+			nudge_delay_secs: default_nudge_delay_secs(),
+			nudge_max_attempts: default_nudge_max_attempts(),
+		}
+	}
+}
@@ -0,0 +1,38 @@
+use godfig::env_default;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+	/// Most completion transactions submitted on Eth per window, the others are queued.
+	/// 0 disables the limit.
+	#[serde(default = "default_eth_completion_rate_limit")]
+	pub eth_completion_rate_limit: u64,
+	/// Most completion transactions submitted on Movement per window, the others are queued.
+	/// 0 disables the limit.
+	#[serde(default = "default_movement_completion_rate_limit")]
+	pub movement_completion_rate_limit: u64,
+	/// Rolling window in seconds of the completion rate limits.
+	#[serde(default = "default_completion_rate_window_secs")]
+	pub completion_rate_window_secs: u64,
+}
+
+env_default!(default_eth_completion_rate_limit, "BRIDGE_ETH_COMPLETION_RATE_LIMIT", u64, 0);
+
+env_default!(
+	default_movement_completion_rate_limit,
+	"BRIDGE_MOVEMENT_COMPLETION_RATE_LIMIT",
+	u64,
+	0
+);
+
+env_default!(default_completion_rate_window_secs, "BRIDGE_COMPLETION_RATE_WINDOW_SECS", u64, 60);
+
+impl Default for RateLimitConfig {
+	fn default() -> Self {
+		RateLimitConfig {
+			eth_completion_rate_limit: default_eth_completion_rate_limit(),
+			movement_completion_rate_limit: default_movement_completion_rate_limit(),
+			completion_rate_window_secs: default_completion_rate_window_secs(),
+		}
+	}
+}
@@ -0,0 +1,26 @@
+use godfig::env_default;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RefundConfig {
+	/// Refund automatically the Eth to Movement transfers whose time lock expired.
+	/// When disabled the transfer is flagged and an operator does the refund.
+	#[serde(default = "default_eth_to_movement_auto_refund")]
+	pub eth_to_movement_auto_refund: bool,
+	/// Refund automatically the Movement to Eth transfers whose time lock expired.
+	#[serde(default = "default_movement_to_eth_auto_refund")]
+	pub movement_to_eth_auto_refund: bool,
+}
+
+env_default!(default_eth_to_movement_auto_refund, "BRIDGE_ETH_TO_MOVEMENT_AUTO_REFUND", bool, true);
+
+env_default!(default_movement_to_eth_auto_refund, "BRIDGE_MOVEMENT_TO_ETH_AUTO_REFUND", bool, true);
+
+impl Default for RefundConfig {
+	fn default() -> Self {
+		RefundConfig {
+			eth_to_movement_auto_refund: default_eth_to_movement_auto_refund(),
+			movement_to_eth_auto_refund: default_movement_to_eth_auto_refund(),
+		}
+	}
+}
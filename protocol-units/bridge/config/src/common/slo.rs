@@ -0,0 +1,31 @@
+use godfig::env_default;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SloConfig {
+	/// Seconds from the lock to the completion within which a transfer meets the SLO.
+	#[serde(default = "default_slo_completion_target_secs")]
+	pub slo_completion_target_secs: u64,
+	/// Percentage of the transfers that must meet the target, it's breached below.
+	#[serde(default = "default_slo_objective_percent")]
+	pub slo_objective_percent: f64,
+	/// Rolling window in seconds of the completions the compliance is computed on.
+	#[serde(default = "default_slo_window_secs")]
+	pub slo_window_secs: u64,
+}
+
+env_default!(default_slo_completion_target_secs, "BRIDGE_SLO_COMPLETION_TARGET_SECS", u64, 600);
+
+env_default!(default_slo_objective_percent, "BRIDGE_SLO_OBJECTIVE_PERCENT", f64, 95.0);
+
+env_default!(default_slo_window_secs, "BRIDGE_SLO_WINDOW_SECS", u64, 3600);
+
+impl Default for SloConfig {
+	fn default() -> Self {
+		SloConfig {
+			slo_completion_target_secs: default_slo_completion_target_secs(),
+			slo_objective_percent: default_slo_objective_percent(),
+			slo_window_secs: default_slo_window_secs(),
+		}
+	}
+}
@@ -0,0 +1,29 @@
+use godfig::env_default;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_STORE_BACKEND: &str = "memory";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoreConfig {
+	/// Persistence backend of the transfers: `memory`, `jsonl`, `sqlite` or `postgres`.
+	#[serde(default = "default_store_backend")]
+	pub store_backend: String,
+	/// File path for `jsonl`, database url for `sqlite` and `postgres`.
+	#[serde(default = "default_store_url")]
+	pub store_url: String,
+}
+
+env_default!(
+	default_store_backend,
+	"BRIDGE_STORE_BACKEND",
+	String,
+	DEFAULT_STORE_BACKEND.to_string()
+);
+
+env_default!(default_store_url, "BRIDGE_STORE_URL", String, String::new());
+
+impl Default for StoreConfig {
+	fn default() -> Self {
+		StoreConfig { store_backend: default_store_backend(), store_url: default_store_url() }
+	}
+}
@@ -0,0 +1,43 @@
+use godfig::env_default;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StuckConfig {
+	/// Seconds after their initiation before the incomplete Eth to Movement transfers
+	/// are flagged as stuck.
+	#[serde(default = "default_eth_to_movement_stuck_grace_secs")]
+	pub eth_to_movement_stuck_grace_secs: u64,
+	/// Seconds after their initiation before the incomplete Movement to Eth transfers
+	/// are flagged as stuck.
+	#[serde(default = "default_movement_to_eth_stuck_grace_secs")]
+	pub movement_to_eth_stuck_grace_secs: u64,
+	/// Eth block time, the grace windows are extended by the time the Eth confirmations take.
+	#[serde(default = "default_eth_block_time_secs")]
+	pub eth_block_time_secs: u64,
+}
+
+env_default!(
+	default_eth_to_movement_stuck_grace_secs,
+	"BRIDGE_ETH_TO_MOVEMENT_STUCK_GRACE_SECS",
+	u64,
+	600
+);
+
+env_default!(
+	default_movement_to_eth_stuck_grace_secs,
+	"BRIDGE_MOVEMENT_TO_ETH_STUCK_GRACE_SECS",
+	u64,
+	600
+);
+
+env_default!(default_eth_block_time_secs, "BRIDGE_ETH_BLOCK_TIME_SECS", u64, 12);
+
+impl Default for StuckConfig {
+	fn default() -> Self {
+		StuckConfig {
+			eth_to_movement_stuck_grace_secs: default_eth_to_movement_stuck_grace_secs(),
+			movement_to_eth_stuck_grace_secs: default_movement_to_eth_stuck_grace_secs(),
+			eth_block_time_secs: default_eth_block_time_secs(),
+		}
+	}
+}
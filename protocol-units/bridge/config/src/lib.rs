@@ -14,6 +14,38 @@ pub struct Config {
 	#[serde(default)]
 	pub movement: common::movement::MovementConfig,
 
+	/// Persistence of the in-flight transfers.
+	#[serde(default)]
+	pub store: common::store::StoreConfig,
+
+	/// Handling of the transfers whose time lock expired.
+	#[serde(default)]
+	pub refund: common::refund::RefundConfig,
+
+	/// Re-submission of the completions that aren't observed on chain.
+	#[serde(default)]
+	pub nudge: common::nudge::NudgeConfig,
+
+	/// Detection of the transfers that don't complete in time.
+	#[serde(default)]
+	pub stuck: common::stuck::StuckConfig,
+
+	/// Completion latency SLO of the transfers.
+	#[serde(default)]
+	pub slo: common::slo::SloConfig,
+
+	/// Alerts and degraded mode on low signer balances.
+	#[serde(default)]
+	pub balance: common::balance::BalanceConfig,
+
+	/// Caps on the completion transactions submitted per chain.
+	#[serde(default)]
+	pub rate_limit: common::rate_limit::RateLimitConfig,
+
+	/// Prometheus endpoint of the relayer.
+	#[serde(default)]
+	pub metrics: common::metrics::MetricsConfig,
+
 	/// Optional testing config
 	#[serde(default)]
 	pub testing: common::testing::TestingConfig,
@@ -24,13 +56,63 @@ impl Default for Config {
 		Config {
 			eth: common::eth::EthConfig::default(),
 			movement: common::movement::MovementConfig::default(),
+			store: common::store::StoreConfig::default(),
+			refund: common::refund::RefundConfig::default(),
+			nudge: common::nudge::NudgeConfig::default(),
+			stuck: common::stuck::StuckConfig::default(),
+			slo: common::slo::SloConfig::default(),
+			balance: common::balance::BalanceConfig::default(),
+			rate_limit: common::rate_limit::RateLimitConfig::default(),
+			metrics: common::metrics::MetricsConfig::default(),
 			testing: common::testing::TestingConfig::default(),
 		}
 	}
 }
 
+impl Config {
+	/// Copy of the config without its secrets, to be logged.
+	pub fn redacted(&self) -> Self {
+		Config { eth: self.eth.redacted(), movement: self.movement.redacted(), ..self.clone() }
+	}
+}
+
 pub fn get_config_path(dot_movement: &dot_movement::DotMovement) -> std::path::PathBuf {
 	let mut pathbuff = std::path::PathBuf::from(dot_movement.get_path());
 	pathbuff.push(BRIDGE_CONF_FOLDER);
 	pathbuff
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_redacted_config_hides_the_credentials() {
+		let mut config = Config::default();
+		config
+			.eth
+			.eth_rpc_headers
+			.insert("x-api-key".to_string(), "eth-header-secret".to_string());
+		config.eth.eth_rpc_bearer_token = "eth-token-secret".to_string();
+		config.eth.signer_private_key = "eth-key-secret".to_string();
+		config
+			.movement
+			.mvt_rpc_headers
+			.insert("x-api-key".to_string(), "mvt-header-secret".to_string());
+		config.movement.mvt_rpc_bearer_token = "mvt-token-secret".to_string();
+
+		let logged = format!("{:?}", config.redacted());
+		for secret in [
+			"eth-header-secret",
+			"eth-token-secret",
+			"eth-key-secret",
+			"mvt-header-secret",
+			"mvt-token-secret",
+		] {
+			assert!(!logged.contains(secret), "{secret} is logged");
+		}
+		// The header names and the settings stay readable.
+		assert!(logged.contains("x-api-key"));
+		assert!(logged.contains(&config.eth.eth_rpc_connection_hostname));
+	}
+}
@@ -75,7 +75,7 @@ contract AtomicBridgeCounterparty is IAtomicBridgeCounterparty, OwnableUpgradeab
         return true;
     }
 
-    function completeBridgeTransfer(bytes32 bridgeTransferId, bytes32 preImage) external {
+    function completeBridgeTransfer(bytes32 bridgeTransferId, bytes32 preImage) public {
         BridgeTransferDetails storage details = bridgeTransfers[bridgeTransferId];
         if (details.state != MessageState.PENDING) revert BridgeTransferStateNotPending();
         bytes32 computedHash = keccak256(abi.encodePacked(preImage));
@@ -89,6 +89,15 @@ contract AtomicBridgeCounterparty is IAtomicBridgeCounterparty, OwnableUpgradeab
         emit BridgeTransferCompleted(bridgeTransferId, preImage);
     }
 
+    function batchCompleteBridgeTransfer(bytes32[] calldata bridgeTransferIds, bytes32[] calldata preImages)
+        external
+    {
+        if (bridgeTransferIds.length != preImages.length) revert BatchLengthMismatch();
+        for (uint256 i = 0; i < bridgeTransferIds.length; i++) {
+            completeBridgeTransfer(bridgeTransferIds[i], preImages[i]);
+        }
+    }
+
     function abortBridgeTransfer(bytes32 bridgeTransferId) external onlyOwner {
         BridgeTransferDetails storage details = bridgeTransfers[bridgeTransferId];
         if (details.state != MessageState.PENDING) revert BridgeTransferStateNotPending();
@@ -30,6 +30,7 @@ interface IAtomicBridgeCounterparty {
     error TimeLockExpired();
     error ZeroAddress();
     error Unauthorized();
+    error BatchLengthMismatch();
 
     /**
      * @dev Locks the assets for a new atomic bridge transfer
@@ -57,6 +58,15 @@ interface IAtomicBridgeCounterparty {
      */
     function completeBridgeTransfer(bytes32 bridgeTransferId, bytes32 preImage) external;
 
+    /**
+     * @dev Completes several bridge transfers in one transaction, reverting all of them if one fails
+     * @param bridgeTransferIds Unique identifiers of the BridgeTransfers
+     * @param preImages The secrets that unlock the funds, in the order of the identifiers
+     *
+     */
+    function batchCompleteBridgeTransfer(bytes32[] calldata bridgeTransferIds, bytes32[] calldata preImages)
+        external;
+
     /**
      * @dev Cancels the bridge transfer and refunds the initiator if the timelock has expired
      * @param bridgeTransferId Unique identifier for the BridgeTransfer
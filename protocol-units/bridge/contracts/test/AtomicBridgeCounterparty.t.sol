@@ -5,6 +5,7 @@ pragma abicoder v2;
 import {Test, console} from "forge-std/Test.sol";
 import {AtomicBridgeCounterparty} from "../src/AtomicBridgeCounterparty.sol";
 import {AtomicBridgeInitiator} from "../src/AtomicBridgeInitiator.sol";
+import {IAtomicBridgeCounterparty} from "../src/IAtomicBridgeCounterparty.sol";
 import {ProxyAdmin} from "@openzeppelin/contracts/proxy/transparent/ProxyAdmin.sol";
 import {TransparentUpgradeableProxy} from "@openzeppelin/contracts/proxy/transparent/TransparentUpgradeableProxy.sol";
 import {IWETH9} from "../src/IWETH9.sol";
@@ -149,6 +150,56 @@ contract AtomicBridgeCounterpartyTest is Test {
         vm.stopPrank();
     }
 
+    function testBatchCompleteBridgeTransfer() public {
+        bytes32[] memory bridgeTransferIds = new bytes32[](3);
+        bytes32[] memory preImages = new bytes32[](3);
+
+        vm.deal(deployer, 3 ether);
+        vm.startPrank(deployer);
+
+        for (uint256 i = 0; i < 3; i++) {
+            preImages[i] = keccak256(abi.encodePacked("secret", i));
+            bytes32 testHashLock = keccak256(abi.encodePacked(preImages[i]));
+            bridgeTransferIds[i] = keccak256(abi.encodePacked(bridgeTransferId, i));
+
+            // Deposit WETH into AtomicBridgeInitiator to increase poolBalance
+            weth.deposit{value: amount}();
+            weth.approve(address(atomicBridgeInitiator), amount);
+            atomicBridgeInitiator.initiateBridgeTransfer(amount, initiator, testHashLock);
+
+            atomicBridgeCounterparty.lockBridgeTransfer(
+                initiator, bridgeTransferIds[i], testHashLock, recipient, amount
+            );
+        }
+
+        vm.stopPrank();
+        vm.startPrank(otherUser);
+
+        // A wrong secret reverts the whole batch.
+        bytes32 validPreImage = preImages[2];
+        preImages[2] = "wrong";
+        vm.expectRevert(IAtomicBridgeCounterparty.InvalidSecret.selector);
+        atomicBridgeCounterparty.batchCompleteBridgeTransfer(bridgeTransferIds, preImages);
+        (,,,,, AtomicBridgeCounterparty.MessageState firstState) =
+            atomicBridgeCounterparty.bridgeTransfers(bridgeTransferIds[0]);
+        assertEq(uint8(firstState), uint8(AtomicBridgeCounterparty.MessageState.PENDING));
+
+        preImages[2] = validPreImage;
+        for (uint256 i = 0; i < 3; i++) {
+            vm.expectEmit();
+            emit IAtomicBridgeCounterparty.BridgeTransferCompleted(bridgeTransferIds[i], preImages[i]);
+        }
+        atomicBridgeCounterparty.batchCompleteBridgeTransfer(bridgeTransferIds, preImages);
+
+        for (uint256 i = 0; i < 3; i++) {
+            (,,,,, AtomicBridgeCounterparty.MessageState completedState) =
+                atomicBridgeCounterparty.bridgeTransfers(bridgeTransferIds[i]);
+            assertEq(uint8(completedState), uint8(AtomicBridgeCounterparty.MessageState.COMPLETED));
+        }
+
+        vm.stopPrank();
+    }
+
     function testAbortBridgeTransfer() public {
         vm.deal(deployer, 1 ether);
         vm.startPrank(deployer);
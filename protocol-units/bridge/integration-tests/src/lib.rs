@@ -5,6 +5,7 @@ use alloy::primitives::{keccak256, U256};
 use alloy::providers::ProviderBuilder;
 use alloy::signers::local::PrivateKeySigner;
 use alloy_network::EthereumWallet;
+use aptos_sdk::coin_client::CoinClient;
 use aptos_sdk::rest_client::{Client, FaucetClient};
 use aptos_sdk::types::account_address::AccountAddress;
 use aptos_sdk::types::LocalAccount;
@@ -12,6 +13,7 @@ use bridge_config::Config;
 use bridge_service::chains::ethereum::types::AlloyProvider;
 use bridge_service::chains::ethereum::types::EthAddress;
 use bridge_service::chains::ethereum::{client::EthClient, types::EthHash};
+use bridge_service::chains::mock::{MockChain, MockMonitoring};
 use bridge_service::chains::movement::utils::MovementAddress;
 use bridge_service::chains::movement::{client::MovementClient, utils::MovementHash};
 use bridge_service::types::Amount;
@@ -178,6 +180,29 @@ impl HarnessMvtClient {
 
 		HarnessMvtClient { movement_client, rest_client, faucet_client }
 	}
+
+	/// Fund `address` from the faucet, retrying while the faucet is unavailable.
+	/// Funding is skipped if the account already holds at least `amount`.
+	pub async fn fund_account(
+		&self,
+		address: AccountAddress,
+		amount: u64,
+	) -> Result<(), anyhow::Error> {
+		// The account doesn't exist before its first funding.
+		let coin_client = CoinClient::new(&self.rest_client);
+		if let Ok(balance) = coin_client.get_account_balance(&address).await {
+			if balance >= amount {
+				tracing::info!("Skip funding of {address}, balance {balance} is enough");
+				return Ok(());
+			}
+		}
+
+		let faucet_client = self.faucet_client.read().unwrap();
+		utils::fund_with_retry(utils::FundingRetry::default(), || async {
+			faucet_client.fund(address, amount).await.map_err(anyhow::Error::from)
+		})
+		.await
+	}
 }
 
 pub struct TestHarness;
@@ -216,6 +241,18 @@ impl TestHarness {
 		(test_hadness, config, movement_process)
 	}
 
+	/// Build in memory Eth and Movement chains, no node is started.
+	pub fn new_with_mocks() -> (
+		MockChain<EthAddress>,
+		MockMonitoring<EthAddress>,
+		MockChain<MovementAddress>,
+		MockMonitoring<MovementAddress>,
+	) {
+		let (eth_chain, eth_monitoring) = MockChain::build();
+		let (mvt_chain, mvt_monitoring) = MockChain::build();
+		(eth_chain, eth_monitoring, mvt_chain, mvt_monitoring)
+	}
+
 	pub async fn new_only_eth(config: Config) -> (HarnessEthClient, Config, AnvilInstance) {
 		let (config, anvil) = bridge_setup::test_eth_setup(config)
 			.await
@@ -11,8 +11,54 @@ use bridge_service::chains::movement::utils::{
 	self as movement_utils, MovementAddress, MovementHash,
 };
 use bridge_service::types::{Amount, AssetType, BridgeAddress, BridgeTransferDetails, HashLock};
+use std::future::Future;
+use std::time::Duration;
 use tracing::debug;
 
+/// Retry policy of the faucet funding done by the harness.
+#[derive(Clone, Copy, Debug)]
+pub struct FundingRetry {
+	pub max_attempts: u32,
+	/// Backoff before the second attempt, doubled after each failure.
+	pub initial_backoff: Duration,
+}
+
+impl Default for FundingRetry {
+	fn default() -> Self {
+		FundingRetry { max_attempts: 5, initial_backoff: Duration::from_millis(500) }
+	}
+}
+
+/// Call `fund` until it succeeds or the retry policy is exhausted.
+/// The faucet can be temporarily down or rate limiting the tests.
+pub async fn fund_with_retry<F, Fut>(retry: FundingRetry, mut fund: F) -> Result<()>
+where
+	F: FnMut() -> Fut,
+	Fut: Future<Output = Result<()>>,
+{
+	let mut backoff = retry.initial_backoff;
+	let mut attempt = 1;
+	loop {
+		match fund().await {
+			Ok(()) => return Ok(()),
+			Err(err) if attempt < retry.max_attempts => {
+				tracing::warn!(
+					"Faucet funding attempt {attempt}/{} failed: {err:?}, retrying in {backoff:?}",
+					retry.max_attempts
+				);
+				tokio::time::sleep(backoff).await;
+				backoff *= 2;
+				attempt += 1;
+			}
+			Err(err) => {
+				return Err(err.context(format!(
+					"Faucet funding failed after {attempt} attempts, is the faucet running?"
+				)))
+			}
+		}
+	}
+}
+
 pub fn assert_bridge_transfer_details(
 	details: &BridgeTransferDetails<MovementAddress>, // MovementAddress for initiator
 	expected_bridge_transfer_id: [u8; 32],
@@ -80,8 +126,9 @@ pub async fn fund_and_check_balance(
 	let movement_client_signer = movement_harness.movement_client.signer();
 	let rest_client = movement_harness.rest_client.clone();
 	let coin_client = CoinClient::new(&rest_client);
-	let faucet_client = movement_harness.faucet_client.write().unwrap();
-	faucet_client.fund(movement_client_signer.address(), expected_balance).await?;
+	movement_harness
+		.fund_account(movement_client_signer.address(), expected_balance)
+		.await?;
 
 	let balance = coin_client.get_account_balance(&movement_client_signer.address()).await?;
 	assert!(
@@ -1,4 +1,3 @@
-use alloy::primitives::keccak256;
 use alloy::primitives::{FixedBytes, U256};
 use alloy::providers::ProviderBuilder;
 use alloy::signers::local::PrivateKeySigner;
@@ -9,12 +8,16 @@ use aptos_types::account_address::AccountAddress;
 use bridge_config::Config;
 use bridge_integration_tests::HarnessEthClient;
 use bridge_integration_tests::TestHarness;
+use bridge_service::chains::bridge_contracts::BridgeContract;
 use bridge_service::chains::bridge_contracts::BridgeContractError;
+use bridge_service::chains::bridge_contracts::BridgeContractEvent;
+use bridge_service::chains::bridge_contracts::BridgeContractMonitoring;
+use bridge_service::chains::ethereum::gas_price::GasPricing;
 use bridge_service::chains::ethereum::types::AtomicBridgeInitiator;
 use bridge_service::chains::ethereum::utils::send_transaction;
 use bridge_service::chains::ethereum::utils::send_transaction_rules;
 use bridge_service::chains::{
-	ethereum::{client::EthClient, event_monitoring::EthMonitoring, types::EthAddress},
+	ethereum::{event_monitoring::EthMonitoring, types::EthAddress},
 	movement::{
 		client::MovementClient, event_monitoring::MovementMonitoring, utils::MovementAddress,
 	},
@@ -22,24 +25,77 @@ use bridge_service::chains::{
 use bridge_service::types::Amount;
 use bridge_service::types::AssetType;
 use bridge_service::types::BridgeAddress;
+use bridge_service::types::HashAlgorithm;
 use bridge_service::types::HashLock;
 use bridge_service::types::HashLockPreImage;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
 use tokio_stream::StreamExt;
 use tracing_subscriber::EnvFilter;
 
-async fn start_bridge_local(config: &Config) -> Result<tokio::task::JoinHandle<()>, anyhow::Error> {
-	let one_stream = EthMonitoring::build(&config.eth).await?;
-	let one_client = EthClient::new(&config.eth).await?;
-	let two_client = MovementClient::new(&config.movement).await?;
+// Build and start the relayer binary, it reads the same config as the test harness.
+async fn start_bridge_relayer() -> Result<Child, anyhow::Error> {
+	let status = Command::new(env!("CARGO"))
+		.args(["build", "-p", "bridge-service", "--bin", "bridge-relayer"])
+		.status()
+		.await?;
+	anyhow::ensure!(status.success(), "Failed to build the relayer");
+
+	let target_dir = std::env::var("CARGO_TARGET_DIR")
+		.map(PathBuf::from)
+		.unwrap_or_else(|_| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../target"));
+	let mut relayer = Command::new(target_dir.join("debug/bridge-relayer"))
+		.stdout(Stdio::piped())
+		.kill_on_drop(true)
+		.spawn()?;
+
+	// Bridge only once the relayer monitors both chains, forwarding its logs.
+	let mut lines = BufReader::new(relayer.stdout.take().expect("piped stdout")).lines();
+	tokio::time::timeout(Duration::from_secs(120), async move {
+		while let Some(line) = lines.next_line().await? {
+			println!("{line}");
+			if line.contains("Starting bridge loop") {
+				tokio::spawn(async move {
+					while let Ok(Some(line)) = lines.next_line().await {
+						println!("{line}");
+					}
+				});
+				return Ok(());
+			}
+		}
+		Err(anyhow::anyhow!("The relayer exited before starting"))
+	})
+	.await??;
+	Ok(relayer)
+}
 
-	let two_stream = MovementMonitoring::build(&config.movement).await?;
+// Stop the relayer the way the deployments do and check it exits cleanly.
+async fn stop_bridge_relayer(mut relayer: Child) -> Result<(), anyhow::Error> {
+	let pid = relayer.id().expect("The relayer already exited");
+	let status = Command::new("kill").args(["-TERM", &pid.to_string()]).status().await?;
+	anyhow::ensure!(status.success(), "Failed to send SIGTERM to the relayer");
+	let status = tokio::time::timeout(Duration::from_secs(30), relayer.wait()).await??;
+	anyhow::ensure!(status.success(), "The relayer exited with {status}");
+	Ok(())
+}
 
-	let jh = tokio::spawn(async move {
-		bridge_service::run_bridge(one_client, one_stream, two_client, two_stream)
-			.await
-			.unwrap()
-	});
-	Ok(jh)
+// Wait until `monitoring` delivers an event `check` returns a value for.
+async fn wait_for_event<A, T>(
+	monitoring: &mut impl BridgeContractMonitoring<Address = A>,
+	mut check: impl FnMut(BridgeContractEvent<A>) -> Option<T>,
+) -> Result<T, anyhow::Error> {
+	tokio::time::timeout(Duration::from_secs(120), async {
+		while let Some(event) = monitoring.next().await {
+			if let Some(value) = check(event?) {
+				return Ok(value);
+			}
+		}
+		Err(anyhow::anyhow!("Event monitoring stopped"))
+	})
+	.await?
 }
 
 async fn initiate_eth_bridge_transfer(
@@ -78,6 +134,7 @@ async fn initiate_eth_bridge_transfer(
 		&send_transaction_rules(),
 		config.eth.transaction_send_retries,
 		config.eth.gas_limit as u128,
+		&GasPricing::try_from(&config.eth)?,
 	)
 	.await
 	.map_err(|e| BridgeContractError::GenericError(format!("Failed to send transaction: {}", e)))?;
@@ -92,19 +149,23 @@ async fn test_bridge_transfer_eth_movement_happy_path() -> Result<(), anyhow::Er
 		)
 		.init();
 
-	let (eth_client_harness, mvt_client_harness, config) =
+	let (eth_client_harness, mut mvt_client_harness, config) =
 		TestHarness::new_with_eth_and_movement().await?;
 
 	let movement_client_signer_address = mvt_client_harness.movement_client.signer().address();
 
-	{
-		let faucet_client = mvt_client_harness.faucet_client.write().unwrap();
-		faucet_client.fund(movement_client_signer_address, 100_000_000).await?;
-	}
+	mvt_client_harness
+		.fund_account(movement_client_signer_address, 100_000_000)
+		.await?;
+
+	// Observe the chains next to the relayer.
+	let mut eth_monitoring = EthMonitoring::build(&config.eth).await?;
+	let mut mvt_monitoring = MovementMonitoring::build(&config.movement).await?;
+	let mut relayer = start_bridge_relayer().await?;
 
 	// 1) initialize transfer
 	let hash_lock_pre_image = HashLockPreImage::random();
-	let hash_lock = HashLock(From::from(keccak256(hash_lock_pre_image)));
+	let hash_lock = hash_lock_pre_image.hash_lock(HashAlgorithm::Keccak256);
 	let mov_recipient = MovementAddress(AccountAddress::new(*b"0x00000000000000000000000000face"));
 
 	let amount = Amount(AssetType::EthAndWeth((1, 0)));
@@ -118,10 +179,29 @@ async fn test_bridge_transfer_eth_movement_happy_path() -> Result<(), anyhow::Er
 	.await
 	.expect("Failed to initiate bridge transfer");
 
-	//Wait for the tx to be executed
-	let _ = tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
+	// 2) the relayer locks the transfer on Movement
+	let transfer_id = wait_for_event(&mut mvt_monitoring, |event| match event {
+		BridgeContractEvent::Locked(details) if details.hash_lock == hash_lock => {
+			Some(details.bridge_transfer_id)
+		}
+		_ => None,
+	})
+	.await?;
+
+	// 3) the recipient reveals the secret on Movement
+	mvt_client_harness
+		.movement_client
+		.counterparty_complete_bridge_transfer(transfer_id, hash_lock_pre_image)
+		.await?;
 
-	//send counter complete event.
+	// 4) the relayer completes the transfer on Eth with the revealed secret
+	wait_for_event(&mut eth_monitoring, |event| match event {
+		BridgeContractEvent::InitialtorCompleted(id, _) if id == transfer_id => Some(()),
+		_ => None,
+	})
+	.await?;
+	assert!(relayer.try_wait()?.is_none(), "The relayer stopped");
+	stop_bridge_relayer(relayer).await?;
 
 	Ok(())
 }
@@ -247,6 +327,42 @@ async fn test_movement_event() -> Result<(), anyhow::Error> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn test_movement_event_streaming() -> Result<(), anyhow::Error> {
+	let mut config = TestHarness::read_bridge_config().await?;
+	config.movement.movement_event_source = "streaming".to_string();
+	let mut movement_monitoring = MovementMonitoring::build(&config.movement).await?;
+	// Let the monitoring poll the past events and switch to the stream.
+	tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
+
+	use bridge_integration_tests::MovementToEthCallArgs;
+	let mut movement_client = MovementClient::new(&config.movement).await?;
+	let args = MovementToEthCallArgs::default();
+	bridge_integration_tests::utils::initiate_bridge_transfer_helper(
+		&mut movement_client,
+		args.initiator.0,
+		args.recipient.clone(),
+		args.hash_lock.0,
+		args.amount,
+		true,
+	)
+	.await
+	.expect("Failed to initiate bridge transfer");
+
+	let hash_lock = HashLock(args.hash_lock.0);
+	tokio::time::timeout(tokio::time::Duration::from_secs(30), async {
+		while let Some(event) = movement_monitoring.next().await {
+			if let Ok(BridgeContractEvent::Initiated(details)) = event {
+				if details.hash_lock == hash_lock {
+					return Ok(());
+				}
+			}
+		}
+		Err(anyhow::anyhow!("Movement monitoring stopped before the initiated event"))
+	})
+	.await?
+}
+
 async fn test_get_events_by_account_event_handle(
 	rest_url: &str,
 	account_address: &str,
@@ -0,0 +1,90 @@
+use alloy::primitives::Address;
+use anyhow::Result;
+use bridge_integration_tests::TestHarness;
+use bridge_service::chains::bridge_contracts::{BridgeContract, BridgeContractEvent};
+use bridge_service::chains::ethereum::types::EthAddress;
+use bridge_service::types::{
+	Amount, AssetTag, AssetType, BridgeAddress, BridgeTransferId, HashAlgorithm, HashLockPreImage,
+};
+use bridge_service::{HashAlgorithms, RelayerOptions};
+use std::time::Duration;
+
+// Poll the mock chain state until `check` returns a value.
+async fn wait_for<T>(mut check: impl FnMut() -> Option<T>) -> Result<T> {
+	tokio::time::timeout(Duration::from_secs(1), async {
+		loop {
+			if let Some(value) = check() {
+				return value;
+			}
+			tokio::time::sleep(Duration::from_millis(5)).await;
+		}
+	})
+	.await
+	.map_err(|_| anyhow::anyhow!("Mock chain didn't reach the expected state"))
+}
+
+// Bridge a transfer from Eth to Movement, both chains hashing the locks with `hash_algorithm`.
+async fn bridge_eth_movement_transfer(hash_algorithm: HashAlgorithm) -> Result<()> {
+	let (eth_chain, eth_monitoring, mvt_chain, mvt_monitoring) = TestHarness::new_with_mocks();
+	let mut eth_chain = eth_chain.with_hash_algorithm(hash_algorithm);
+	let mut mvt_chain = mvt_chain.with_hash_algorithm(hash_algorithm);
+
+	let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+	let relayer = tokio::spawn(bridge_service::run_bridge(
+		eth_chain.clone(),
+		eth_monitoring,
+		mvt_chain.clone(),
+		mvt_monitoring,
+		RelayerOptions {
+			hash_algorithms: HashAlgorithms { one: hash_algorithm, two: hash_algorithm },
+			..Default::default()
+		},
+		async {
+			let _ = stopped.await;
+		},
+	));
+
+	let secret = HashLockPreImage::random();
+	eth_chain
+		.initiate_bridge_transfer(
+			BridgeAddress(EthAddress(Address::from([1; 20]))),
+			BridgeAddress(vec![2; 32]),
+			secret.hash_lock(hash_algorithm),
+			Amount(AssetType::EthAndWeth((100, 0))),
+		)
+		.await?;
+
+	// The relayer locks the transfer on Movement.
+	let transfer_id: BridgeTransferId = wait_for(|| {
+		mvt_chain.events().into_iter().find_map(|event| match event {
+			BridgeContractEvent::Locked(details) => Some(details.bridge_transfer_id),
+			_ => None,
+		})
+	})
+	.await?;
+
+	// The recipient reveals the secret on Movement, the relayer completes on Eth.
+	mvt_chain.counterparty_complete_bridge_transfer(transfer_id, secret).await?;
+	wait_for(|| {
+		eth_chain
+			.events()
+			.contains(&BridgeContractEvent::InitialtorCompleted(transfer_id, AssetTag::EthAndWeth))
+			.then_some(())
+	})
+	.await?;
+
+	// The relayer stops cleanly once asked to.
+	let _ = stop.send(());
+	relayer.await??;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_mock_bridge_transfer_eth_movement_happy_path() -> Result<()> {
+	bridge_eth_movement_transfer(HashAlgorithm::Keccak256).await
+}
+
+#[tokio::test]
+async fn test_mock_bridge_transfer_with_sha256_hash_lock() -> Result<()> {
+	bridge_eth_movement_transfer(HashAlgorithm::Sha256).await
+}
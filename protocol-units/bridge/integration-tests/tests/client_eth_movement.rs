@@ -9,6 +9,10 @@ use bridge_integration_tests::utils as test_utils;
 use bridge_integration_tests::EthToMovementCallArgs;
 use bridge_integration_tests::HarnessMvtClient;
 use bridge_integration_tests::TestHarness;
+use bridge_service::chains::bridge_contracts::{
+	BridgeContractEvent, BridgeContractEventType, EventFilter,
+};
+use bridge_service::chains::ethereum::event_monitoring::EthMonitoring;
 use bridge_service::chains::ethereum::types::EthAddress;
 use bridge_service::chains::{
 	bridge_contracts::BridgeContract, ethereum::types::EthHash, movement::utils::MovementHash,
@@ -18,6 +22,7 @@ use bridge_service::types::{
 };
 use tokio::time::{sleep, Duration};
 use tokio::{self};
+use tokio_stream::StreamExt;
 use tracing::info;
 
 #[tokio::test]
@@ -27,13 +32,13 @@ async fn test_movement_client_build_and_fund_accounts() -> Result<(), anyhow::Er
 		TestHarness::new_with_movement(config).await;
 
 	//
-	let rest_client = mvt_client_harness.rest_client;
+	let rest_client = mvt_client_harness.rest_client.clone();
 	let coin_client = CoinClient::new(&rest_client);
 	let movement_client_signer = mvt_client_harness.movement_client.signer();
 
-	let faucet_client = mvt_client_harness.faucet_client.write().unwrap();
-
-	faucet_client.fund(movement_client_signer.address(), 100_000_000).await?;
+	mvt_client_harness
+		.fund_account(movement_client_signer.address(), 100_000_000)
+		.await?;
 	let balance = coin_client.get_account_balance(&movement_client_signer.address()).await?;
 	assert!(
 		balance >= 100_000_000,
@@ -76,10 +81,9 @@ async fn test_movement_client_should_successfully_call_lock_and_complete(
 		let coin_client = CoinClient::new(&mvt_client_harness.rest_client);
 		let movement_client_signer = mvt_client_harness.movement_client.signer();
 
-		{
-			let faucet_client = mvt_client_harness.faucet_client.write().unwrap();
-			faucet_client.fund(movement_client_signer.address(), 100_000_000).await?;
-		}
+		mvt_client_harness
+			.fund_account(movement_client_signer.address(), 100_000_000)
+			.await?;
 
 		let balance = coin_client.get_account_balance(&movement_client_signer.address()).await?;
 		assert!(
@@ -179,10 +183,9 @@ async fn test_movement_client_should_successfully_call_lock_and_abort() -> Resul
 		let coin_client = CoinClient::new(&mvt_client_harness.rest_client);
 		let movement_client_signer = mvt_client_harness.movement_client.signer();
 
-		{
-			let faucet_client = mvt_client_harness.faucet_client.write().unwrap();
-			faucet_client.fund(movement_client_signer.address(), 100_000_000).await?;
-		}
+		mvt_client_harness
+			.fund_account(movement_client_signer.address(), 100_000_000)
+			.await?;
 
 		let balance = coin_client.get_account_balance(&movement_client_signer.address()).await?;
 		assert!(
@@ -333,7 +336,7 @@ async fn test_eth_client_should_successfully_call_initiate_transfer_only_eth() {
 
 	let recipient = HarnessMvtClient::gen_aptos_account();
 	let hash_lock: [u8; 32] = keccak256("secret".to_string().as_bytes()).into();
-	eth_client_harness
+	let result = eth_client_harness
 		.eth_client
 		.initiate_bridge_transfer(
 			BridgeAddress(EthAddress(signer_address)),
@@ -343,6 +346,10 @@ async fn test_eth_client_should_successfully_call_initiate_transfer_only_eth() {
 		)
 		.await
 		.expect("Failed to initiate bridge transfer");
+	assert_ne!(result.transfer_id.0, [0; 32]);
+	assert_ne!(result.tx_hash, [0; 32]);
+	assert!(result.block > 0);
+	assert!(result.gas_used > 0);
 }
 
 #[tokio::test]
@@ -456,3 +463,41 @@ async fn test_eth_client_should_successfully_complete_transfer() {
 
 	//TODO: Here call complete with the id captured from the event
 }
+
+#[tokio::test]
+async fn test_eth_monitoring_should_receive_initiated_event_by_subscription() -> Result<()> {
+	let mut config = Config::default();
+	config.eth.eth_event_source = "subscription".to_string();
+	let (mut eth_client_harness, config, _anvil) = TestHarness::new_only_eth(config).await;
+
+	let mut monitoring = EthMonitoring::build_with_filter(
+		&config.eth,
+		EventFilter::only([BridgeContractEventType::Initiated]),
+	)
+	.await?;
+
+	let signer_address: alloy::primitives::Address = eth_client_harness.signer_address();
+	let recipient = HarnessMvtClient::gen_aptos_account();
+	let hash_lock: [u8; 32] = keccak256("subscription".to_string().as_bytes()).into();
+	eth_client_harness
+		.eth_client
+		.initiate_bridge_transfer(
+			BridgeAddress(EthAddress(signer_address)),
+			BridgeAddress(recipient),
+			HashLock(EthHash(hash_lock).0),
+			Amount(AssetType::EthAndWeth((1, 0))),
+		)
+		.await
+		.expect("Failed to initiate bridge transfer");
+
+	let event = tokio::time::timeout(Duration::from_secs(10), monitoring.next())
+		.await?
+		.expect("Eth monitoring stream closed")?;
+	match event {
+		BridgeContractEvent::Initiated(details) => {
+			assert_eq!(details.hash_lock, HashLock(hash_lock))
+		}
+		event => panic!("Unexpected event {event:?}"),
+	}
+	Ok(())
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+use bridge_integration_tests::utils::{fund_with_retry, FundingRetry};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+const RETRY: FundingRetry =
+	FundingRetry { max_attempts: 3, initial_backoff: Duration::from_millis(1) };
+
+#[tokio::test]
+async fn test_funding_retries_until_faucet_recovers() -> Result<()> {
+	let calls = AtomicU32::new(0);
+	// Mock faucet, unavailable on the first call.
+	fund_with_retry(RETRY, || async {
+		match calls.fetch_add(1, Ordering::SeqCst) {
+			0 => Err(anyhow::anyhow!("faucet returned 503")),
+			_ => Ok(()),
+		}
+	})
+	.await?;
+
+	assert_eq!(calls.load(Ordering::SeqCst), 2);
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_funding_fails_with_clear_error_when_faucet_stays_down() {
+	let calls = AtomicU32::new(0);
+	let err = fund_with_retry(RETRY, || async {
+		calls.fetch_add(1, Ordering::SeqCst);
+		Err(anyhow::anyhow!("faucet returned 429"))
+	})
+	.await
+	.unwrap_err();
+
+	assert_eq!(calls.load(Ordering::SeqCst), 3);
+	assert!(err.to_string().contains("Faucet funding failed after 3 attempts"));
+	assert!(format!("{err:?}").contains("faucet returned 429"));
+}
@@ -8,12 +8,11 @@ module atomic_bridge::atomic_bridge_counterparty {
     use aptos_framework::timestamp;
     use aptos_framework::aptos_hash::keccak256;
     use aptos_std::smart_table::{Self, SmartTable};
+    use aptos_framework::vector;
     use moveth::moveth;
 
     #[test_only] 
     use aptos_framework::account::create_account_for_test;
-    #[test_only]
-    use aptos_framework::vector;
     #[test_only] 
     use std::debug;
     #[test_only]
@@ -35,6 +34,7 @@ module atomic_bridge::atomic_bridge_counterparty {
     const ENO_RESULT: u64 = 9;
     const EWRONG_STATE: u64 = 10;
     const ETIMELOCK_EXPIRED: u64 = 11;
+    const EBATCH_LENGTH_MISMATCH: u64 = 12;
 
     struct BridgeConfig has key {
         moveth_minter: address,
@@ -213,6 +213,25 @@ module atomic_bridge::atomic_bridge_counterparty {
         );
     }
 
+    /// Completes several transfers in one transaction, aborting all of them if one fails.
+    public entry fun batch_complete_bridge_transfers(
+        account: &signer,
+        bridge_transfer_ids: vector<vector<u8>>,
+        pre_images: vector<vector<u8>>,
+    ) acquires BridgeTransferStore, BridgeConfig {
+        let len = vector::length(&bridge_transfer_ids);
+        assert!(len == vector::length(&pre_images), EBATCH_LENGTH_MISMATCH);
+        let i = 0;
+        while (i < len) {
+            complete_bridge_transfer(
+                account,
+                *vector::borrow(&bridge_transfer_ids, i),
+                *vector::borrow(&pre_images, i),
+            );
+            i = i + 1;
+        };
+    }
+
     public entry fun abort_bridge_transfer(
         account: &signer,
         bridge_transfer_id: vector<u8>
@@ -310,6 +329,70 @@ module atomic_bridge::atomic_bridge_counterparty {
         assert!(bridge_transfer.originator == originator, EWRONG_ORIGINATOR);
     }
 
+    #[test(origin_account = @origin_addr, resource_addr = @resource_addr, aptos_framework = @0x1, moveth = @moveth, admin = @admin, client = @0xdca)]
+    fun test_batch_complete_bridge_transfers(
+        origin_account: &signer,
+        resource_addr: signer,
+        client: &signer,
+        aptos_framework: signer,
+        moveth: &signer,
+    ) acquires BridgeTransferStore, BridgeConfig {
+        set_up_test(origin_account, &resource_addr);
+
+        timestamp::set_time_has_started_for_testing(&aptos_framework);
+        moveth::init_for_test(moveth);
+        let originator = b"0x123"; //In real world this would be an ethereum address
+        let recipient = @0xface;
+        let bridge_transfer_ids = vector[b"transfer1", b"transfer2", b"transfer3"];
+        let pre_images = vector[b"secret1", b"secret2", b"secret3"];
+        let i = 0;
+        while (i < 3) {
+            lock_bridge_transfer(
+                origin_account,
+                originator,
+                *vector::borrow(&bridge_transfer_ids, i),
+                keccak256(*vector::borrow(&pre_images, i)),
+                recipient,
+                100
+            );
+            i = i + 1;
+        };
+
+        batch_complete_bridge_transfers(client, bridge_transfer_ids, pre_images);
+
+        let store = borrow_global<BridgeTransferStore>(signer::address_of(&resource_addr));
+        assert!(event::counter(&store.bridge_transfer_completed_events) == 3, ENO_RESULT);
+        let i = 0;
+        while (i < 3) {
+            let bridge_transfer: &BridgeTransfer = smart_table::borrow(&store.transfers, *vector::borrow(&bridge_transfer_ids, i));
+            assert!(bridge_transfer.state == COMPLETED, EWRONG_STATE);
+            i = i + 1;
+        };
+    }
+
+    #[test(origin_account = @origin_addr, resource_addr = @resource_addr, aptos_framework = @0x1, moveth = @moveth, admin = @admin, client = @0xdca)]
+    #[expected_failure (abort_code = EWRONG_PREIMAGE, location = Self)]
+    fun test_batch_complete_bridge_transfers_wrong_pre_image(
+        origin_account: &signer,
+        resource_addr: signer,
+        client: &signer,
+        aptos_framework: signer,
+        moveth: &signer,
+    ) acquires BridgeTransferStore, BridgeConfig {
+        set_up_test(origin_account, &resource_addr);
+
+        timestamp::set_time_has_started_for_testing(&aptos_framework);
+        moveth::init_for_test(moveth);
+        lock_bridge_transfer(origin_account, b"0x123", b"transfer1", keccak256(b"secret1"), @0xface, 100);
+        lock_bridge_transfer(origin_account, b"0x123", b"transfer2", keccak256(b"secret2"), @0xface, 100);
+
+        batch_complete_bridge_transfers(
+            client,
+            vector[b"transfer1", b"transfer2"],
+            vector[b"secret1", b"wrong"],
+        );
+    }
+
     #[test(origin_account = @origin_addr, resource_addr = @resource_addr, aptos_framework = @0x1, moveth = @moveth, admin = @admin, client = @0xdca)]
     #[expected_failure (abort_code = ETIMELOCK_EXPIRED, location = Self)]
     fun test_complete_bridge_transfer_expired(
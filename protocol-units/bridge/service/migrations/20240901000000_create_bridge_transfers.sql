@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS bridge_transfers (
+	transfer_id TEXT PRIMARY KEY NOT NULL,
+	pending BOOLEAN NOT NULL,
+	record TEXT NOT NULL,
+	updated_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS bridge_transfers_pending ON bridge_transfers (pending);
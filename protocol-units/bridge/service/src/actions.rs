@@ -1,5 +1,6 @@
 use crate::chains::bridge_contracts::BridgeContract;
 use crate::chains::bridge_contracts::BridgeContractError;
+use crate::chains::bridge_contracts::BridgeTransferStatus;
 use crate::types::Amount;
 use crate::types::BridgeAddress;
 use crate::types::BridgeTransferId;
@@ -12,7 +13,7 @@ use std::pin::Pin;
 use thiserror::Error;
 
 #[derive(Error, Debug, Clone)]
-pub struct ActionExecError(TransferAction, BridgeContractError);
+pub struct ActionExecError(pub TransferAction, pub BridgeContractError);
 
 impl ActionExecError {
 	pub fn inner(self) -> (TransferAction, BridgeContractError) {
@@ -48,6 +49,8 @@ pub enum TransferActionType {
 		amount: Amount,
 	},
 	WaitAndCompleteInitiator(u64, HashLockPreImage),
+	// Re-submit the initiator completion if it's still not done on chain.
+	NudgeCompleteInitiator(HashLockPreImage),
 	RefundInitiator,
 	TransferDone,
 	NoAction,
@@ -58,6 +61,7 @@ impl fmt::Display for TransferActionType {
 		let act = match self {
 			TransferActionType::LockBridgeTransfer { .. } => "LockBridgeTransfer",
 			TransferActionType::WaitAndCompleteInitiator(..) => "WaitAndCompleteInitiator",
+			TransferActionType::NudgeCompleteInitiator(..) => "NudgeCompleteInitiator",
 			TransferActionType::RefundInitiator => "RefundInitiator",
 			TransferActionType::TransferDone => "TransferDone",
 			TransferActionType::NoAction => "NoAction",
@@ -108,8 +112,77 @@ where
 			};
 			Some(Box::pin(future))
 		}
-		TransferActionType::RefundInitiator => None,
+		TransferActionType::NudgeCompleteInitiator(secret) => {
+			let future = async move {
+				// The contracts encode the states differently, the status maps them per chain.
+				let status = client
+					.get_bridge_transfer_details(action.transfer_id)
+					.await
+					.map_err(|err| ActionExecError(action.clone(), err))?;
+				match status {
+					// The previous completion was dropped.
+					BridgeTransferStatus::Initiated(_) => client
+						.initiator_complete_bridge_transfer(action.transfer_id, secret)
+						.await
+						.map_err(|err| ActionExecError(action, err)),
+					// Already completed or refunded, its event is still to come.
+					_ => Ok(()),
+				}
+			};
+			Some(Box::pin(future))
+		}
+		TransferActionType::RefundInitiator => {
+			let future = async move {
+				client
+					.refund_bridge_transfer(action.transfer_id)
+					.await
+					.map_err(|err| ActionExecError(action, err))
+			};
+			Some(Box::pin(future))
+		}
 		TransferActionType::TransferDone => None,
 		TransferActionType::NoAction => None,
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chains::bridge_contracts::BridgeContractEvent;
+	use crate::chains::mock::MockChain;
+	use crate::types::AssetType;
+
+	#[tokio::test]
+	async fn test_nudge_completes_only_the_initiated_transfers() {
+		// The Move modules encode an initialized transfer as 1, the Solidity contracts as 0.
+		let (move_chain, _) = MockChain::<Vec<u8>>::build();
+		let (eth_chain, _) = MockChain::<Vec<u8>>::build();
+		for mut chain in [move_chain, eth_chain.with_solidity_states()] {
+			let transfer_id = chain
+				.initiate_bridge_transfer(
+					BridgeAddress(vec![1; 20]),
+					BridgeAddress(vec![2; 32]),
+					HashLock([3; 32]),
+					Amount(AssetType::Moveth(10)),
+				)
+				.await
+				.unwrap()
+				.transfer_id;
+			let nudge = TransferAction {
+				chain: ChainId::ONE,
+				transfer_id,
+				kind: TransferActionType::NudgeCompleteInitiator(HashLockPreImage([4; 32])),
+			};
+
+			// The dropped completion is sent again, not once the transfer is completed.
+			process_action(nudge.clone(), chain.clone()).unwrap().await.unwrap();
+			process_action(nudge, chain.clone()).unwrap().await.unwrap();
+			let completions = chain
+				.events()
+				.into_iter()
+				.filter(|event| matches!(event, BridgeContractEvent::InitialtorCompleted(..)))
+				.count();
+			assert_eq!(completions, 1);
+		}
+	}
+}
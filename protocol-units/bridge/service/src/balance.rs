@@ -0,0 +1,256 @@
+use crate::actions::{TransferAction, TransferActionType};
+use crate::self_check::ChainHealthCheck;
+use crate::types::ChainId;
+use bridge_config::common::balance::BalanceConfig;
+use prometheus::{GaugeVec, Opts, Registry};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Signer balances below which the relayer alerts and pauses the new completions.
+/// A 0 threshold is disabled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BalanceThresholds {
+	pub warning: u128,
+	pub degraded: u128,
+}
+
+impl BalanceThresholds {
+	pub fn eth(config: &BalanceConfig) -> Self {
+		BalanceThresholds {
+			warning: config.eth_low_balance_warning,
+			degraded: config.eth_degraded_balance,
+		}
+	}
+
+	pub fn movement(config: &BalanceConfig) -> Self {
+		BalanceThresholds {
+			warning: config.movement_low_balance_warning,
+			degraded: config.movement_degraded_balance,
+		}
+	}
+
+	pub fn level(&self, balance: u128) -> BalanceLevel {
+		if balance < self.degraded {
+			BalanceLevel::Degraded
+		} else if balance < self.warning {
+			BalanceLevel::Low
+		} else {
+			BalanceLevel::Healthy
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceLevel {
+	Healthy,
+	/// Below the warning threshold, the signer must be topped up.
+	Low,
+	/// Below the degraded threshold, the new completions are paused.
+	Degraded,
+}
+
+/// Chains whose signer balance is too low to keep completing transfers.
+/// Shared between the balance monitoring and the bridge loop.
+#[derive(Debug, Clone, Default)]
+pub struct DegradedChains(Arc<[AtomicBool; 2]>);
+
+impl DegradedChains {
+	pub fn is_degraded(&self, chain: ChainId) -> bool {
+		self.0[chain_index(chain)].load(Ordering::Relaxed)
+	}
+
+	fn set(&self, chain: ChainId, degraded: bool) {
+		self.0[chain_index(chain)].store(degraded, Ordering::Relaxed);
+	}
+}
+
+fn chain_index(chain: ChainId) -> usize {
+	match chain {
+		ChainId::ONE => 0,
+		ChainId::TWO => 1,
+	}
+}
+
+/// Follows the balance of a chain signer and reports the threshold crossings.
+pub struct BalanceWatch {
+	chain: ChainId,
+	name: &'static str,
+	thresholds: BalanceThresholds,
+	level: BalanceLevel,
+}
+
+impl BalanceWatch {
+	pub fn new(chain: ChainId, name: &'static str, thresholds: BalanceThresholds) -> Self {
+		BalanceWatch { chain, name, thresholds, level: BalanceLevel::Healthy }
+	}
+
+	/// Update the level with the current balance, alerting when it changes.
+	pub fn observe(&mut self, balance: u128, degraded: &DegradedChains) -> BalanceLevel {
+		let level = self.thresholds.level(balance);
+		if level != self.level {
+			match level {
+				BalanceLevel::Healthy => {
+					tracing::info!("{} signer balance {balance} is healthy again", self.name)
+				}
+				BalanceLevel::Low => tracing::warn!(
+					"{} signer balance {balance} is below {}, top it up before the completions fail",
+					self.name,
+					self.thresholds.warning
+				),
+				BalanceLevel::Degraded => tracing::error!(
+					"{} signer balance {balance} is below {}, new completions are paused",
+					self.name,
+					self.thresholds.degraded
+				),
+			}
+			self.level = level;
+		}
+		degraded.set(self.chain, level == BalanceLevel::Degraded);
+		level
+	}
+}
+
+/// Holds the new completions of the degraded chains until their signer is topped up.
+pub(crate) struct BalanceGuard {
+	degraded: DegradedChains,
+	paused: Vec<TransferAction>,
+}
+
+impl BalanceGuard {
+	pub fn new(degraded: DegradedChains) -> Self {
+		BalanceGuard { degraded, paused: Vec::new() }
+	}
+
+	/// Return the action if it can be executed, else keep it until the chain recovers.
+	/// Refunds and nudges of the already completed transfers aren't paused.
+	pub fn hold(&mut self, action: TransferAction) -> Option<TransferAction> {
+		let completion = matches!(
+			action.kind,
+			TransferActionType::LockBridgeTransfer { .. }
+				| TransferActionType::WaitAndCompleteInitiator(..)
+		);
+		if completion && self.degraded.is_degraded(action.chain) {
+			tracing::warn!("Action {action} paused until the signer balance is topped up");
+			self.paused.push(action);
+			return None;
+		}
+		Some(action)
+	}
+
+	/// The paused actions whose chain isn't degraded anymore.
+	pub fn release(&mut self) -> Vec<TransferAction> {
+		let (released, paused) = self
+			.paused
+			.drain(..)
+			.partition(|action| !self.degraded.is_degraded(action.chain));
+		self.paused = paused;
+		released
+	}
+}
+
+/// Check the signer balances periodically: update the degraded chains and the balance gauge.
+pub async fn run_balance_monitoring(
+	one: impl ChainHealthCheck,
+	one_thresholds: BalanceThresholds,
+	two: impl ChainHealthCheck,
+	two_thresholds: BalanceThresholds,
+	degraded: DegradedChains,
+	registry: &Registry,
+	check_interval: Duration,
+) -> prometheus::Result<()> {
+	let signer_balance = GaugeVec::new(
+		Opts::new("bridge_signer_balance", "Gas token balance of the relayer signer"),
+		&["chain"],
+	)?;
+	registry.register(Box::new(signer_balance.clone()))?;
+
+	let mut watches = [
+		(
+			BalanceWatch::new(ChainId::ONE, one.chain_name(), one_thresholds),
+			&one as &dyn ChainHealthCheck,
+		),
+		(
+			BalanceWatch::new(ChainId::TWO, two.chain_name(), two_thresholds),
+			&two as &dyn ChainHealthCheck,
+		),
+	];
+	let mut interval = tokio::time::interval(check_interval);
+	loop {
+		interval.tick().await;
+		for (watch, chain) in watches.iter_mut() {
+			match chain.signer_balance().await {
+				Ok(balance) => {
+					signer_balance.with_label_values(&[chain.chain_name()]).set(balance as f64);
+					watch.observe(balance, &degraded);
+				}
+				Err(err) => {
+					tracing::warn!("Failed to read the {} signer balance:{err}", chain.chain_name())
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::types::{BridgeTransferId, HashLockPreImage};
+
+	#[test]
+	fn test_draining_balance_warns_then_degrades() {
+		let degraded = DegradedChains::default();
+		let mut watch = BalanceWatch::new(
+			ChainId::ONE,
+			"Eth",
+			BalanceThresholds { warning: 1_000, degraded: 100 },
+		);
+		let levels: Vec<_> = [5_000, 1_000, 999, 500, 100, 99, 0]
+			.into_iter()
+			.map(|balance| (balance, watch.observe(balance, &degraded)))
+			.collect();
+		assert_eq!(
+			levels,
+			vec![
+				(5_000, BalanceLevel::Healthy),
+				(1_000, BalanceLevel::Healthy),
+				(999, BalanceLevel::Low),
+				(500, BalanceLevel::Low),
+				(100, BalanceLevel::Low),
+				(99, BalanceLevel::Degraded),
+				(0, BalanceLevel::Degraded),
+			]
+		);
+		assert!(degraded.is_degraded(ChainId::ONE));
+		assert!(!degraded.is_degraded(ChainId::TWO));
+
+		// Topped up.
+		assert_eq!(watch.observe(10_000, &degraded), BalanceLevel::Healthy);
+		assert!(!degraded.is_degraded(ChainId::ONE));
+	}
+
+	#[test]
+	fn test_completions_are_paused_while_degraded() {
+		let degraded = DegradedChains::default();
+		let mut watch =
+			BalanceWatch::new(ChainId::ONE, "Eth", BalanceThresholds { warning: 0, degraded: 100 });
+		let mut guard = BalanceGuard::new(degraded.clone());
+		let action =
+			|chain, kind| TransferAction { chain, transfer_id: BridgeTransferId([1; 32]), kind };
+		let complete =
+			|| TransferActionType::WaitAndCompleteInitiator(0, HashLockPreImage([2; 32]));
+
+		watch.observe(10, &degraded);
+		assert!(guard.hold(action(ChainId::ONE, complete())).is_none());
+		// Refunds and the other chain aren't paused.
+		assert!(guard.hold(action(ChainId::ONE, TransferActionType::RefundInitiator)).is_some());
+		assert!(guard.hold(action(ChainId::TWO, complete())).is_some());
+		assert!(guard.release().is_empty());
+
+		watch.observe(1_000, &degraded);
+		let released = guard.release();
+		assert_eq!(released.len(), 1);
+		assert!(matches!(released[0].kind, TransferActionType::WaitAndCompleteInitiator(..)));
+		assert!(guard.release().is_empty());
+	}
+}
@@ -1,9 +1,11 @@
 use crate::types::LockDetails;
+use std::collections::HashSet;
 use thiserror::Error;
 use tokio_stream::Stream;
 
 use crate::types::{
-	Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferId, HashLock, HashLockPreImage,
+	Amount, AssetTag, BridgeAddress, BridgeHash, BridgeTransferDetails, BridgeTransferId, HashLock,
+	HashLockPreImage, TimeLock,
 };
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -48,12 +50,61 @@ pub enum BridgeContractError {
 	OnChainUnknownEvent,
 	#[error("Error during onchain call:{0}")]
 	OnChainError(String),
+	#[error("Transaction reverted:{0}")]
+	TransactionReverted(String),
+	#[error("Transfers of the fee-on-transfer token {0} are rejected")]
+	FeeOnTransferRejected(String),
+	#[error("Token {0} isn't locked by the bridge contract")]
+	UnsupportedToken(String),
+	#[error("Transfer {0} can't be refunded before its time lock {1} expires")]
+	RefundBeforeExpiry(BridgeTransferId, u64),
+	#[error("Rpc request timed out:{0}")]
+	RpcTimeout(String),
+	#[error("Transaction nonce too low:{0}")]
+	NonceTooLow(String),
+	#[error("Insufficient funds to send the transaction:{0}")]
+	InsufficientFunds(String),
+	#[error("The secret doesn't match the transfer hash lock")]
+	InvalidHashLock,
 }
 
 impl BridgeContractError {
 	pub fn generic<E: std::error::Error>(e: E) -> Self {
 		Self::GenericError(e.to_string())
 	}
+
+	/// Classify a client error from its message, the chain clients only report them as text.
+	pub fn from_message(message: impl Into<String>) -> Self {
+		let message = message.into();
+		let lowercase = message.to_lowercase();
+		let contains_any = |patterns: &[&str]| patterns.iter().any(|p| lowercase.contains(p));
+		// The hash lock check is a revert too, test it first.
+		if contains_any(&["invalidsecret", "ewrong_preimage", "ewrong_hashlock"]) {
+			Self::InvalidHashLock
+		} else if contains_any(&["timed out", "timeout", "deadline has elapsed"]) {
+			Self::RpcTimeout(message)
+		} else if contains_any(&["nonce too low", "sequence_number_too_old"]) {
+			Self::NonceTooLow(message)
+		} else if contains_any(&["insufficient funds", "insufficient_balance"]) {
+			Self::InsufficientFunds(message)
+		} else if contains_any(&["revert", "move abort"]) {
+			Self::TransactionReverted(message)
+		} else {
+			Self::GenericError(message)
+		}
+	}
+
+	/// Whether the same action can succeed if it's sent again.
+	/// Unclassified errors are retried too, the relayer bounds the retries.
+	pub fn is_retryable(&self) -> bool {
+		matches!(
+			self,
+			Self::RpcTimeout(_)
+				| Self::NonceTooLow(_)
+				| Self::OnChainError(_)
+				| Self::GenericError(_)
+		)
+	}
 }
 
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -78,10 +129,10 @@ pub type BridgeContractWETH9Result<T> = Result<T, BridgeContractWETH9Error>;
 pub enum BridgeContractEvent<A> {
 	Initiated(BridgeTransferDetails<A>),
 	Locked(LockDetails<A>),
-	InitialtorCompleted(BridgeTransferId),
-	CounterPartCompleted(BridgeTransferId, HashLockPreImage),
-	Cancelled(BridgeTransferId),
-	Refunded(BridgeTransferId),
+	InitialtorCompleted(BridgeTransferId, AssetTag),
+	CounterPartCompleted(BridgeTransferId, HashLockPreImage, AssetTag),
+	Cancelled(BridgeTransferId, AssetTag),
+	Refunded(BridgeTransferId, AssetTag),
 }
 
 impl<A> BridgeContractEvent<A> {
@@ -89,10 +140,33 @@ impl<A> BridgeContractEvent<A> {
 		match self {
 			Self::Initiated(details) => details.bridge_transfer_id,
 			Self::Locked(details) => details.bridge_transfer_id,
-			Self::InitialtorCompleted(id)
-			| Self::CounterPartCompleted(id, _)
-			| Self::Cancelled(id)
-			| Self::Refunded(id) => *id,
+			Self::InitialtorCompleted(id, _)
+			| Self::CounterPartCompleted(id, _, _)
+			| Self::Cancelled(id, _)
+			| Self::Refunded(id, _) => *id,
+		}
+	}
+
+	/// Asset the event refers to, so it's routed to the right asset mapping.
+	pub fn asset(&self) -> AssetTag {
+		match self {
+			Self::Initiated(details) => details.amount.tag(),
+			Self::Locked(details) => details.amount.tag(),
+			Self::InitialtorCompleted(_, asset)
+			| Self::CounterPartCompleted(_, _, asset)
+			| Self::Cancelled(_, asset)
+			| Self::Refunded(_, asset) => *asset,
+		}
+	}
+
+	pub fn event_type(&self) -> BridgeContractEventType {
+		match self {
+			Self::Initiated(_) => BridgeContractEventType::Initiated,
+			Self::Locked(_) => BridgeContractEventType::Locked,
+			Self::InitialtorCompleted(..) => BridgeContractEventType::InitialtorCompleted,
+			Self::CounterPartCompleted(..) => BridgeContractEventType::CounterPartCompleted,
+			Self::Cancelled(..) => BridgeContractEventType::Cancelled,
+			Self::Refunded(..) => BridgeContractEventType::Refunded,
 		}
 	}
 
@@ -105,10 +179,87 @@ impl<A> BridgeContractEvent<A> {
 	}
 }
 
+/// Kind of a [BridgeContractEvent], without its payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BridgeContractEventType {
+	Initiated,
+	Locked,
+	InitialtorCompleted,
+	CounterPartCompleted,
+	Cancelled,
+	Refunded,
+}
+
+/// Set of event types a monitoring emits.
+/// Event types outside the filter are not fetched nor decoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventFilter {
+	// None means every event type.
+	types: Option<HashSet<BridgeContractEventType>>,
+}
+
+impl EventFilter {
+	/// Emit every event type. Used by the relayer service.
+	pub fn all() -> Self {
+		EventFilter { types: None }
+	}
+
+	/// Emit only the given event types.
+	pub fn only(types: impl IntoIterator<Item = BridgeContractEventType>) -> Self {
+		EventFilter { types: Some(types.into_iter().collect()) }
+	}
+
+	pub fn accepts(&self, event_type: BridgeContractEventType) -> bool {
+		self.types.as_ref().map_or(true, |types| types.contains(&event_type))
+	}
+}
+
+impl Default for EventFilter {
+	fn default() -> Self {
+		EventFilter::all()
+	}
+}
+
+/// Transaction of a transfer call, so the caller can track the transfer without its events.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BridgeTransferResult {
+	pub transfer_id: BridgeTransferId,
+	pub tx_hash: BridgeHash,
+	/// Block number on Eth, ledger version on Movement.
+	pub block: u64,
+	pub gas_used: u64,
+}
+
+/// Fields of a transfer as stored by a bridge contract, with the addresses as on chain bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferStatusDetails {
+	pub initiator_address: Vec<u8>,
+	pub recipient_address: Vec<u8>,
+	pub hash_lock: HashLock,
+	pub time_lock: TimeLock,
+	pub amount: Amount,
+}
+
+/// On chain status of a transfer, initiated or locked on the queried chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BridgeTransferStatus {
+	Initiated(TransferStatusDetails),
+	Locked(TransferStatusDetails),
+	Completed(TransferStatusDetails),
+	/// Refunded to the initiator, or aborted on the counterparty.
+	Refunded(TransferStatusDetails),
+	/// Unknown to both the initiator and the counterparty contracts.
+	NotFound,
+}
+
 pub trait BridgeContractMonitoring:
 	Stream<Item = BridgeContractResult<BridgeContractEvent<Self::Address>>> + Unpin
 {
 	type Address;
+
+	/// Close the connections of the monitoring.
+	/// The stream ends once the events already received are consumed.
+	fn stop(&mut self);
 }
 
 #[async_trait::async_trait]
@@ -119,7 +270,7 @@ pub trait BridgeContract<A>: Clone + Unpin + Send + Sync {
 		recipient_address: BridgeAddress<Vec<u8>>,
 		hash_lock: HashLock,
 		amount: Amount,
-	) -> BridgeContractResult<()>;
+	) -> BridgeContractResult<BridgeTransferResult>;
 
 	async fn initiator_complete_bridge_transfer(
 		&mut self,
@@ -131,7 +282,16 @@ pub trait BridgeContract<A>: Clone + Unpin + Send + Sync {
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 		secret: HashLockPreImage,
-	) -> BridgeContractResult<()>;
+	) -> BridgeContractResult<BridgeTransferResult>;
+
+	/// Complete several transfers on the counterparty chain, in a single transaction when the
+	/// client supports it. Returns the result of each transfer, in order.
+	async fn counterparty_complete_bridge_transfers(
+		&mut self,
+		transfers: Vec<(BridgeTransferId, HashLockPreImage)>,
+	) -> Vec<BridgeContractResult<BridgeTransferResult>> {
+		counterparty_complete_individually(self, transfers).await
+	}
 
 	async fn refund_bridge_transfer(
 		&mut self,
@@ -148,6 +308,12 @@ pub trait BridgeContract<A>: Clone + Unpin + Send + Sync {
 		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<Option<BridgeTransferDetails<A>>>;
 
+	/// Status of the transfer read from the contract storage, initiator first.
+	async fn get_bridge_transfer_details(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<BridgeTransferStatus>;
+
 	async fn lock_bridge_transfer(
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
@@ -163,7 +329,60 @@ pub trait BridgeContract<A>: Clone + Unpin + Send + Sync {
 	) -> BridgeContractResult<()>;
 }
 
+/// Complete the transfers one transaction each, the fallback of a reverted batch.
+pub async fn counterparty_complete_individually<A, C: BridgeContract<A>>(
+	contract: &mut C,
+	transfers: Vec<(BridgeTransferId, HashLockPreImage)>,
+) -> Vec<BridgeContractResult<BridgeTransferResult>> {
+	let mut results = Vec::with_capacity(transfers.len());
+	for (bridge_transfer_id, secret) in transfers {
+		let result =
+			contract.counterparty_complete_bridge_transfer(bridge_transfer_id, secret).await;
+		results.push(result);
+	}
+	results
+}
+
 #[async_trait::async_trait]
 pub trait BridgeContractWETH9: Clone + Unpin + Send + Sync {
 	async fn deposit_weth(&mut self, amount: Amount) -> BridgeContractWETH9Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_client_errors_are_classified() {
+		let timeout =
+			BridgeContractError::from_message("Failed in getting chain id: request timed out");
+		assert!(matches!(timeout, BridgeContractError::RpcTimeout(_)));
+		assert!(timeout.is_retryable());
+
+		let nonce = BridgeContractError::from_message(
+			"Transaction submission error: SEQUENCE_NUMBER_TOO_OLD",
+		);
+		assert!(matches!(nonce, BridgeContractError::NonceTooLow(_)));
+		assert!(nonce.is_retryable());
+
+		let funds = BridgeContractError::from_message(
+			"server returned an error response: error code -32000: insufficient funds for gas * price + value",
+		);
+		assert!(matches!(funds, BridgeContractError::InsufficientFunds(_)));
+		assert!(!funds.is_retryable());
+
+		let revert = BridgeContractError::from_message("execution reverted");
+		assert_eq!(revert, BridgeContractError::TransactionReverted("execution reverted".into()));
+		assert!(!revert.is_retryable());
+
+		let hash_lock = BridgeContractError::from_message(
+			"Move abort in 0x1::atomic_bridge_counterparty: EWRONG_PREIMAGE(0x2)",
+		);
+		assert_eq!(hash_lock, BridgeContractError::InvalidHashLock);
+		assert!(!hash_lock.is_retryable());
+
+		let unknown = BridgeContractError::from_message("connection reset by peer");
+		assert!(matches!(unknown, BridgeContractError::GenericError(_)));
+		assert!(unknown.is_retryable());
+	}
+}
@@ -1,23 +1,40 @@
+use super::fee_on_transfer::FeeOnTransferGuard;
+use super::gas_price::GasPricing;
 use super::types::{
 	AlloyProvider, AtomicBridgeCounterparty, AtomicBridgeInitiator, CounterpartyContract,
 	EthAddress, InitiatorContract, WETH9Contract, WETH9,
 };
-use super::utils::{calculate_storage_slot, send_transaction, send_transaction_rules};
+use super::utils::{
+	calculate_storage_slot, receipt_event, receipt_has_event, send_transaction,
+	send_transaction_rules, transfer_result, EthUtilError,
+};
+use crate::chains::bridge_contracts::counterparty_complete_individually;
 use crate::chains::bridge_contracts::BridgeContractError;
 use crate::chains::bridge_contracts::BridgeContractResult;
+use crate::chains::bridge_contracts::BridgeTransferResult;
+use crate::chains::bridge_contracts::{BridgeTransferStatus, TransferStatusDetails};
+use crate::chains::rpc_headers::{rpc_headers, rpc_http_client};
+use crate::self_check::ChainHealthCheck;
 use crate::types::{
 	Amount, AssetType, BridgeAddress, BridgeTransferDetails, BridgeTransferId, HashLock,
 	HashLockPreImage, TimeLock,
 };
+use alloy::eips::BlockNumberOrTag;
 use alloy::primitives::{Address, FixedBytes, U256};
 use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::client::RpcClient;
 use alloy::signers::local::PrivateKeySigner;
+use alloy::sol_types::SolError;
+use alloy::transports::http::Http;
+use alloy::transports::utils::guess_local_url;
+use alloy::transports::RpcError;
 use alloy::{
 	network::EthereumWallet,
 	rlp::{RlpDecodable, RlpEncodable},
 };
 use alloy_rlp::Decodable;
 use bridge_config::common::eth::EthConfig;
+use reqwest::header::HeaderMap;
 use std::fmt::{self, Debug};
 use url::Url;
 
@@ -32,12 +49,16 @@ impl fmt::Debug for AtomicBridgeInitiator::wethReturn {
 #[derive(Clone, Debug)]
 pub struct Config {
 	pub rpc_url: Url,
+	/// Headers sent with every RPC request.
+	pub rpc_headers: HeaderMap,
 	pub signer_private_key: PrivateKeySigner,
 	pub initiator_contract: Address,
 	pub counterparty_contract: Address,
 	pub weth_contract: Address,
 	pub gas_limit: u128,
 	pub transaction_send_retries: u32,
+	pub gas_pricing: GasPricing,
+	pub fee_on_transfer: FeeOnTransferGuard,
 }
 impl TryFrom<&EthConfig> for Config {
 	type Error = anyhow::Error;
@@ -48,16 +69,34 @@ impl TryFrom<&EthConfig> for Config {
 
 		Ok(Config {
 			rpc_url,
+			rpc_headers: rpc_headers(&conf.eth_rpc_headers, &conf.eth_rpc_bearer_token)?,
 			signer_private_key,
 			initiator_contract: conf.eth_initiator_contract.parse()?,
 			counterparty_contract: conf.eth_counterparty_contract.parse()?,
 			weth_contract: conf.eth_weth_contract.parse()?,
 			gas_limit: conf.gas_limit.into(),
 			transaction_send_retries: conf.transaction_send_retries,
+			gas_pricing: GasPricing::try_from(conf)?,
+			fee_on_transfer: FeeOnTransferGuard::new(&conf.eth_fee_on_transfer_tokens)?,
 		})
 	}
 }
 
+/// Build the provider signing with the configured key.
+/// The RPC requests carry the configured headers, if any.
+pub async fn rpc_provider(config: &Config) -> Result<AlloyProvider, anyhow::Error> {
+	let builder = ProviderBuilder::new()
+		.with_recommended_fillers()
+		.wallet(EthereumWallet::from(config.signer_private_key.clone()));
+	if config.rpc_headers.is_empty() {
+		return Ok(builder.on_builtin(config.rpc_url.as_str()).await?);
+	}
+	let http =
+		Http::with_client(rpc_http_client(config.rpc_headers.clone())?, config.rpc_url.clone());
+	let rpc_client = RpcClient::new(http, guess_local_url(config.rpc_url.as_str())).boxed();
+	Ok(builder.on_client(rpc_client))
+}
+
 #[derive(RlpDecodable, RlpEncodable)]
 struct EthBridgeTransferDetails {
 	pub amount: U256,
@@ -85,11 +124,7 @@ pub struct EthClient {
 impl EthClient {
 	pub async fn new(config: &EthConfig) -> Result<Self, anyhow::Error> {
 		let config: Config = config.try_into()?;
-		let rpc_provider = ProviderBuilder::new()
-			.with_recommended_fillers()
-			.wallet(EthereumWallet::from(config.signer_private_key.clone()))
-			.on_builtin(config.rpc_url.as_str())
-			.await?;
+		let rpc_provider = rpc_provider(&config).await?;
 
 		//load smart contract
 		let initiator_contract =
@@ -126,6 +161,7 @@ impl EthClient {
 			&send_transaction_rules(),
 			self.config.transaction_send_retries,
 			self.config.gas_limit,
+			&self.config.gas_pricing,
 		)
 		.await?;
 		Ok(())
@@ -143,6 +179,7 @@ impl EthClient {
 			&send_transaction_rules(),
 			self.config.transaction_send_retries,
 			self.config.gas_limit,
+			&self.config.gas_pricing,
 		)
 		.await?;
 
@@ -156,6 +193,7 @@ impl EthClient {
 			&send_transaction_rules(),
 			self.config.transaction_send_retries,
 			self.config.gas_limit,
+			&self.config.gas_pricing,
 		)
 		.await?;
 		Ok(())
@@ -197,6 +235,44 @@ impl EthClient {
 	}
 }
 
+#[async_trait::async_trait]
+impl ChainHealthCheck for EthClient {
+	fn chain_name(&self) -> &'static str {
+		"Eth"
+	}
+
+	async fn check_rpc(&self) -> Result<(), String> {
+		self.get_block_number().await.map(|_| ()).map_err(|err| err.to_string())
+	}
+
+	fn contracts(&self) -> Vec<(&'static str, String)> {
+		vec![
+			("initiator", self.initiator_contract_address().to_string()),
+			("counterparty", self.counterparty_contract_address().to_string()),
+			("weth", self.weth_contract_address().to_string()),
+		]
+	}
+
+	async fn has_code(&self, address: &str) -> Result<bool, String> {
+		let address: Address = address.parse().map_err(|err| format!("{err}"))?;
+		let code = self.rpc_provider.get_code_at(address).await.map_err(|err| err.to_string())?;
+		Ok(!code.is_empty())
+	}
+
+	fn signer_address(&self) -> String {
+		self.get_signer_address().to_string()
+	}
+
+	async fn signer_balance(&self) -> Result<u128, String> {
+		let balance = self
+			.rpc_provider
+			.get_balance(self.get_signer_address())
+			.await
+			.map_err(|err| err.to_string())?;
+		Ok(balance.try_into().unwrap_or(u128::MAX))
+	}
+}
+
 #[async_trait::async_trait]
 impl crate::chains::bridge_contracts::BridgeContract<EthAddress> for EthClient {
 	// `_initiator_address`, or in the contract, `originator` is set
@@ -208,7 +284,15 @@ impl crate::chains::bridge_contracts::BridgeContract<EthAddress> for EthClient {
 		recipient_address: BridgeAddress<Vec<u8>>,
 		hash_lock: HashLock,
 		amount: Amount, // the ETH amount
-	) -> BridgeContractResult<()> {
+	) -> BridgeContractResult<BridgeTransferResult> {
+		// The initiator contract locks the token it was deployed with.
+		let token = self.config.weth_contract;
+		if let Some(token_address) = amount.token_address() {
+			if token_address.0 != token {
+				return Err(BridgeContractError::UnsupportedToken(token_address.0.to_string()));
+			}
+		}
+		self.config.fee_on_transfer.check(token, amount)?;
 		let contract =
 			AtomicBridgeInitiator::new(self.initiator_contract_address(), &self.rpc_provider);
 		let recipient_bytes: [u8; 32] = recipient_address.0.try_into().map_err(|e| {
@@ -218,23 +302,25 @@ impl crate::chains::bridge_contracts::BridgeContract<EthAddress> for EthClient {
 		})?;
 		let call = contract
 			.initiateBridgeTransfer(
-				U256::from(amount.weth_value()),
+				U256::from(amount.token_value()),
 				FixedBytes(recipient_bytes),
 				FixedBytes(hash_lock.0),
 			)
 			.value(U256::from(amount.eth_value()))
 			.from(*initiator_address.0);
-		let _ = send_transaction(
+		let receipt = send_transaction(
 			call,
 			&send_transaction_rules(),
 			self.config.transaction_send_retries,
 			self.config.gas_limit,
+			&self.config.gas_pricing,
 		)
 		.await
-		.map_err(|e| {
-			BridgeContractError::GenericError(format!("Failed to send transaction: {}", e))
-		})?;
-		Ok(())
+		.map_err(send_error)?;
+		// The contract derives the transfer id, it's only known from the event.
+		let initiated = receipt_event::<AtomicBridgeInitiator::BridgeTransferInitiated>(&receipt)
+			.ok_or(BridgeContractError::TransferIdExtractionError)?;
+		Ok(transfer_result(BridgeTransferId(*initiated._bridgeTransferId), &receipt))
 	}
 
 	async fn initiator_complete_bridge_transfer(
@@ -256,16 +342,22 @@ impl crate::chains::bridge_contracts::BridgeContract<EthAddress> for EthClient {
 			AtomicBridgeInitiator::new(self.initiator_contract_address(), &self.rpc_provider);
 		let call = contract
 			.completeBridgeTransfer(FixedBytes(bridge_transfer_id.0), FixedBytes(pre_image));
-		send_transaction(
+		let receipt = send_transaction(
 			call,
 			&send_transaction_rules(),
 			self.config.transaction_send_retries,
 			self.config.gas_limit,
+			&self.config.gas_pricing,
 		)
 		.await
-		.map_err(|e| {
-			BridgeContractError::GenericError(format!("Failed to send transaction: {}", e))
-		})?;
+		.map_err(send_error)?;
+		// A successful receipt without the completion event means the transfer isn't completed.
+		if !receipt_has_event::<AtomicBridgeInitiator::BridgeTransferCompleted>(&receipt) {
+			tracing::warn!(
+				"Completion of transfer:{bridge_transfer_id} succeeded without a completed event, receipt:{receipt:?}"
+			);
+			return Err(BridgeContractError::CompleteTransferError);
+		}
 		Ok(())
 	}
 
@@ -273,7 +365,7 @@ impl crate::chains::bridge_contracts::BridgeContract<EthAddress> for EthClient {
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 		pre_image: HashLockPreImage,
-	) -> BridgeContractResult<()> {
+	) -> BridgeContractResult<BridgeTransferResult> {
 		// The Alloy generated type for smart contract`pre_image` arg is `FixedBytes<32>`
 		// so it must be converted to `[u8; 32]`.
 		let generic_error = |desc| BridgeContractError::GenericError(String::from(desc));
@@ -285,20 +377,58 @@ impl crate::chains::bridge_contracts::BridgeContract<EthAddress> for EthClient {
 			.map_err(|_| generic_error("Could not convert pre-image to [u8; 32]"))?;
 
 		let contract =
-			AtomicBridgeInitiator::new(self.initiator_contract_address(), &self.rpc_provider);
+			AtomicBridgeCounterparty::new(self.counterparty_contract_address(), &self.rpc_provider);
 		let call = contract
 			.completeBridgeTransfer(FixedBytes(bridge_transfer_id.0), FixedBytes(pre_image));
-		send_transaction(
+		let receipt = send_transaction(
 			call,
 			&send_transaction_rules(),
 			self.config.transaction_send_retries,
 			self.config.gas_limit,
+			&self.config.gas_pricing,
 		)
 		.await
-		.map_err(|e| {
-			BridgeContractError::GenericError(format!("Failed to send transaction: {}", e))
-		})?;
-		Ok(())
+		.map_err(send_error)?;
+		Ok(transfer_result(bridge_transfer_id, &receipt))
+	}
+
+	async fn counterparty_complete_bridge_transfers(
+		&mut self,
+		transfers: Vec<(BridgeTransferId, HashLockPreImage)>,
+	) -> Vec<BridgeContractResult<BridgeTransferResult>> {
+		if transfers.len() < 2 {
+			return counterparty_complete_individually(self, transfers).await;
+		}
+		let contract =
+			AtomicBridgeCounterparty::new(self.counterparty_contract_address(), &self.rpc_provider);
+		let (bridge_transfer_ids, pre_images) = transfers
+			.iter()
+			.map(|(bridge_transfer_id, pre_image)| {
+				(FixedBytes(bridge_transfer_id.0), FixedBytes(pre_image.0))
+			})
+			.unzip();
+		let call = contract.batchCompleteBridgeTransfer(bridge_transfer_ids, pre_images);
+		match send_transaction(
+			call,
+			&send_transaction_rules(),
+			self.config.transaction_send_retries,
+			self.config.gas_limit,
+			&self.config.gas_pricing,
+		)
+		.await
+		{
+			Ok(receipt) => transfers
+				.iter()
+				.map(|(bridge_transfer_id, _)| Ok(transfer_result(*bridge_transfer_id, &receipt)))
+				.collect(),
+			Err(e) => {
+				tracing::warn!(
+					"Batch completion of {} transfers failed, completing them one by one: {e}",
+					transfers.len()
+				);
+				counterparty_complete_individually(self, transfers).await
+			}
+		}
 	}
 
 	async fn refund_bridge_transfer(
@@ -307,17 +437,33 @@ impl crate::chains::bridge_contracts::BridgeContract<EthAddress> for EthClient {
 	) -> BridgeContractResult<()> {
 		let contract =
 			AtomicBridgeInitiator::new(self.initiator_contract_address(), &self.rpc_provider);
+		// The contract reverts the refunds until the latest block reaches the time lock.
+		let transfer = contract
+			.bridgeTransfers(FixedBytes(bridge_transfer_id.0))
+			.call()
+			.await
+			.map_err(|e| BridgeContractError::OnChainError(e.to_string()))?;
+		let time_lock = transfer.timeLock.saturating_to::<u64>();
+		let latest_block = self
+			.rpc_provider
+			.get_block_by_number(BlockNumberOrTag::Latest, false)
+			.await
+			.map_err(|e| BridgeContractError::OnChainError(e.to_string()))?
+			.ok_or(BridgeContractError::GenericError("Latest block not found".to_string()))?;
+		if latest_block.header.timestamp < time_lock {
+			return Err(BridgeContractError::RefundBeforeExpiry(bridge_transfer_id, time_lock));
+		}
+
 		let call = contract.refundBridgeTransfer(FixedBytes(bridge_transfer_id.0));
 		send_transaction(
 			call,
 			&send_transaction_rules(),
 			self.config.transaction_send_retries,
 			self.config.gas_limit,
+			&self.config.gas_pricing,
 		)
 		.await
-		.map_err(|e| {
-			BridgeContractError::GenericError(format!("Failed to send transaction: {}", e))
-		})?;
+		.map_err(send_error)?;
 		Ok(())
 	}
 
@@ -345,11 +491,10 @@ impl crate::chains::bridge_contracts::BridgeContract<EthAddress> for EthClient {
 			&send_transaction_rules(),
 			self.config.transaction_send_retries,
 			self.config.gas_limit,
+			&self.config.gas_pricing,
 		)
 		.await
-		.map_err(|e| {
-			BridgeContractError::GenericError(format!("Failed to send transaction: {}", e))
-		})?;
+		.map_err(send_error)?;
 		Ok(())
 	}
 
@@ -365,11 +510,10 @@ impl crate::chains::bridge_contracts::BridgeContract<EthAddress> for EthClient {
 			&send_transaction_rules(),
 			self.config.transaction_send_retries,
 			self.config.gas_limit,
+			&self.config.gas_pricing,
 		)
 		.await
-		.map_err(|e| {
-			BridgeContractError::GenericError(format!("Failed to send transaction: {}", e))
-		})?;
+		.map_err(send_error)?;
 		Ok(())
 	}
 
@@ -406,6 +550,49 @@ impl crate::chains::bridge_contracts::BridgeContract<EthAddress> for EthClient {
 		}))
 	}
 
+	async fn get_bridge_transfer_details(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<BridgeTransferStatus> {
+		// Unknown ids read as zeroed transfers, and every transfer has a hash lock.
+		let initiator =
+			AtomicBridgeInitiator::new(self.initiator_contract_address(), &self.rpc_provider);
+		let transfer = initiator
+			.bridgeTransfers(FixedBytes(bridge_transfer_id.0))
+			.call()
+			.await
+			.map_err(|e| BridgeContractError::OnChainError(e.to_string()))?;
+		if !transfer.hashLock.is_zero() {
+			let details = TransferStatusDetails {
+				initiator_address: transfer.originator.to_vec(),
+				recipient_address: transfer.recipient.to_vec(),
+				hash_lock: HashLock(transfer.hashLock.0),
+				time_lock: TimeLock(transfer.timeLock.saturating_to::<u64>()),
+				amount: Amount(AssetType::EthAndWeth((0, transfer.amount.saturating_to::<u64>()))),
+			};
+			return Ok(transfer_status(transfer.state, details, BridgeTransferStatus::Initiated));
+		}
+
+		let counterparty =
+			AtomicBridgeCounterparty::new(self.counterparty_contract_address(), &self.rpc_provider);
+		let transfer = counterparty
+			.bridgeTransfers(FixedBytes(bridge_transfer_id.0))
+			.call()
+			.await
+			.map_err(|e| BridgeContractError::OnChainError(e.to_string()))?;
+		if transfer.hashLock.is_zero() {
+			return Ok(BridgeTransferStatus::NotFound);
+		}
+		let details = TransferStatusDetails {
+			initiator_address: transfer.originator.to_vec(),
+			recipient_address: transfer.recipient.to_vec(),
+			hash_lock: HashLock(transfer.hashLock.0),
+			time_lock: TimeLock(transfer.timeLock.saturating_to::<u64>()),
+			amount: Amount(AssetType::EthAndWeth((0, transfer.amount.saturating_to::<u64>()))),
+		};
+		Ok(transfer_status(transfer.state, details, BridgeTransferStatus::Locked))
+	}
+
 	async fn get_bridge_transfer_details_counterparty(
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
@@ -440,6 +627,40 @@ impl crate::chains::bridge_contracts::BridgeContract<EthAddress> for EthClient {
 	}
 }
 
+/// Map the error of a sent transaction, the contracts revert with `InvalidSecret` on a wrong
+/// secret.
+fn send_error(err: anyhow::Error) -> BridgeContractError {
+	let contract_error = match err.downcast_ref::<EthUtilError>() {
+		Some(EthUtilError::TransactionReverted(reason)) => {
+			return BridgeContractError::TransactionReverted(reason.clone())
+		}
+		Some(EthUtilError::SendTxError(contract_error)) => Some(contract_error),
+		_ => err.downcast_ref::<alloy::contract::Error>(),
+	};
+	if let Some(alloy::contract::Error::TransportError(RpcError::ErrorResp(payload))) =
+		contract_error
+	{
+		let invalid_secret = hex::encode(AtomicBridgeInitiator::InvalidSecret::SELECTOR);
+		if payload.data.as_ref().is_some_and(|data| data.get().contains(&invalid_secret)) {
+			return BridgeContractError::InvalidHashLock;
+		}
+	}
+	BridgeContractError::from_message(format!("Failed to send transaction: {err}"))
+}
+
+/// Status of a transfer from the `MessageState` of the contracts, `pending` for its first state.
+fn transfer_status(
+	state: u8,
+	details: TransferStatusDetails,
+	pending: fn(TransferStatusDetails) -> BridgeTransferStatus,
+) -> BridgeTransferStatus {
+	match state {
+		1 => BridgeTransferStatus::Completed(details),
+		2 => BridgeTransferStatus::Refunded(details),
+		_ => pending(details),
+	}
+}
+
 #[cfg(test)]
 fn test_wrapping_to(a: &U256, b: u64) {
 	assert_eq!(a.wrapping_to::<u64>(), b);
@@ -448,7 +669,183 @@ fn test_wrapping_to(a: &U256, b: u64) {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::chains::bridge_contracts::BridgeContract;
+	use crate::chains::rpc_headers::tests::mock_endpoint;
 	use std::time::{SystemTime, UNIX_EPOCH};
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+	use tokio::net::TcpListener;
+
+	fn mock_config(url: &str, rpc_headers: HeaderMap) -> Config {
+		Config {
+			rpc_url: url.parse().unwrap(),
+			rpc_headers,
+			signer_private_key: PrivateKeySigner::random(),
+			initiator_contract: Address::ZERO,
+			counterparty_contract: Address::ZERO,
+			weth_contract: Address::ZERO,
+			gas_limit: 0,
+			transaction_send_retries: 0,
+			gas_pricing: GasPricing::default(),
+			fee_on_transfer: FeeOnTransferGuard::default(),
+		}
+	}
+
+	async fn mock_client(config: Config) -> EthClient {
+		let rpc_provider = rpc_provider(&config).await.unwrap();
+		EthClient {
+			initiator_contract: AtomicBridgeInitiator::new(
+				config.initiator_contract,
+				rpc_provider.clone(),
+			),
+			counterparty_contract: CounterpartyContract::new(
+				config.counterparty_contract,
+				rpc_provider.clone(),
+			),
+			weth_contract: WETH9Contract::new(config.weth_contract, rpc_provider.clone()),
+			rpc_provider,
+			config,
+		}
+	}
+
+	/// Client whose first RPC request is answered with `error`.
+	async fn failing_client(error: &'static str) -> EthClient {
+		let (url, _) = mock_endpoint(error).await;
+		mock_client(mock_config(&url, HeaderMap::new())).await
+	}
+
+	/// Node reverting every RPC request, the received requests are sent to the returned channel.
+	async fn reverting_node() -> (String, tokio::sync::mpsc::UnboundedReceiver<String>) {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = format!("http://{}", listener.local_addr().unwrap());
+		let (sender, requests) = tokio::sync::mpsc::unbounded_channel();
+		tokio::spawn(async move {
+			while let Ok((mut socket, _)) = listener.accept().await {
+				let mut buf = vec![0; 64 * 1024];
+				let len = socket.read(&mut buf).await.unwrap();
+				let request = String::from_utf8_lossy(&buf[..len]).to_lowercase();
+				let id = request
+					.split_once(r#""id":"#)
+					.and_then(|(_, id)| id.split(|c: char| !c.is_ascii_digit()).next())
+					.unwrap_or("0")
+					.to_string();
+				let body = format!(
+					r#"{{"jsonrpc":"2.0","id":{id},"error":{{"code":3,"message":"execution reverted","data":"0x"}}}}"#
+				);
+				let response = format!(
+					"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+					body.len()
+				);
+				socket.write_all(response.as_bytes()).await.unwrap();
+				let _ = sender.send(request);
+			}
+		});
+		(url, requests)
+	}
+
+	#[tokio::test]
+	async fn test_rpc_requests_carry_the_configured_headers() {
+		let (url, request) = mock_endpoint(r#"{"jsonrpc":"2.0","id":0,"result":"0x2a"}"#).await;
+		let headers = [("X-Api-Key".to_string(), "secret-key".to_string())].into();
+		let config = mock_config(&url, rpc_headers(&headers, "token").unwrap());
+
+		let provider = rpc_provider(&config).await.unwrap();
+		assert_eq!(provider.get_block_number().await.unwrap(), 42);
+		let request = request.await.unwrap();
+		assert!(request.contains("x-api-key: secret-key"), "{request}");
+		assert!(request.contains("authorization: bearer token"), "{request}");
+	}
+
+	#[tokio::test]
+	async fn test_reverted_transaction_is_classified() {
+		let mut client = failing_client(
+			r#"{"jsonrpc":"2.0","id":0,"error":{"code":3,"message":"execution reverted","data":"0x"}}"#,
+		)
+		.await;
+		let err = client.abort_bridge_transfer(BridgeTransferId([1; 32])).await.unwrap_err();
+		assert!(matches!(err, BridgeContractError::TransactionReverted(_)), "{err:?}");
+		assert!(!err.is_retryable());
+
+		// `InvalidSecret()` selector.
+		let mut client = failing_client(
+			r#"{"jsonrpc":"2.0","id":0,"error":{"code":3,"message":"execution reverted","data":"0xabab6bd7"}}"#,
+		)
+		.await;
+		let err = client
+			.initiator_complete_bridge_transfer(
+				BridgeTransferId([1; 32]),
+				HashLockPreImage([2; 32]),
+			)
+			.await
+			.unwrap_err();
+		assert_eq!(err, BridgeContractError::InvalidHashLock);
+	}
+
+	#[tokio::test]
+	async fn test_reverted_batch_completes_the_transfers_on_the_counterparty() {
+		let (url, mut requests) = reverting_node().await;
+		let counterparty = Address::repeat_byte(0xcc);
+		let config = Config {
+			initiator_contract: Address::repeat_byte(0xaa),
+			counterparty_contract: counterparty,
+			..mock_config(&url, HeaderMap::new())
+		};
+		let mut client = mock_client(config).await;
+
+		let transfers = vec![
+			(BridgeTransferId([1; 32]), HashLockPreImage([2; 32])),
+			(BridgeTransferId([3; 32]), HashLockPreImage([4; 32])),
+		];
+		let results = client.counterparty_complete_bridge_transfers(transfers).await;
+		assert_eq!(results.len(), 2);
+		for result in results {
+			assert!(
+				matches!(result, Err(BridgeContractError::TransactionReverted(_))),
+				"{result:?}"
+			);
+		}
+
+		// The batch is tried first, then each transfer is completed on its own.
+		let mut calls = vec![];
+		while let Ok(request) = requests.try_recv() {
+			calls.push(request);
+		}
+		let batch_complete = "0x320ddbbf";
+		let complete = "0x9f8f8799";
+		assert_eq!(calls.len(), 3, "{calls:?}");
+		assert!(calls[0].contains(batch_complete), "{}", calls[0]);
+		assert!(calls[1].contains(&format!("{complete}{}", hex::encode([1; 32]))), "{}", calls[1]);
+		assert!(calls[2].contains(&format!("{complete}{}", hex::encode([3; 32]))), "{}", calls[2]);
+		for call in calls {
+			assert!(call.contains(&format!(r#""to":"{counterparty:#x}""#)), "{call}");
+		}
+	}
+
+	#[tokio::test]
+	async fn test_timed_out_transaction_is_classified() {
+		let mut client = failing_client(
+			r#"{"jsonrpc":"2.0","id":0,"error":{"code":-32002,"message":"request timed out"}}"#,
+		)
+		.await;
+		let err = client.abort_bridge_transfer(BridgeTransferId([1; 32])).await.unwrap_err();
+		assert!(matches!(err, BridgeContractError::RpcTimeout(_)), "{err:?}");
+		assert!(err.is_retryable());
+	}
+
+	#[test]
+	fn test_transfer_states_use_the_solidity_encoding() {
+		let details = TransferStatusDetails {
+			initiator_address: vec![1; 20],
+			recipient_address: vec![2; 32],
+			hash_lock: HashLock([3; 32]),
+			time_lock: TimeLock(100),
+			amount: Amount(AssetType::EthAndWeth((0, 10))),
+		};
+		let status =
+			|state| transfer_status(state, details.clone(), BridgeTransferStatus::Initiated);
+		assert_eq!(status(0), BridgeTransferStatus::Initiated(details.clone()));
+		assert_eq!(status(1), BridgeTransferStatus::Completed(details.clone()));
+		assert_eq!(status(2), BridgeTransferStatus::Refunded(details.clone()));
+	}
 
 	#[test]
 	fn test_wrapping_to_on_eth_details() {
@@ -1,33 +1,99 @@
 use super::types::EthAddress;
 use crate::chains::bridge_contracts::BridgeContractError;
 use crate::chains::bridge_contracts::BridgeContractEvent;
+use crate::chains::bridge_contracts::BridgeContractEventType;
 use crate::chains::bridge_contracts::BridgeContractMonitoring;
 use crate::chains::bridge_contracts::BridgeContractResult;
+use crate::chains::bridge_contracts::EventFilter;
+use crate::chains::ethereum::client::rpc_provider;
+use crate::chains::ethereum::event_source::{
+	contract_event_stream, EventSource, ReconnectPolicy, WsConnection,
+};
+use crate::chains::ethereum::types::AlloyProvider;
 use crate::chains::ethereum::types::AtomicBridgeCounterparty;
 use crate::chains::ethereum::types::AtomicBridgeInitiator;
+use crate::checkpoint::{CheckpointCadence, CheckpointFile, CheckpointTracker, InFlightEvents};
+use crate::confirmation::event_amount;
+use crate::confirmation::ConfirmationGate;
+use crate::confirmation::ConfirmationPolicy;
+use crate::dedup::EventDedup;
+use crate::metrics::RelayerMetrics;
+use crate::types::AssetTag;
 use crate::types::HashLockPreImage;
 use crate::types::LockDetails;
 use crate::types::{BridgeAddress, BridgeTransferDetails, BridgeTransferId, HashLock};
+use crate::unix_now;
 use alloy::eips::BlockNumberOrTag;
 use alloy::primitives::Address;
-use alloy::providers::ProviderBuilder;
-use alloy_network::EthereumWallet;
+use alloy::providers::Provider;
 use bridge_config::common::eth::EthConfig;
+use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use futures::SinkExt;
-use futures::{channel::mpsc::UnboundedReceiver, Stream, StreamExt};
-use std::{pin::Pin, task::Poll};
+use futures::{Stream, StreamExt};
+use std::{pin::Pin, task::Poll, time::Duration};
 use tokio::select;
+use tokio::task::JoinHandle;
+
+/// How often the chain head is checked while events wait for confirmations.
+const CONFIRMATION_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+/// How often the checkpoint is advanced with the chain head, besides after each processed event.
+const CHECKPOINT_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+/// Number of the last delivered events remembered to drop their duplicates.
+const EVENT_DEDUP_CAPACITY: usize = 4096;
 
 pub struct EthMonitoring {
 	listener: UnboundedReceiver<BridgeContractResult<BridgeContractEvent<EthAddress>>>,
+	// Tells the forwarding task each time the relayer takes an event.
+	taken: UnboundedSender<()>,
+	// Forwarding task, it owns the providers and the log streams.
+	task: JoinHandle<()>,
+	metrics: Option<RelayerMetrics>,
 }
 
 impl BridgeContractMonitoring for EthMonitoring {
 	type Address = EthAddress;
+
+	fn stop(&mut self) {
+		// Dropping the task drops the log streams and their connections.
+		self.task.abort();
+		self.listener.close();
+	}
 }
 
 impl EthMonitoring {
 	pub async fn build(config: &EthConfig) -> Result<Self, anyhow::Error> {
+		EthMonitoring::build_with_filter(config, EventFilter::all()).await
+	}
+
+	/// Build a monitoring replaying the events from `start_block`, instead of the block after
+	/// the checkpoint.
+	pub async fn build_from(config: &EthConfig, start_block: u64) -> Result<Self, anyhow::Error> {
+		EthMonitoring::build_monitoring(config, EventFilter::all(), Some(start_block), None).await
+	}
+
+	/// Build a monitoring counting the observed events and the WebSocket reconnections
+	/// in `metrics`.
+	pub async fn build_with_metrics(
+		config: &EthConfig,
+		metrics: RelayerMetrics,
+	) -> Result<Self, anyhow::Error> {
+		EthMonitoring::build_monitoring(config, EventFilter::all(), None, Some(metrics)).await
+	}
+
+	/// Build a monitoring that only subscribes to the event types accepted by `filter`.
+	pub async fn build_with_filter(
+		config: &EthConfig,
+		filter: EventFilter,
+	) -> Result<Self, anyhow::Error> {
+		EthMonitoring::build_monitoring(config, filter, None, None).await
+	}
+
+	async fn build_monitoring(
+		config: &EthConfig,
+		filter: EventFilter,
+		start_block: Option<u64>,
+		metrics: Option<RelayerMetrics>,
+	) -> Result<Self, anyhow::Error> {
 		// let rpc_url = config.eth_ws_connection_url();
 		// let ws = WsConnect::new(rpc_url);
 		// let ws = ProviderBuilder::new().on_ws(ws).await?;
@@ -35,20 +101,77 @@ impl EthMonitoring {
 		// 	AtomicBridgeInitiator::new(config.eth_initiator_contract.parse()?, ws.clone());
 
 		let client_config: crate::chains::ethereum::client::Config = config.try_into()?;
-		let rpc_provider = ProviderBuilder::new()
-			.with_recommended_fillers()
-			.wallet(EthereumWallet::from(client_config.signer_private_key.clone()))
-			.on_builtin(client_config.rpc_url.as_str())
-			.await?;
+		let rpc_provider = rpc_provider(&client_config).await?;
 		let initiator_contract = AtomicBridgeInitiator::new(
 			config.eth_initiator_contract.parse()?,
 			rpc_provider.clone(),
 		);
 
+		let asset: AssetTag = config.eth_asset.parse()?;
+		// The initiator contract locks the token it was deployed with.
+		let locked_token = client_config.weth_contract;
+		let initiator_address = client_config.initiator_contract;
+		let fee_on_transfer = client_config.fee_on_transfer.clone();
+
+		let ws_connection = match config.eth_event_source.parse()? {
+			EventSource::Polling => None,
+			EventSource::Subscription => {
+				let reconnect = ReconnectPolicy {
+					max_retries: config.eth_ws_reconnect_max_retries,
+					base_delay: Duration::from_millis(config.eth_ws_reconnect_base_delay_ms),
+				};
+				WsConnection::connect(
+					&config.eth_ws_connection_url(),
+					&config.eth_rpc_bearer_token,
+					reconnect,
+				)
+				.await
+				.map(|connection| match &metrics {
+					Some(metrics) => {
+						connection.with_reconnect_counter(metrics.monitor_reconnects("Eth"))
+					}
+					None => connection,
+				})
+			}
+		};
+
+		let confirmation_policy = ConfirmationPolicy::new(config.eth_confirmation_tiers.clone())
+			.with_asset_overrides(&config.eth_asset_confirmations)?
+			.with_min_confirmations(config.eth_confirmation_blocks);
+		if config.eth_confirmation_secs > 0 {
+			match block_time(&rpc_provider, BlockNumberOrTag::Latest).await? {
+				Some((_, timestamp)) if timestamp > 0 => {}
+				_ => anyhow::bail!(
+					"The Eth chain doesn't expose block timestamps, the events can't be confirmed by chain time"
+				),
+			}
+		}
+		// Restart after the checkpoint so the events of the blocks that could be reorged
+		// since the last run are observed again.
+		let checkpoint_file = (!config.eth_checkpoint_path.is_empty())
+			.then(|| CheckpointFile::new(&config.eth_checkpoint_path));
+		let persisted = match &checkpoint_file {
+			Some(file) => file.load().await?,
+			None => None,
+		};
+		let mut checkpoint = CheckpointTracker::new(
+			confirmation_policy.max_confirmations(),
+			CheckpointCadence {
+				every_blocks: config.eth_checkpoint_every_blocks,
+				every_secs: config.eth_checkpoint_every_secs,
+			},
+			persisted,
+			unix_now(),
+		);
+		let from_block = start_block
+			.or(checkpoint.start_block())
+			.map_or(BlockNumberOrTag::Latest, BlockNumberOrTag::Number);
+
 		tracing::info!(
-			"Start Eth monitoring with initiator:{} counterpart:{}",
+			"Start Eth monitoring with initiator:{} counterpart:{} subscription:{} from block:{from_block}",
 			config.eth_initiator_contract,
-			config.eth_counterparty_contract
+			config.eth_counterparty_contract,
+			ws_connection.is_some()
 		);
 
 		//register initiator event
@@ -60,28 +183,40 @@ impl EthMonitoring {
 		//     bytes32 _hashLock,
 		//     uint256 _timeLock
 		// );
-		let initiator_initiate_event_filter = initiator_contract
-			.BridgeTransferInitiated_filter()
-			.from_block(BlockNumberOrTag::Latest)
-			.watch()
-			.await?;
-		let mut initiator_initiate_sub_stream = initiator_initiate_event_filter.into_stream();
+		let mut initiator_initiate_sub_stream =
+			if filter.accepts(BridgeContractEventType::Initiated) {
+				Some(contract_event_stream(
+					initiator_contract.BridgeTransferInitiated_filter().from_block(from_block),
+					rpc_provider.clone(),
+					ws_connection.clone(),
+				))
+			} else {
+				None
+			};
 
 		// event BridgeTransferCompleted(bytes32 indexed _bridgeTransferId, bytes32 pre_image);
-		let initiator_trcompleted_event_filter = initiator_contract
-			.BridgeTransferCompleted_filter()
-			.from_block(BlockNumberOrTag::Latest)
-			.watch()
-			.await?;
-		let mut initiator_trcompleted_sub_stream = initiator_trcompleted_event_filter.into_stream();
+		let mut initiator_trcompleted_sub_stream =
+			if filter.accepts(BridgeContractEventType::InitialtorCompleted) {
+				Some(contract_event_stream(
+					initiator_contract.BridgeTransferCompleted_filter().from_block(from_block),
+					rpc_provider.clone(),
+					ws_connection.clone(),
+				))
+			} else {
+				None
+			};
 
 		// event BridgeTransferRefunded(bytes32 indexed _bridgeTransferId);
-		let initiator_trrefund_event_filter = initiator_contract
-			.BridgeTransferRefunded_filter()
-			.from_block(BlockNumberOrTag::Latest)
-			.watch()
-			.await?;
-		let mut initiator_trrefund_sub_stream = initiator_trrefund_event_filter.into_stream();
+		let mut initiator_trrefund_sub_stream = if filter.accepts(BridgeContractEventType::Refunded)
+		{
+			Some(contract_event_stream(
+				initiator_contract.BridgeTransferRefunded_filter().from_block(from_block),
+				rpc_provider.clone(),
+				ws_connection.clone(),
+			))
+		} else {
+			None
+		};
 
 		let counterpart_contract = AtomicBridgeCounterparty::new(
 			config.eth_counterparty_contract.parse()?,
@@ -96,92 +231,231 @@ impl EthMonitoring {
 		//     bytes32 hashLock,
 		//     uint256 timeLock
 		// );
-		let counterpart_trlocked_event_filter = counterpart_contract
-			.BridgeTransferLocked_filter()
-			.from_block(BlockNumberOrTag::Latest)
-			.watch()
-			.await?;
-		let mut counterpart_trlocked_sub_stream = counterpart_trlocked_event_filter.into_stream();
+		let mut counterpart_trlocked_sub_stream = if filter.accepts(BridgeContractEventType::Locked)
+		{
+			Some(contract_event_stream(
+				counterpart_contract.BridgeTransferLocked_filter().from_block(from_block),
+				rpc_provider.clone(),
+				ws_connection.clone(),
+			))
+		} else {
+			None
+		};
 
 		//event BridgeTransferCompleted(bytes32 indexed bridgeTransferId, bytes32 pre_image);
-		let counterpart_trcompleted_event_filter = counterpart_contract
-			.BridgeTransferCompleted_filter()
-			.from_block(BlockNumberOrTag::Latest)
-			.watch()
-			.await?;
 		let mut counterpart_trcompleted_sub_stream =
-			counterpart_trcompleted_event_filter.into_stream();
+			if filter.accepts(BridgeContractEventType::CounterPartCompleted) {
+				Some(contract_event_stream(
+					counterpart_contract.BridgeTransferCompleted_filter().from_block(from_block),
+					rpc_provider.clone(),
+					ws_connection.clone(),
+				))
+			} else {
+				None
+			};
 
 		//event BridgeTransferAborted(bytes32 indexed bridgeTransferId);
-		let counterpart_trcaborted_event_filter = counterpart_contract
-			.BridgeTransferCompleted_filter()
-			.from_block(BlockNumberOrTag::Latest)
-			.watch()
-			.await?;
 		let mut counterpart_trcaborted_sub_stream =
-			counterpart_trcaborted_event_filter.into_stream();
+			if filter.accepts(BridgeContractEventType::Cancelled) {
+				Some(contract_event_stream(
+					counterpart_contract.BridgeTransferAborted_filter().from_block(from_block),
+					rpc_provider.clone(),
+					ws_connection.clone(),
+				))
+			} else {
+				None
+			};
 
 		// Spawn a task to forward events to the listener channel
 		let (mut sender, listener) = futures::channel::mpsc::unbounded::<
 			BridgeContractResult<BridgeContractEvent<EthAddress>>,
 		>();
 
-		tokio::spawn(async move {
+		let mut confirmation_gate = ConfirmationGate::new(confirmation_policy);
+		if config.eth_confirmation_secs > 0 {
+			confirmation_gate = confirmation_gate.with_chain_time(config.eth_confirmation_secs);
+		}
+		let (taken, mut taken_events) = futures::channel::mpsc::unbounded::<()>();
+		// Blocks of the events sent to the listener and not processed yet.
+		let mut in_flight = InFlightEvents::<Option<u64>>::new();
+		let mut dedup = EventDedup::new(EVENT_DEDUP_CAPACITY);
+		let mut confirmation_tick = tokio::time::interval(CONFIRMATION_CHECK_INTERVAL);
+		let mut checkpoint_tick = tokio::time::interval(CHECKPOINT_CHECK_INTERVAL);
+		let head_provider = rpc_provider.clone();
+
+		let task = tokio::spawn(async move {
 			loop {
 				let event;
 				select! {
 					//Initiator event stream
-					Some(res) = initiator_initiate_sub_stream.next() => {
-						event = res.map(|(initiated, _log)| {
-							// BridgeTransferInitiated(bridgeTransferId, originator, recipient, totalAmount, hashLock, initiatorTimeLockDuration);
-							let details: BridgeTransferDetails<EthAddress> = BridgeTransferDetails {
-								bridge_transfer_id: BridgeTransferId(*initiated._bridgeTransferId),
-								initiator_address: BridgeAddress(EthAddress(Address::from(initiated._originator))),
-								recipient_address: BridgeAddress(initiated._recipient.to_vec()),
-								hash_lock: HashLock(*initiated._hashLock),
-								time_lock: initiated._timeLock.into(),
-								amount: initiated.amount.into(),
-								state: 0,
-							};
-							BridgeContractEvent::Initiated(details)
-						}).map_err(|err| BridgeContractError::OnChainError(err.to_string()));
+					Some(res) = next_event(&mut initiator_initiate_sub_stream) => {
+						event = match res {
+							Ok((initiated, log)) => {
+								// BridgeTransferInitiated(bridgeTransferId, originator, recipient, totalAmount, hashLock, initiatorTimeLockDuration);
+								let mut details: BridgeTransferDetails<EthAddress> = BridgeTransferDetails {
+									bridge_transfer_id: BridgeTransferId(*initiated._bridgeTransferId),
+									initiator_address: BridgeAddress(EthAddress(Address::from(initiated._originator))),
+									recipient_address: BridgeAddress(initiated._recipient.to_vec()),
+									hash_lock: HashLock(*initiated._hashLock),
+									time_lock: initiated._timeLock.into(),
+									amount: asset.amount(initiated.amount.wrapping_to::<u64>()),
+									state: 0,
+								};
+								// The contract received less than the initiated amount of a fee-on-transfer token.
+								let locked_amount = if log.removed {
+									Ok(Some(details.amount))
+								} else {
+									fee_on_transfer
+										.initiated_amount(&head_provider, locked_token, initiator_address, &details, log.transaction_hash)
+										.await
+								};
+								match locked_amount {
+									Ok(Some(amount)) => {
+										details.amount = amount;
+										Ok((BridgeContractEvent::Initiated(details), log.block_number, log.removed))
+									}
+									Ok(None) => {
+										tracing::error!(
+											"Transfer:{} of the fee-on-transfer token {locked_token} rejected, it won't be locked and must be refunded once expired",
+											details.bridge_transfer_id
+										);
+										continue;
+									}
+									Err(err) => Err(err),
+								}
+							}
+							Err(err) => Err(BridgeContractError::OnChainError(err.to_string())),
+						};
 					}
-					Some(res) = initiator_trcompleted_sub_stream.next() => {
-						event = res.map(|(completed, _log)| {
-							BridgeContractEvent::InitialtorCompleted(BridgeTransferId(*completed._bridgeTransferId))
+					Some(res) = next_event(&mut initiator_trcompleted_sub_stream) => {
+						event = res.map(|(completed, log)| {
+							(BridgeContractEvent::InitialtorCompleted(BridgeTransferId(*completed._bridgeTransferId), asset), log.block_number, log.removed)
 						}).map_err(|err| BridgeContractError::OnChainError(err.to_string()));
 					}
-					Some(res) = initiator_trrefund_sub_stream.next() => {
-						event = res.map(|(refund, _log)| {
-							BridgeContractEvent::Refunded(BridgeTransferId(*refund._bridgeTransferId))
+					Some(res) = next_event(&mut initiator_trrefund_sub_stream) => {
+						event = res.map(|(refund, log)| {
+							(BridgeContractEvent::Refunded(BridgeTransferId(*refund._bridgeTransferId), asset), log.block_number, log.removed)
 						}).map_err(|err| BridgeContractError::OnChainError(err.to_string()));
 					}
 					//Counterpart event stream
-					Some(res) = counterpart_trlocked_sub_stream.next() => {
-						event = res.map(|(trlocked, _log)| {
+					Some(res) = next_event(&mut counterpart_trlocked_sub_stream) => {
+						event = res.map(|(trlocked, log)| {
 							// BridgeTransferInitiated(bridgeTransferId, originator, recipient, totalAmount, hashLock, initiatorTimeLockDuration);
 							let details: LockDetails<EthAddress> = LockDetails {
 								bridge_transfer_id: BridgeTransferId(*trlocked.bridgeTransferId),
 								initiator_address: BridgeAddress(trlocked.initiator.to_vec()),
 								recipient_address: BridgeAddress(EthAddress(Address::from(trlocked.recipient))),
-								amount: trlocked.amount.into(),
+								amount: asset.amount(trlocked.amount.wrapping_to::<u64>()),
 								hash_lock: HashLock(*trlocked.hashLock),
 								time_lock: trlocked.timeLock.into(),
 							};
-							BridgeContractEvent::Locked(details)
+							(BridgeContractEvent::Locked(details), log.block_number, log.removed)
 						}).map_err(|err| BridgeContractError::OnChainError(err.to_string()));
 					}
-					Some(res) = counterpart_trcompleted_sub_stream.next() => {
-						event = res.map(|(completed, _log)| {
-							BridgeContractEvent::CounterPartCompleted(BridgeTransferId(*completed.bridgeTransferId), HashLockPreImage(*completed.pre_image))
+					Some(res) = next_event(&mut counterpart_trcompleted_sub_stream) => {
+						event = res.map(|(completed, log)| {
+							(BridgeContractEvent::CounterPartCompleted(BridgeTransferId(*completed.bridgeTransferId), HashLockPreImage(*completed.pre_image), asset), log.block_number, log.removed)
 						}).map_err(|err| BridgeContractError::OnChainError(err.to_string()));
 					}
-					Some(res) = counterpart_trcaborted_sub_stream.next() => {
-						event = res.map(|(aborted, _log)| {
-							BridgeContractEvent::Cancelled(BridgeTransferId(*aborted.bridgeTransferId))
+					Some(res) = next_event(&mut counterpart_trcaborted_sub_stream) => {
+						event = res.map(|(aborted, log)| {
+							(BridgeContractEvent::Cancelled(BridgeTransferId(*aborted.bridgeTransferId), asset), log.block_number, log.removed)
 						}).map_err(|err| BridgeContractError::OnChainError(err.to_string()));
 					}
+					// Release the pending events that are now deep enough.
+					_ = confirmation_tick.tick(), if confirmation_gate.has_pending() => {
+						let released = if confirmation_gate.confirms_by_chain_time() {
+							block_time(&head_provider, BlockNumberOrTag::Latest).await.map(|head| match head {
+								Some((head, head_timestamp)) => confirmation_gate.release_timed(head, head_timestamp),
+								None => Vec::new(),
+							})
+						} else {
+							head_provider.get_block_number().await.map(|head| confirmation_gate.release(head)).map_err(Into::into)
+						};
+						match released {
+							Ok(released) => {
+								for (event, block_number) in released {
+									in_flight.sent(block_number);
+									if sender.send(Ok(event)).await.is_err() {
+										tracing::error!("Failed to send event to listener channel");
+										return;
+									}
+								}
+							}
+							Err(err) => tracing::warn!("Eth monitoring failed to get the head block:{err}"),
+						}
+						continue;
+					}
+					// The relayer took an event, the one it took before is processed.
+					Some(()) = taken_events.next() => {
+						if let Some(Some(block)) = in_flight.taken() {
+							checkpoint.processed(block);
+						}
+						if let Some(file) = &checkpoint_file {
+							let oldest_unprocessed = oldest_unprocessed_block(&in_flight, &confirmation_gate);
+							advance_checkpoint(&head_provider, &mut checkpoint, oldest_unprocessed, file).await;
+						}
+						continue;
+					}
+					// Persist the last block that can't be reorged anymore and whose events are processed.
+					_ = checkpoint_tick.tick(), if checkpoint_file.is_some() => {
+						if let Some(file) = &checkpoint_file {
+							let oldest_unprocessed = oldest_unprocessed_block(&in_flight, &confirmation_gate);
+							advance_checkpoint(&head_provider, &mut checkpoint, oldest_unprocessed, file).await;
+						}
+						continue;
+					}
 				};
+				let (event, block_number) = match event {
+					// The block of the event was reorged out, it must not be acted on.
+					Ok((event, block_number, true)) => {
+						dedup.forget(&event, block_number);
+						let reorged = (event, block_number);
+						if !confirmation_gate.drop_reorged(&reorged) {
+							tracing::warn!("Eth monitoring observed a reorged event that was already confirmed:{:?}", reorged.0);
+						}
+						continue;
+					}
+					// Observed again after a reconnect or in an overlapping block range.
+					Ok((event, block_number, false)) if !dedup.insert(&event, block_number) => {
+						continue;
+					}
+					Ok((event, block_number, false)) => {
+						let amount = event_amount(&event);
+						let asset = event.asset();
+						// The timestamp is only needed when confirming by chain time.
+						let block_timestamp = match block_number {
+							Some(block_number) if confirmation_gate.confirms_by_chain_time() => {
+								match block_time(
+									&head_provider,
+									BlockNumberOrTag::Number(block_number),
+								)
+								.await
+								{
+									Ok(block) => block.map(|(_, timestamp)| timestamp),
+									Err(err) => {
+										tracing::warn!("Eth monitoring failed to get the timestamp of block {block_number}:{err}");
+										None
+									}
+								}
+							}
+							_ => None,
+						};
+						match confirmation_gate.push_timed(
+							(event, block_number),
+							block_number,
+							block_timestamp,
+							asset,
+							amount,
+						) {
+							Some((event, block_number)) => (Ok(event), block_number),
+							// Wait for the event to be confirmed.
+							None => continue,
+						}
+					}
+					Err(err) => (Err(err), None),
+				};
+				in_flight.sent(block_number);
 				if sender.send(event).await.is_err() {
 					tracing::error!("Failed to send event to listener channel");
 					break;
@@ -189,7 +463,55 @@ impl EthMonitoring {
 			}
 		});
 
-		Ok(Self { listener })
+		Ok(Self { listener, taken, task, metrics })
+	}
+}
+
+// Oldest block of an event observed but not processed yet, held by the gate or sent to the
+// relayer.
+fn oldest_unprocessed_block(
+	in_flight: &InFlightEvents<Option<u64>>,
+	confirmation_gate: &ConfirmationGate<(BridgeContractEvent<EthAddress>, Option<u64>)>,
+) -> Option<u64> {
+	let sent = in_flight.unprocessed().flatten().copied();
+	sent.chain(confirmation_gate.oldest_pending_block()).min()
+}
+
+// Persist the checkpoint at the current head if it moved forward.
+async fn advance_checkpoint(
+	provider: &AlloyProvider,
+	checkpoint: &mut CheckpointTracker,
+	oldest_unprocessed: Option<u64>,
+	file: &CheckpointFile,
+) {
+	let head = match provider.get_block_number().await {
+		Ok(head) => head,
+		Err(err) => {
+			tracing::warn!("Eth monitoring failed to get the head block number:{err}");
+			return;
+		}
+	};
+	if let Some(block) = checkpoint.advance(head, oldest_unprocessed, unix_now()) {
+		if let Err(err) = file.save(block).await {
+			tracing::warn!("Eth monitoring failed to persist the checkpoint {block}:{err}");
+		}
+	}
+}
+
+// Number and timestamp of a block, None if the node doesn't have it.
+async fn block_time(
+	provider: &AlloyProvider,
+	block: BlockNumberOrTag,
+) -> Result<Option<(u64, u64)>, anyhow::Error> {
+	let block = provider.get_block_by_number(block, false).await?;
+	Ok(block.and_then(|block| Some((block.header.number?, block.header.timestamp))))
+}
+
+// Next item of an optional subscription, a missing subscription never yields.
+async fn next_event<S: Stream + Unpin>(stream: &mut Option<S>) -> Option<S::Item> {
+	match stream {
+		Some(stream) => stream.next().await,
+		None => std::future::pending().await,
 	}
 }
 
@@ -198,7 +520,14 @@ impl Stream for EthMonitoring {
 
 	fn poll_next(self: Pin<&mut Self>, cx: &mut std::task::Context) -> Poll<Option<Self::Item>> {
 		let this = self.get_mut();
-		this.listener.poll_next_unpin(cx)
+		let poll = this.listener.poll_next_unpin(cx);
+		if let Poll::Ready(Some(_)) = &poll {
+			let _ = this.taken.unbounded_send(());
+		}
+		if let (Poll::Ready(Some(Ok(event))), Some(metrics)) = (&poll, &this.metrics) {
+			metrics.observe_event("Eth", event);
+		}
+		poll
 	}
 }
 
@@ -475,3 +804,186 @@ impl Stream for EthMonitoring {
 // 		Err(BridgeContractError::ConversionFailed("event selector".to_string()))
 // 	}
 // }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloy::sol_types::SolEvent;
+	use serde_json::{json, Value};
+	use std::sync::{Arc, Mutex};
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+	use tokio::net::{TcpListener, TcpStream};
+
+	const INITIATOR: &str = "0x1111111111111111111111111111111111111111";
+
+	// Logs of the chain and the log filters created on it.
+	#[derive(Default)]
+	struct MockChain {
+		head: u64,
+		logs: Vec<(u64, Value)>,
+		// From block of each filter and the number of logs it returned.
+		filters: Vec<(u64, usize)>,
+	}
+
+	impl MockChain {
+		// Completion of the transfer `id` in `block` on the initiator contract.
+		fn complete(&mut self, id: u8, block: u64) {
+			let log = json!({
+				"address": INITIATOR,
+				"topics": [
+					AtomicBridgeInitiator::BridgeTransferCompleted::SIGNATURE_HASH,
+					format!("0x{id:064x}"),
+				],
+				"data": format!("0x{:064x}", 0),
+				"blockNumber": format!("{block:#x}"),
+				"blockHash": format!("0x{block:064x}"),
+				"transactionHash": format!("0x{id:064x}"),
+				"transactionIndex": "0x0",
+				"logIndex": "0x0",
+				"removed": false,
+			});
+			self.logs.push((block, log));
+		}
+
+		fn answer(&mut self, method: &str, params: &Value) -> Value {
+			match method {
+				"eth_blockNumber" => json!(format!("{:#x}", self.head)),
+				"eth_newFilter" => {
+					let from_block = params[0]["fromBlock"].as_str().unwrap_or("0x0");
+					let from_block = u64::from_str_radix(from_block.trim_start_matches("0x"), 16);
+					self.filters.push((from_block.unwrap_or(0), 0));
+					json!(format!("{:#x}", self.filters.len()))
+				}
+				"eth_getFilterChanges" => {
+					let id = params[0].as_str().unwrap().trim_start_matches("0x");
+					let (from_block, returned) =
+						&mut self.filters[usize::from_str_radix(id, 16).unwrap() - 1];
+					let logs: Vec<Value> = self.logs[*returned..]
+						.iter()
+						.filter(|(block, _)| block >= from_block)
+						.map(|(_, log)| log.clone())
+						.collect();
+					*returned = self.logs.len();
+					json!(logs)
+				}
+				_ => Value::Null,
+			}
+		}
+	}
+
+	/// Node answering the JSON-RPC requests from `chain`.
+	async fn mock_node(chain: Arc<Mutex<MockChain>>) -> u16 {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let port = listener.local_addr().unwrap().port();
+		tokio::spawn(async move {
+			while let Ok((socket, _)) = listener.accept().await {
+				tokio::spawn(serve(socket, chain.clone()));
+			}
+		});
+		port
+	}
+
+	async fn serve(mut socket: TcpStream, chain: Arc<Mutex<MockChain>>) {
+		let mut request = Vec::new();
+		let mut buf = vec![0; 64 * 1024];
+		loop {
+			let Ok(len) = socket.read(&mut buf).await else { return };
+			if len == 0 {
+				return;
+			}
+			request.extend_from_slice(&buf[..len]);
+			let text = String::from_utf8_lossy(&request).to_string();
+			let Some((head, body)) = text.split_once("\r\n\r\n") else { continue };
+			let content_length = head
+				.lines()
+				.find_map(|line| {
+					line.to_lowercase().strip_prefix("content-length:").map(str::to_owned)
+				})
+				.and_then(|length| length.trim().parse::<usize>().ok())
+				.unwrap_or(0);
+			if body.len() < content_length {
+				continue;
+			}
+			let request: Value = serde_json::from_str(body).unwrap();
+			let result = chain
+				.lock()
+				.unwrap()
+				.answer(request["method"].as_str().unwrap(), &request["params"]);
+			let body = json!({"jsonrpc": "2.0", "id": request["id"], "result": result}).to_string();
+			let response = format!(
+				"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+				body.len()
+			);
+			let _ = socket.write_all(response.as_bytes()).await;
+			return;
+		}
+	}
+
+	// Transfer of the next event delivered by `monitoring`.
+	async fn next_transfer(monitoring: &mut EthMonitoring) -> u8 {
+		let event = tokio::time::timeout(Duration::from_secs(10), monitoring.next())
+			.await
+			.expect("no event delivered")
+			.unwrap()
+			.unwrap();
+		event.bridge_transfer_id().0[31]
+	}
+
+	#[tokio::test]
+	async fn test_killed_monitoring_is_rebuilt_without_skipping_events() {
+		let path =
+			std::env::temp_dir().join(format!("bridge-eth-checkpoint-kill-{}", std::process::id()));
+		let _ = std::fs::remove_file(&path);
+		let chain = Arc::new(Mutex::new(MockChain { head: 40, ..MockChain::default() }));
+		for (id, block) in [(1, 10), (2, 20), (3, 30)] {
+			chain.lock().unwrap().complete(id, block);
+		}
+		let config = EthConfig {
+			eth_rpc_connection_hostname: "127.0.0.1".to_string(),
+			eth_rpc_connection_port: mock_node(chain.clone()).await,
+			eth_initiator_contract: INITIATOR.to_string(),
+			eth_counterparty_contract: "0x2222222222222222222222222222222222222222".to_string(),
+			eth_weth_contract: "0x3333333333333333333333333333333333333333".to_string(),
+			eth_event_source: "polling".to_string(),
+			eth_confirmation_tiers: Vec::new(),
+			eth_asset_confirmations: Vec::new(),
+			eth_confirmation_blocks: 0,
+			eth_confirmation_secs: 0,
+			eth_checkpoint_path: path.to_string_lossy().to_string(),
+			eth_checkpoint_every_blocks: 0,
+			eth_checkpoint_every_secs: 0,
+			..EthConfig::default()
+		};
+		let filter = EventFilter::only([BridgeContractEventType::InitialtorCompleted]);
+
+		// The relayer processed the transfer of block 10 and is killed while it processes the one
+		// of block 20, the one of block 30 is still in the channel.
+		let mut monitoring =
+			EthMonitoring::build_with_filter(&config, filter.clone()).await.unwrap();
+		assert_eq!(next_transfer(&mut monitoring).await, 1);
+		assert_eq!(next_transfer(&mut monitoring).await, 2);
+		let file = CheckpointFile::new(&path);
+		tokio::time::timeout(Duration::from_secs(10), async {
+			while file.load().await.unwrap().is_none() {
+				tokio::time::sleep(Duration::from_millis(50)).await;
+			}
+		})
+		.await
+		.expect("no checkpoint persisted");
+		monitoring.stop();
+
+		// A transfer completes while the relayer is down.
+		chain.lock().unwrap().complete(4, 35);
+
+		// The rebuilt monitoring replays from the checkpoint, no transfer is skipped.
+		let mut monitoring = EthMonitoring::build_with_filter(&config, filter).await.unwrap();
+		let mut delivered = Vec::new();
+		for _ in 0..4 {
+			delivered.push(next_transfer(&mut monitoring).await);
+		}
+		assert_eq!(delivered, vec![1, 2, 3, 4]);
+		assert_eq!(file.load().await.unwrap(), Some(9));
+		monitoring.stop();
+		let _ = std::fs::remove_file(&path);
+	}
+}
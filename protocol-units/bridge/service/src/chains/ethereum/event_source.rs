@@ -0,0 +1,411 @@
+use alloy::contract::Event;
+use alloy::primitives::B256;
+use alloy::providers::{Provider, ProviderBuilder, RootProvider, WsConnect};
+use alloy::pubsub::PubSubFrontend;
+use alloy::rpc::types::{Filter, Log};
+use alloy::sol_types::SolEvent;
+use alloy::transports::{Authorization, TransportResult};
+use futures::{Stream, StreamExt};
+use prometheus::IntCounter;
+use std::collections::{HashSet, VecDeque};
+use std::pin::Pin;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Number of recent logs remembered to drop the ones delivered twice when the source changes.
+const SEEN_LOGS_CAPACITY: usize = 1024;
+/// Delay before retrying to create a log poller.
+const POLLER_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+pub type WsProvider = RootProvider<PubSubFrontend>;
+
+pub type ContractEventStream<E> = Pin<Box<dyn Stream<Item = Result<(E, Log), String>> + Send>>;
+
+/// How the Eth monitoring observes the bridge contract logs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventSource {
+	/// Poll a log filter over the RPC connection.
+	Polling,
+	/// Subscribe to the logs over the WebSocket connection, polling if it's unavailable.
+	Subscription,
+}
+
+impl FromStr for EventSource {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"polling" => Ok(EventSource::Polling),
+			"subscription" => Ok(EventSource::Subscription),
+			_ => Err(anyhow::anyhow!("Unknown Eth event source: {s}")),
+		}
+	}
+}
+
+/// Reconnection of a dropped WebSocket: `max_retries` attempts, the first one after
+/// `base_delay` and the next ones after twice the previous delay.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+	pub max_retries: u32,
+	pub base_delay: Duration,
+}
+
+impl ReconnectPolicy {
+	/// Delay before the reconnect attempt `attempt`, starting at 1.
+	pub fn delay(&self, attempt: u32) -> Duration {
+		self.base_delay.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+	}
+}
+
+/// WebSocket used by the log subscriptions, reconnected when it drops.
+#[derive(Clone)]
+pub struct WsConnection {
+	url: String,
+	bearer_token: String,
+	provider: WsProvider,
+	reconnect: ReconnectPolicy,
+	reconnects: Option<IntCounter>,
+}
+
+impl WsConnection {
+	/// A non empty `bearer_token` authenticates the connection.
+	/// Returns None if the node can't be reached, the monitoring then polls.
+	pub async fn connect(
+		url: &str,
+		bearer_token: &str,
+		reconnect: ReconnectPolicy,
+	) -> Option<WsConnection> {
+		match open_ws(url, bearer_token).await {
+			Ok(provider) => Some(WsConnection {
+				url: url.to_string(),
+				bearer_token: bearer_token.to_string(),
+				provider,
+				reconnect,
+				reconnects: None,
+			}),
+			Err(err) => {
+				tracing::warn!("Eth WebSocket {url} unavailable, falling back to polling:{err}");
+				None
+			}
+		}
+	}
+
+	/// Count the successful reconnections in `reconnects`.
+	pub fn with_reconnect_counter(mut self, reconnects: IntCounter) -> Self {
+		self.reconnects = Some(reconnects);
+		self
+	}
+
+	/// Replace the dropped connection, fails once the reconnect attempts are exhausted.
+	async fn reconnect(&mut self) -> Result<(), String> {
+		let max_retries = self.reconnect.max_retries;
+		let mut last_err = String::from("no reconnect attempt");
+		for attempt in 1..=max_retries {
+			let delay = self.reconnect.delay(attempt);
+			tracing::warn!(
+				"Eth WebSocket {} reconnect attempt {attempt}/{max_retries} in {delay:?}",
+				self.url
+			);
+			tokio::time::sleep(delay).await;
+			match open_ws(&self.url, &self.bearer_token).await {
+				Ok(provider) => {
+					self.provider = provider;
+					if let Some(reconnects) = &self.reconnects {
+						reconnects.inc();
+					}
+					return Ok(());
+				}
+				Err(err) => last_err = err.to_string(),
+			}
+		}
+		Err(format!(
+			"Eth WebSocket {} lost after {max_retries} reconnect attempts:{last_err}",
+			self.url
+		))
+	}
+}
+
+async fn open_ws(url: &str, bearer_token: &str) -> TransportResult<WsProvider> {
+	let auth = (!bearer_token.is_empty()).then(|| Authorization::bearer(bearer_token));
+	ProviderBuilder::new().on_ws(WsConnect::with_auth(url, auth)).await
+}
+
+/// Stream the logs of a contract event.
+/// Logs come from the WebSocket subscription while it's alive and from `http_provider` polling
+/// otherwise. When the subscription closes, the WebSocket is reconnected and the subscription
+/// restarts from the last block seen, the logs already delivered are dropped. Once the
+/// reconnect attempts are exhausted, an error is yielded and the logs are polled.
+pub fn contract_event_stream<T, P, E, N, H>(
+	event: Event<T, P, E, N>,
+	http_provider: H,
+	ws: Option<WsConnection>,
+) -> ContractEventStream<E>
+where
+	E: SolEvent + Send + 'static,
+	H: Provider + Clone + 'static,
+{
+	log_event_stream(event.filter, http_provider, ws)
+}
+
+fn log_event_stream<E, H>(
+	filter: Filter,
+	http_provider: H,
+	ws: Option<WsConnection>,
+) -> ContractEventStream<E>
+where
+	E: SolEvent + Send + 'static,
+	H: Provider + Clone + 'static,
+{
+	Box::pin(async_stream::stream! {
+		let mut ws = ws;
+		let mut seen_logs = SeenLogs::new(SEEN_LOGS_CAPACITY);
+		let mut last_block = None;
+		loop {
+			let filter = match last_block {
+				Some(block) => filter.clone().from_block(block),
+				None => filter.clone(),
+			};
+			let mut logs = match open_log_stream(&filter, &http_provider, &mut ws).await {
+				Ok(logs) => logs,
+				Err(err) => {
+					yield Err(err);
+					tokio::time::sleep(POLLER_RETRY_DELAY).await;
+					continue;
+				}
+			};
+			while let Some(log) = logs.next().await {
+				if log.block_number.is_some() {
+					last_block = log.block_number;
+				}
+				if log.removed {
+					// Reorged out, the log is delivered again if it's included in the new chain.
+					seen_logs.forget(&log);
+				} else if !seen_logs.insert(&log) {
+					continue;
+				}
+				yield E::decode_log_data(log.data(), true)
+					.map(|event| (event, log))
+					.map_err(|err| err.to_string());
+			}
+			// Only a subscription ends, the polling goes on while the provider lives.
+			let Some(connection) = ws.as_mut() else {
+				continue;
+			};
+			tracing::warn!("Eth log subscription closed, reconnecting");
+			if let Err(err) = connection.reconnect().await {
+				tracing::warn!("{err}, falling back to polling");
+				ws = None;
+				yield Err(err);
+			}
+		}
+	})
+}
+
+async fn open_log_stream<H: Provider>(
+	filter: &Filter,
+	http_provider: &H,
+	ws: &mut Option<WsConnection>,
+) -> Result<Pin<Box<dyn Stream<Item = Log> + Send>>, String> {
+	if let Some(connection) = ws {
+		match connection.provider.subscribe_logs(filter).await {
+			Ok(subscription) => return Ok(Box::pin(subscription.into_stream())),
+			Err(err) => {
+				tracing::warn!("Eth log subscription failed, falling back to polling:{err}");
+				*ws = None;
+			}
+		}
+	}
+	let poller = http_provider.watch_logs(filter).await.map_err(|err| err.to_string())?;
+	Ok(Box::pin(poller.into_stream().flat_map(futures::stream::iter)))
+}
+
+/// Bounded set of the last logs delivered.
+struct SeenLogs {
+	order: VecDeque<(Option<B256>, Option<u64>)>,
+	ids: HashSet<(Option<B256>, Option<u64>)>,
+	capacity: usize,
+}
+
+impl SeenLogs {
+	fn new(capacity: usize) -> Self {
+		SeenLogs { order: VecDeque::new(), ids: HashSet::new(), capacity }
+	}
+
+	/// Returns false if the log was already seen.
+	fn insert(&mut self, log: &Log) -> bool {
+		let id = (log.transaction_hash, log.log_index);
+		if !self.ids.insert(id) {
+			return false;
+		}
+		self.order.push_back(id);
+		if self.order.len() > self.capacity {
+			if let Some(oldest) = self.order.pop_front() {
+				self.ids.remove(&oldest);
+			}
+		}
+		true
+	}
+
+	fn forget(&mut self, log: &Log) {
+		let id = (log.transaction_hash, log.log_index);
+		if self.ids.remove(&id) {
+			self.order.retain(|seen| *seen != id);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use alloy::primitives::{Address, U256};
+	use futures::SinkExt;
+	use tokio::net::{TcpListener, TcpStream};
+	use tokio_tungstenite::tungstenite::Message;
+	use tokio_tungstenite::WebSocketStream;
+
+	alloy::sol! {
+		#[derive(Debug)]
+		event Ping(uint256 value);
+	}
+
+	fn log(tx: u8, index: u64) -> Log {
+		Log {
+			transaction_hash: Some(B256::repeat_byte(tx)),
+			log_index: Some(index),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn test_seen_logs_drop_overlap() {
+		let mut seen_logs = SeenLogs::new(2);
+		assert!(seen_logs.insert(&log(1, 0)));
+		assert!(seen_logs.insert(&log(1, 1)));
+		assert!(!seen_logs.insert(&log(1, 0)));
+		// The oldest log is forgotten once the capacity is reached.
+		assert!(seen_logs.insert(&log(2, 0)));
+		assert!(seen_logs.insert(&log(1, 0)));
+	}
+
+	#[test]
+	fn test_seen_logs_forget_reorged_log() {
+		let mut seen_logs = SeenLogs::new(2);
+		assert!(seen_logs.insert(&log(1, 0)));
+		seen_logs.forget(&log(1, 0));
+		assert!(seen_logs.insert(&log(1, 0)));
+		assert!(!seen_logs.insert(&log(1, 0)));
+	}
+
+	#[test]
+	fn test_reconnect_delay_doubles() {
+		let policy = ReconnectPolicy { max_retries: 4, base_delay: Duration::from_millis(500) };
+		let delays: Vec<_> = (1..=4).map(|attempt| policy.delay(attempt).as_millis()).collect();
+		assert_eq!(delays, vec![500, 1_000, 2_000, 4_000]);
+		// No overflow on long outages.
+		assert!(policy.delay(u32::MAX) > policy.delay(4));
+	}
+
+	// Accept a WebSocket connection and acknowledge its log subscription.
+	async fn accept_subscription(listener: &TcpListener) -> WebSocketStream<TcpStream> {
+		let (socket, _) = listener.accept().await.unwrap();
+		let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+		let request = loop {
+			if let Message::Text(text) = ws.next().await.unwrap().unwrap() {
+				break serde_json::from_str::<serde_json::Value>(&text).unwrap();
+			}
+		};
+		assert_eq!(request["method"], "eth_subscribe");
+		let response = serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": "0x1"});
+		ws.send(Message::Text(response.to_string())).await.unwrap();
+		// Let the client listen to the subscription before notifying it.
+		tokio::time::sleep(Duration::from_millis(100)).await;
+		ws
+	}
+
+	fn ping_notification(value: u8) -> Message {
+		let log = serde_json::json!({
+			"address": Address::repeat_byte(0xbb),
+			"topics": [Ping::SIGNATURE_HASH],
+			"data": format!("0x{}", hex::encode(U256::from(value).to_be_bytes::<32>())),
+			"blockHash": B256::repeat_byte(value),
+			"blockNumber": format!("0x{value:x}"),
+			"transactionHash": B256::repeat_byte(value),
+			"transactionIndex": "0x0",
+			"logIndex": "0x0",
+			"removed": false,
+		});
+		let notification = serde_json::json!({
+			"jsonrpc": "2.0",
+			"method": "eth_subscription",
+			"params": {"subscription": "0x1", "result": log},
+		});
+		Message::Text(notification.to_string())
+	}
+
+	#[tokio::test]
+	async fn test_dropped_subscription_is_reconnected() {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = format!("ws://{}", listener.local_addr().unwrap());
+		let (delivered, first_delivered) = tokio::sync::oneshot::channel();
+		let server = tokio::spawn(async move {
+			let mut ws = accept_subscription(&listener).await;
+			ws.send(ping_notification(1)).await.unwrap();
+			// The node goes down mid-stream and refuses the next connections.
+			first_delivered.await.unwrap();
+			ws.close(None).await.unwrap();
+			drop(ws);
+			for _ in 0..2 {
+				drop(listener.accept().await.unwrap());
+			}
+			let mut ws = accept_subscription(&listener).await;
+			ws.send(ping_notification(2)).await.unwrap();
+			ws
+		});
+
+		let policy = ReconnectPolicy { max_retries: 3, base_delay: Duration::from_millis(10) };
+		let reconnects = IntCounter::new("reconnects", "reconnects").unwrap();
+		let ws = WsConnection::connect(&url, "", policy)
+			.await
+			.unwrap()
+			.with_reconnect_counter(reconnects.clone());
+		// Never polled while the WebSocket recovers.
+		let http_provider = ProviderBuilder::new().on_builtin("http://127.0.0.1:1").await.unwrap();
+		let mut logs = log_event_stream::<Ping, _>(Filter::new(), http_provider, Some(ws));
+
+		let (first, _) = logs.next().await.unwrap().unwrap();
+		delivered.send(()).unwrap();
+		let next = tokio::time::timeout(Duration::from_secs(5), logs.next()).await;
+		let (second, _) = next.expect("The monitoring didn't recover").unwrap().unwrap();
+		assert_eq!((first.value, second.value), (U256::from(1), U256::from(2)));
+		// The refused attempts aren't counted.
+		assert_eq!(reconnects.get(), 1);
+		drop(server.await.unwrap());
+	}
+
+	#[tokio::test]
+	async fn test_error_is_yielded_once_the_reconnects_are_exhausted() {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = format!("ws://{}", listener.local_addr().unwrap());
+		let (delivered, first_delivered) = tokio::sync::oneshot::channel();
+		tokio::spawn(async move {
+			let mut ws = accept_subscription(&listener).await;
+			ws.send(ping_notification(1)).await.unwrap();
+			first_delivered.await.unwrap();
+			drop(ws);
+			// The node never comes back.
+			loop {
+				drop(listener.accept().await.unwrap());
+			}
+		});
+
+		let policy = ReconnectPolicy { max_retries: 2, base_delay: Duration::from_millis(10) };
+		let ws = WsConnection::connect(&url, "", policy).await.unwrap();
+		let http_provider = ProviderBuilder::new().on_builtin("http://127.0.0.1:1").await.unwrap();
+		let mut logs = log_event_stream::<Ping, _>(Filter::new(), http_provider, Some(ws));
+
+		assert!(logs.next().await.unwrap().is_ok());
+		delivered.send(()).unwrap();
+		let next = tokio::time::timeout(Duration::from_secs(5), logs.next()).await.unwrap();
+		assert!(next.unwrap().unwrap_err().contains("lost after 2 reconnect attempts"));
+	}
+}
@@ -0,0 +1,244 @@
+use super::types::{AlloyProvider, EthAddress, WETH9};
+use crate::chains::bridge_contracts::{BridgeContractError, BridgeContractResult};
+use crate::types::{Amount, BridgeTransferDetails};
+use alloy::primitives::{Address, TxHash, U256};
+use alloy::providers::Provider;
+use alloy::rpc::types::Log;
+use bridge_config::common::eth::FeeOnTransferToken;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// How the transfers of a fee-on-transfer token are initiated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeeOnTransferPolicy {
+	/// The transfer isn't initiated, nor locked on the counterparty if the fee was taken.
+	Reject,
+	/// The locked amount is the amount the bridge contract actually received.
+	Adjust,
+}
+
+impl FromStr for FeeOnTransferPolicy {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"reject" => Ok(FeeOnTransferPolicy::Reject),
+			"adjust" => Ok(FeeOnTransferPolicy::Adjust),
+			_ => Err(anyhow::anyhow!("Unknown fee-on-transfer policy: {s}")),
+		}
+	}
+}
+
+/// Keeps the locked amounts of the configured fee-on-transfer tokens in line with what the
+/// bridge contract received, so the counterparty never locks more than was deposited.
+#[derive(Clone, Debug, Default)]
+pub struct FeeOnTransferGuard {
+	tokens: HashMap<Address, FeeOnTransferPolicy>,
+}
+
+impl FeeOnTransferGuard {
+	pub fn new(tokens: &[FeeOnTransferToken]) -> Result<Self, anyhow::Error> {
+		let tokens = tokens
+			.iter()
+			.map(|token| Ok((token.token.parse()?, token.policy.parse()?)))
+			.collect::<Result<_, anyhow::Error>>()?;
+		Ok(FeeOnTransferGuard { tokens })
+	}
+
+	pub fn policy(&self, token: Address) -> Option<FeeOnTransferPolicy> {
+		self.tokens.get(&token).copied()
+	}
+
+	/// Reject the transfer before it's sent if `token` is part of `amount` and rejected.
+	pub fn check(&self, token: Address, amount: Amount) -> BridgeContractResult<()> {
+		match self.policy(token) {
+			Some(FeeOnTransferPolicy::Reject) if amount.token_value() > 0 => {
+				Err(BridgeContractError::FeeOnTransferRejected(token.to_string()))
+			}
+			_ => Ok(()),
+		}
+	}
+
+	/// Amount locked by the transfer of `amount` once `token` took `fee` of it, None if the
+	/// transfer is rejected.
+	pub fn locked_amount(&self, token: Address, amount: Amount, fee: u64) -> Option<Amount> {
+		match self.policy(token) {
+			Some(FeeOnTransferPolicy::Reject) if fee > 0 => None,
+			Some(FeeOnTransferPolicy::Adjust) if fee > 0 => {
+				let sent = amount.token_value();
+				let received = sent.saturating_sub(fee);
+				tracing::warn!(
+					"Fee-on-transfer token {token}: {sent} sent, {received} received by the bridge contract"
+				);
+				Some(amount.with_token_value(received))
+			}
+			_ => Some(amount),
+		}
+	}
+
+	/// Amount locked by the initiated transfer of `details` to `contract`, from the receipt of
+	/// its transaction `tx_hash`. None if the transfer is rejected.
+	pub async fn initiated_amount(
+		&self,
+		provider: &AlloyProvider,
+		token: Address,
+		contract: Address,
+		details: &BridgeTransferDetails<EthAddress>,
+		tx_hash: Option<TxHash>,
+	) -> BridgeContractResult<Option<Amount>> {
+		if self.policy(token).is_none() {
+			return Ok(Some(details.amount));
+		}
+		let receipt = match tx_hash {
+			Some(tx_hash) => provider.get_transaction_receipt(tx_hash).await.map_err(|err| {
+				BridgeContractError::OnChainError(format!(
+					"Failed to get the receipt of {tx_hash}: {err}"
+				))
+			})?,
+			None => None,
+		};
+		let receipt = receipt.ok_or_else(|| {
+			BridgeContractError::OnChainError(format!(
+				"No receipt for the transfer {}",
+				details.bridge_transfer_id
+			))
+		})?;
+		let originator = details.initiator_address.0 .0;
+		let fee = transfer_fee(receipt.inner.logs(), token, originator, contract);
+		Ok(self.locked_amount(token, details.amount, fee))
+	}
+}
+
+/// Fee `token` took when `originator` sent it to `contract`, from the `Transfer` logs of the
+/// transaction: what the originator was debited beyond what the contract received.
+pub fn transfer_fee(logs: &[Log], token: Address, originator: Address, contract: Address) -> u64 {
+	let (debited, received) = logs
+		.iter()
+		.filter(|log| log.address() == token)
+		.filter_map(|log| log.log_decode::<WETH9::Transfer>().ok())
+		.map(|log| log.inner.data)
+		.filter(|transfer| transfer.src == originator)
+		.fold((U256::ZERO, U256::ZERO), |(debited, received), transfer| {
+			let to_contract = if transfer.dst == contract { transfer.wad } else { U256::ZERO };
+			(debited.saturating_add(transfer.wad), received.saturating_add(to_contract))
+		});
+	debited.saturating_sub(received).saturating_to::<u64>()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::actions::TransferActionType;
+	use crate::chains::bridge_contracts::BridgeContractEvent;
+	use crate::types::{
+		AssetTag, AssetType, BridgeAddress, BridgeTransferId, ChainId, HashLock, TimeLock,
+	};
+	use crate::{ChainAssets, RefundPolicy, Runtime, StuckPolicy};
+	use alloy::sol_types::SolEvent;
+
+	const TOKEN: Address = Address::repeat_byte(0xee);
+	const ORIGINATOR: Address = Address::repeat_byte(0x01);
+	const CONTRACT: Address = Address::repeat_byte(0xbb);
+
+	fn guard(policy: &str) -> FeeOnTransferGuard {
+		FeeOnTransferGuard::new(&[FeeOnTransferToken {
+			token: TOKEN.to_string(),
+			policy: policy.to_string(),
+		}])
+		.unwrap()
+	}
+
+	fn transfer_log(token: Address, src: Address, dst: Address, wad: u64) -> Log {
+		let transfer = WETH9::Transfer { src, dst, wad: U256::from(wad) };
+		Log {
+			inner: alloy::primitives::Log { address: token, data: transfer.encode_log_data() },
+			..Default::default()
+		}
+	}
+
+	// Logs of the transfer of `sent` tokens taking a 1% fee to their collector.
+	fn transfer_with_fee(sent: u64) -> Vec<Log> {
+		let fee = sent / 100;
+		vec![
+			transfer_log(TOKEN, ORIGINATOR, CONTRACT, sent - fee),
+			transfer_log(TOKEN, ORIGINATOR, Address::repeat_byte(0xfe), fee),
+		]
+	}
+
+	fn initiated(amount: Amount) -> BridgeTransferDetails<EthAddress> {
+		BridgeTransferDetails {
+			bridge_transfer_id: BridgeTransferId([1; 32]),
+			initiator_address: BridgeAddress(EthAddress(ORIGINATOR)),
+			recipient_address: BridgeAddress(vec![2; 32]),
+			hash_lock: HashLock([3; 32]),
+			time_lock: TimeLock(100),
+			amount,
+			state: 0,
+		}
+	}
+
+	#[test]
+	fn test_transfer_fee_is_what_the_contract_did_not_receive() {
+		assert_eq!(transfer_fee(&transfer_with_fee(10_000), TOKEN, ORIGINATOR, CONTRACT), 100);
+		// Without a fee the contract receives everything.
+		let logs = [transfer_log(TOKEN, ORIGINATOR, CONTRACT, 10_000)];
+		assert_eq!(transfer_fee(&logs, TOKEN, ORIGINATOR, CONTRACT), 0);
+		// The transfers of other tokens and senders aren't counted.
+		let logs = [
+			transfer_log(Address::repeat_byte(0x11), ORIGINATOR, Address::ZERO, 500),
+			transfer_log(TOKEN, Address::repeat_byte(0x22), Address::ZERO, 500),
+			transfer_log(TOKEN, ORIGINATOR, CONTRACT, 10_000),
+		];
+		assert_eq!(transfer_fee(&logs, TOKEN, ORIGINATOR, CONTRACT), 0);
+	}
+
+	#[test]
+	fn test_recorded_amount_is_the_received_amount() {
+		let guard = guard("adjust");
+		let amount = Amount(AssetType::EthAndWeth((0, 10_000)));
+		let fee =
+			transfer_fee(&transfer_with_fee(amount.token_value()), TOKEN, ORIGINATOR, CONTRACT);
+		let mut details = initiated(amount);
+		details.amount = guard.locked_amount(TOKEN, details.amount, fee).unwrap();
+		assert_eq!(details.amount, Amount(AssetType::EthAndWeth((0, 9_900))));
+		// Other tokens are unchanged.
+		assert_eq!(guard.locked_amount(Address::repeat_byte(0x11), amount, fee), Some(amount));
+
+		// The relayer records the transfer and locks the received amount on the counterparty.
+		let transfer_id = details.bridge_transfer_id;
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default())
+			.with_assets(ChainAssets { one: AssetTag::EthAndWeth, two: AssetTag::Moveth });
+		let action = runtime
+			.process_event((BridgeContractEvent::Initiated(details), ChainId::ONE).into())
+			.unwrap();
+		let state = runtime.swap_state_map.get(&transfer_id).unwrap();
+		assert_eq!(state.to_record().amount, Amount(AssetType::EthAndWeth((0, 9_900))));
+		assert_eq!(state.locked_amount, Amount(AssetType::Moveth(9_900)));
+		match action.kind {
+			TransferActionType::LockBridgeTransfer { amount, .. } => {
+				assert_eq!(amount.value(), 9_900)
+			}
+			kind => panic!("Unexpected action {kind}"),
+		}
+	}
+
+	#[test]
+	fn test_rejected_token_transfers_are_not_initiated() {
+		let guard = guard("reject");
+		assert!(matches!(
+			guard.check(TOKEN, Amount(AssetType::EthAndWeth((0, 100)))),
+			Err(BridgeContractError::FeeOnTransferRejected(_))
+		));
+		// Only the token part of the transfer is concerned.
+		assert!(guard.check(TOKEN, Amount(AssetType::EthAndWeth((100, 0)))).is_ok());
+		// Nor locked once the fee was taken.
+		let amount = Amount(AssetType::EthAndWeth((0, 10_000)));
+		assert_eq!(guard.locked_amount(TOKEN, amount, 100), None);
+		assert_eq!(guard.locked_amount(TOKEN, amount, 0), Some(amount));
+		assert!(FeeOnTransferGuard::new(&[FeeOnTransferToken {
+			token: TOKEN.to_string(),
+			policy: "ignore".to_string(),
+		}])
+		.is_err());
+	}
+}
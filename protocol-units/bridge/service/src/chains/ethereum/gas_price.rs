@@ -0,0 +1,232 @@
+use alloy::contract::{CallBuilder, CallDecoder};
+use alloy::network::Ethereum;
+use alloy::providers::Provider;
+use alloy::transports::Transport;
+use bridge_config::common::eth::EthConfig;
+
+/// How the fees of the transactions are set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GasPriceStrategy {
+	/// EIP-1559 fees estimated by the node from the fee history.
+	Oracle,
+	/// Legacy transactions at a fixed gas price, in wei.
+	Fixed { gas_price: u128 },
+	/// EIP-1559 transactions with fixed fee caps, in wei.
+	Eip1559 { max_fee_per_gas: u128, max_priority_fee_per_gas: u128 },
+}
+
+/// Fees of one attempt to send a transaction, in wei.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GasFees {
+	Legacy { gas_price: u128 },
+	Eip1559 { max_fee_per_gas: u128, max_priority_fee_per_gas: u128 },
+}
+
+impl GasFees {
+	/// Most the transaction pays per unit of gas.
+	pub fn max_fee_per_gas(&self) -> u128 {
+		match self {
+			GasFees::Legacy { gas_price } => *gas_price,
+			GasFees::Eip1559 { max_fee_per_gas, .. } => *max_fee_per_gas,
+		}
+	}
+
+	/// The fees raised by `percent`, to replace an underpriced transaction.
+	pub fn bumped(self, percent: u64) -> Self {
+		let bump = |fee: u128| fee + fee * percent as u128 / 100;
+		match self {
+			GasFees::Legacy { gas_price } => GasFees::Legacy { gas_price: bump(gas_price) },
+			GasFees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => GasFees::Eip1559 {
+				max_fee_per_gas: bump(max_fee_per_gas),
+				max_priority_fee_per_gas: bump(max_priority_fee_per_gas),
+			},
+		}
+	}
+
+	/// Set the fees of the transaction sent by `call`.
+	pub fn apply<T, P, D>(
+		self,
+		call: CallBuilder<T, P, D, Ethereum>,
+	) -> CallBuilder<T, P, D, Ethereum>
+	where
+		T: Transport + Clone,
+		P: Provider<T, Ethereum>,
+		D: CallDecoder,
+	{
+		match self {
+			GasFees::Legacy { gas_price } => call.gas_price(gas_price),
+			GasFees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => call
+				.max_fee_per_gas(max_fee_per_gas)
+				.max_priority_fee_per_gas(max_priority_fee_per_gas),
+		}
+	}
+}
+
+/// Fees of the transactions, and how they are raised when a transaction is rejected as
+/// underpriced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasPricing {
+	pub strategy: GasPriceStrategy,
+	pub bump_percent: u64,
+	pub max_bumps: u32,
+}
+
+impl Default for GasPricing {
+	fn default() -> Self {
+		GasPricing { strategy: GasPriceStrategy::Oracle, bump_percent: 10, max_bumps: 3 }
+	}
+}
+
+impl TryFrom<&EthConfig> for GasPricing {
+	type Error = anyhow::Error;
+
+	fn try_from(conf: &EthConfig) -> Result<Self, Self::Error> {
+		let strategy = match conf.eth_gas_price_strategy.as_str() {
+			"oracle" => GasPriceStrategy::Oracle,
+			"fixed" => {
+				if conf.eth_gas_price_wei == 0 {
+					anyhow::bail!("The fixed gas price strategy requires a gas price");
+				}
+				GasPriceStrategy::Fixed { gas_price: conf.eth_gas_price_wei.into() }
+			}
+			"eip1559" => {
+				if conf.eth_max_fee_per_gas_wei == 0 {
+					anyhow::bail!("The eip1559 gas price strategy requires a max fee per gas");
+				}
+				if conf.eth_max_priority_fee_per_gas_wei > conf.eth_max_fee_per_gas_wei {
+					anyhow::bail!("The max priority fee per gas is above the max fee per gas");
+				}
+				GasPriceStrategy::Eip1559 {
+					max_fee_per_gas: conf.eth_max_fee_per_gas_wei.into(),
+					max_priority_fee_per_gas: conf.eth_max_priority_fee_per_gas_wei.into(),
+				}
+			}
+			strategy => anyhow::bail!("Unknown gas price strategy: {strategy}"),
+		};
+		Ok(GasPricing {
+			strategy,
+			bump_percent: conf.eth_gas_price_bump_percent,
+			max_bumps: conf.eth_gas_price_max_bumps,
+		})
+	}
+}
+
+impl GasPricing {
+	/// Fees of the first attempt to send a transaction.
+	pub async fn fees<T, P>(&self, provider: &P) -> Result<GasFees, anyhow::Error>
+	where
+		T: Transport + Clone,
+		P: Provider<T, Ethereum>,
+	{
+		Ok(match self.strategy {
+			GasPriceStrategy::Oracle => {
+				let estimation = provider.estimate_eip1559_fees(None).await?;
+				GasFees::Eip1559 {
+					max_fee_per_gas: estimation.max_fee_per_gas,
+					max_priority_fee_per_gas: estimation.max_priority_fee_per_gas,
+				}
+			}
+			GasPriceStrategy::Fixed { gas_price } => GasFees::Legacy { gas_price },
+			GasPriceStrategy::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas } => {
+				GasFees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas }
+			}
+		})
+	}
+
+	/// Gas price the fee of a transaction sent with `fees` is checked against the gas limit with.
+	/// The max fee per gas estimated by the oracle is a bound of about twice the base fee, so its
+	/// transactions are checked with the gas price of the node, the price they're expected to
+	/// pay. The other strategies are checked with their fee cap.
+	pub async fn checked_gas_price<T, P>(
+		&self,
+		provider: &P,
+		fees: &GasFees,
+	) -> Result<u128, anyhow::Error>
+	where
+		T: Transport + Clone,
+		P: Provider<T, Ethereum>,
+	{
+		match self.strategy {
+			GasPriceStrategy::Oracle => Ok(provider.get_gas_price().await?),
+			GasPriceStrategy::Fixed { .. } | GasPriceStrategy::Eip1559 { .. } => {
+				Ok(fees.max_fee_per_gas())
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chains::rpc_headers::tests::mock_endpoint;
+	use alloy::contract::RawCallBuilder;
+	use alloy::primitives::Bytes;
+	use alloy::providers::ProviderBuilder;
+
+	fn eip1559_config() -> EthConfig {
+		EthConfig {
+			eth_gas_price_strategy: "eip1559".to_string(),
+			eth_max_fee_per_gas_wei: 50_000_000_000,
+			eth_max_priority_fee_per_gas_wei: 2_000_000_000,
+			eth_gas_price_bump_percent: 10,
+			..EthConfig::default()
+		}
+	}
+
+	#[tokio::test]
+	async fn test_transaction_carries_the_configured_max_fees() {
+		let provider = ProviderBuilder::new().on_http("http://localhost:8545".parse().unwrap());
+		let pricing = GasPricing::try_from(&eip1559_config()).unwrap();
+		let fees = pricing.fees(&provider).await.unwrap();
+
+		let call = RawCallBuilder::new_raw(&provider, Bytes::new());
+		let request = fees.apply(call).into_transaction_request();
+		assert_eq!(request.max_fee_per_gas, Some(50_000_000_000));
+		assert_eq!(request.max_priority_fee_per_gas, Some(2_000_000_000));
+		assert_eq!(request.gas_price, None);
+
+		// An underpriced transaction is resent with both fees raised.
+		let call = RawCallBuilder::new_raw(&provider, Bytes::new());
+		let request = fees.bumped(pricing.bump_percent).apply(call).into_transaction_request();
+		assert_eq!(request.max_fee_per_gas, Some(55_000_000_000));
+		assert_eq!(request.max_priority_fee_per_gas, Some(2_200_000_000));
+	}
+
+	#[tokio::test]
+	async fn test_oracle_fee_is_checked_with_the_node_gas_price() {
+		let (url, request) =
+			mock_endpoint(r#"{"jsonrpc":"2.0","id":0,"result":"0x3b9aca00"}"#).await;
+		let provider = ProviderBuilder::new().on_http(url.parse().unwrap());
+		let fees = GasFees::Eip1559 {
+			max_fee_per_gas: 3_000_000_000,
+			max_priority_fee_per_gas: 1_000_000_000,
+		};
+		let gas_price = GasPricing::default().checked_gas_price(&provider, &fees).await.unwrap();
+		assert_eq!(gas_price, 1_000_000_000);
+		assert!(request.await.unwrap().contains("eth_gasprice"));
+
+		// The configured fee caps are checked as set.
+		let pricing = GasPricing::try_from(&eip1559_config()).unwrap();
+		let fees = pricing.fees(&provider).await.unwrap();
+		assert_eq!(pricing.checked_gas_price(&provider, &fees).await.unwrap(), 50_000_000_000);
+	}
+
+	#[test]
+	fn test_invalid_gas_price_config_is_rejected() {
+		let unknown =
+			EthConfig { eth_gas_price_strategy: "auction".to_string(), ..eip1559_config() };
+		assert!(GasPricing::try_from(&unknown).is_err());
+		let no_price =
+			EthConfig { eth_gas_price_strategy: "fixed".to_string(), ..eip1559_config() };
+		assert!(GasPricing::try_from(&no_price).is_err());
+		let inverted =
+			EthConfig { eth_max_priority_fee_per_gas_wei: 60_000_000_000, ..eip1559_config() };
+		assert!(GasPricing::try_from(&inverted).is_err());
+
+		let fixed = EthConfig { eth_gas_price_wei: 30_000_000_000, ..no_price };
+		assert_eq!(
+			GasPricing::try_from(&fixed).unwrap().strategy,
+			GasPriceStrategy::Fixed { gas_price: 30_000_000_000 }
+		);
+	}
+}
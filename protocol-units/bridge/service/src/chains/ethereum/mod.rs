@@ -1,4 +1,7 @@
 pub mod client;
 pub mod event_monitoring;
+pub mod event_source;
+pub mod fee_on_transfer;
+pub mod gas_price;
 pub mod types;
 pub mod utils;
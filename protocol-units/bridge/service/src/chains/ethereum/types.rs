@@ -18,6 +18,7 @@ use alloy::rlp::{RlpDecodable, RlpEncodable};
 use alloy::sol_types::SolEvent;
 use alloy::transports::BoxTransport;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 pub const INITIATOR_INITIATED_SELECT: FixedBytes<32> =
 	AtomicBridgeInitiator::BridgeTransferInitiated::SIGNATURE_HASH;
@@ -119,7 +120,9 @@ pub type AlloyProvider = FillProvider<
 	Ethereum,
 >;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, RlpEncodable, RlpDecodable)]
+#[derive(
+	Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize, RlpEncodable, RlpDecodable,
+)]
 pub struct EthAddress(pub Address);
 
 impl From<EthAddress> for Vec<u8> {
@@ -1,12 +1,16 @@
 use std::str::FromStr;
 
+use crate::chains::bridge_contracts::BridgeTransferResult;
+use crate::chains::ethereum::gas_price::GasPricing;
 use crate::chains::ethereum::types::EthAddress;
+use crate::types::BridgeTransferId;
 use alloy::contract::{CallBuilder, CallDecoder};
 use alloy::network::Ethereum;
 use alloy::primitives::U256;
 use alloy::providers::Provider;
 use alloy::rlp::{Encodable, RlpEncodable};
 use alloy::rpc::types::TransactionReceipt;
+use alloy::sol_types::SolEvent;
 use alloy::transports::Transport;
 use keccak_hash::keccak;
 use mcr_settlement_client::send_eth_transaction::{
@@ -28,6 +32,10 @@ pub enum EthUtilError {
 	GasLimitExceed(u128, u128),
 	#[error("RpcTransactionExecution: {0}")]
 	RpcTransactionExecution(String),
+	#[error("TransactionReverted: {0}")]
+	TransactionReverted(String),
+	#[error("Transaction still rejected after {0} fee bumps: {1}")]
+	FeeBumpsExceeded(u32, String),
 }
 
 impl FromStr for EthAddress {
@@ -61,6 +69,33 @@ pub fn calculate_storage_slot(key: [u8; 32], mapping_slot: U256) -> U256 {
 	U256::from_be_slice(&hash.0)
 }
 
+/// Returns true if the receipt contains a log of the event `E`.
+pub fn receipt_has_event<E: SolEvent>(receipt: &TransactionReceipt) -> bool {
+	receipt.inner.logs().iter().any(|log| log.topic0() == Some(&E::SIGNATURE_HASH))
+}
+
+/// Decodes the first log of the event `E` in the receipt.
+pub fn receipt_event<E: SolEvent>(receipt: &TransactionReceipt) -> Option<E> {
+	receipt
+		.inner
+		.logs()
+		.iter()
+		.find(|log| log.topic0() == Some(&E::SIGNATURE_HASH))
+		.and_then(|log| E::decode_log_data(log.data(), true).ok())
+}
+
+pub fn transfer_result(
+	transfer_id: BridgeTransferId,
+	receipt: &TransactionReceipt,
+) -> BridgeTransferResult {
+	BridgeTransferResult {
+		transfer_id,
+		tx_hash: receipt.transaction_hash.0,
+		block: receipt.block_number.unwrap_or_default(),
+		gas_used: receipt.gas_used as u64,
+	}
+}
+
 pub fn send_transaction_rules() -> Vec<Box<dyn VerifyRule>> {
 	let rule1: Box<dyn VerifyRule> = Box::new(SendTransactionErrorRule::<UnderPriced>::new());
 	let rule2: Box<dyn VerifyRule> = Box::new(SendTransactionErrorRule::<InsufficentFunds>::new());
@@ -76,6 +111,7 @@ pub async fn send_transaction<
 	send_transaction_error_rules: &[Box<dyn VerifyRule>],
 	number_retry: u32,
 	gas_limit: u128,
+	gas_pricing: &GasPricing,
 ) -> Result<TransactionReceipt, anyhow::Error> {
 	println!("base_call_builder: {:?}", base_call_builder);
 	println!("Sending transaction with gas limit: {}", gas_limit);
@@ -86,38 +122,48 @@ pub async fn send_transaction<
 
 	println!("estimated_gas: {}", estimate_gas);
 
+	let mut fees = gas_pricing.fees::<T, P>(base_call_builder.provider).await?;
+	let mut fee_bumps = 0;
+
 	// Sending Transaction automatically can lead to errors that depend on the state for Eth.
 	// It's convenient to manage some of them automatically to avoid to fail commitment Transaction.
 	// I define a first one but other should be added depending on the test with mainnet.
 	for _ in 0..number_retry {
-		let call_builder = base_call_builder.clone().gas(estimate_gas);
+		let call_builder = fees.apply(base_call_builder.clone().gas(estimate_gas));
 
 		//detect if the gas price doesn't execeed the limit.
-		let gas_price = call_builder.provider.get_gas_price().await?;
+		let gas_price = gas_pricing.checked_gas_price(call_builder.provider, &fees).await?;
 		let transaction_fee_wei = estimate_gas * gas_price;
 		if transaction_fee_wei > gas_limit {
 			return Err(EthUtilError::GasLimitExceed(transaction_fee_wei, gas_limit).into());
 		}
 
-		println!("Sending transaction with gas: {}", estimate_gas);
+		tracing::debug!("Sending transaction with gas: {estimate_gas} and fees: {fees:?}");
 
 		//send the Transaction and detect send error.
 		let pending_transaction = match call_builder.send().await {
 			Ok(pending_transaction) => pending_transaction,
 			Err(err) => {
-				//apply defined rules.
+				// Verify all rules. If one rule return true or an error stop verification.
+				// If true resend with higher fees else return the error.
+				let mut resend = false;
 				for rule in send_transaction_error_rules {
-					// Verify all rules. If one rule return true or an error stop verification.
-					// If true retry with more gas else return the error.
 					if rule.verify(&err)? {
-						//increase gas of 10% and retry
-						estimate_gas += (estimate_gas * 10) / 100;
-						tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-						continue;
+						resend = true;
+						break;
 					}
 				}
-
-				return Err(EthUtilError::from(err).into());
+				if !resend {
+					return Err(EthUtilError::from(err).into());
+				}
+				if fee_bumps == gas_pricing.max_bumps {
+					return Err(EthUtilError::FeeBumpsExceeded(fee_bumps, err.to_string()).into());
+				}
+				fee_bumps += 1;
+				fees = fees.bumped(gas_pricing.bump_percent);
+				tracing::warn!("Transaction underpriced, resending with fees {fees:?}: {err}");
+				tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+				continue;
 			}
 		};
 
@@ -136,8 +182,13 @@ pub async fn send_transaction<
 					estimate_gas += (estimate_gas * 30) / 100;
 					continue;
 				} else {
-					return Err(EthUtilError::RpcTransactionExecution(format!(
-						"Send commitment Transaction fail, abort Transaction, receipt:{transaction_receipt:?}"
+					// Replay the call to get the revert reason.
+					let reason = match call_builder.call_raw().await {
+						Err(err) => err.to_string(),
+						Ok(_) => "unknown revert reason".to_string(),
+					};
+					return Err(EthUtilError::TransactionReverted(format!(
+						"{reason}, receipt:{transaction_receipt:?}"
 					))
 					.into());
 				}
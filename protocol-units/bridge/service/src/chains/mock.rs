@@ -0,0 +1,684 @@
+use crate::chains::bridge_contracts::counterparty_complete_individually;
+use crate::chains::bridge_contracts::BridgeContract;
+use crate::chains::bridge_contracts::BridgeContractError;
+use crate::chains::bridge_contracts::BridgeContractEvent;
+use crate::chains::bridge_contracts::BridgeContractMonitoring;
+use crate::chains::bridge_contracts::BridgeContractResult;
+use crate::chains::bridge_contracts::BridgeTransferResult;
+use crate::chains::bridge_contracts::EventFilter;
+use crate::chains::bridge_contracts::{BridgeTransferStatus, TransferStatusDetails};
+use crate::types::{
+	Amount, BridgeAddress, BridgeTransferDetails, BridgeTransferId, HashAlgorithm, HashLock,
+	HashLockPreImage, LockDetails, TimeLock,
+};
+use crate::unix_now;
+use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
+use futures::{Stream, StreamExt};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::{pin::Pin, task::Poll};
+
+/// Time lock duration, in seconds, of the transfers created by the mock chain.
+const MOCK_TIME_LOCK: u64 = 3600;
+
+// Encoding of the contract states, both are only exposed as `BridgeTransferStatus`.
+#[derive(Clone, Copy)]
+struct ContractStates {
+	initialized: u8,
+	completed: u8,
+	refunded: u8,
+}
+
+const MOVE_STATES: ContractStates = ContractStates { initialized: 1, completed: 2, refunded: 3 };
+const SOLIDITY_STATES: ContractStates =
+	ContractStates { initialized: 0, completed: 1, refunded: 2 };
+
+/// Gas used by every mock transaction.
+const MOCK_GAS_USED: u64 = 21_000;
+
+struct MockChainState<A> {
+	initiated: HashMap<BridgeTransferId, BridgeTransferDetails<A>>,
+	locked: HashMap<BridgeTransferId, BridgeTransferDetails<A>>,
+	events: Vec<BridgeContractEvent<A>>,
+	// Incremented by every call returning its transaction.
+	block: u64,
+	filter: EventFilter,
+	listener: UnboundedSender<BridgeContractResult<BridgeContractEvent<A>>>,
+	// Hash function the completions are verified with, None to accept any secret.
+	hash_algorithm: Option<HashAlgorithm>,
+	states: ContractStates,
+}
+
+// Time locks are unix timestamps, like on the real chains.
+fn mock_time_lock() -> TimeLock {
+	TimeLock(unix_now() + MOCK_TIME_LOCK)
+}
+
+// Returns true if `secret` unlocks the hash lock of `details`, like the contracts check.
+fn unlocks<A>(
+	hash_algorithm: Option<HashAlgorithm>,
+	details: &BridgeTransferDetails<A>,
+	secret: &HashLockPreImage,
+) -> bool {
+	hash_algorithm.map_or(true, |algorithm| secret.verify(&details.hash_lock, algorithm))
+}
+
+impl<A: Clone> MockChainState<A> {
+	fn emit(&mut self, event: BridgeContractEvent<A>) {
+		self.events.push(event.clone());
+		if !self.filter.accepts(event.event_type()) {
+			return;
+		}
+		// The monitoring can be dropped, the event is still recorded.
+		let _ = self.listener.unbounded_send(Ok(event));
+	}
+
+	fn transaction(&mut self, transfer_id: BridgeTransferId) -> BridgeTransferResult {
+		self.block += 1;
+		BridgeTransferResult {
+			transfer_id,
+			tx_hash: rand::random(),
+			block: self.block,
+			gas_used: MOCK_GAS_USED,
+		}
+	}
+}
+
+/// In memory implementation of the bridge contracts.
+/// Every contract call updates the chain state and emits the matching event
+/// on the associated [MockMonitoring].
+#[derive(Clone)]
+pub struct MockChain<A> {
+	state: Arc<Mutex<MockChainState<A>>>,
+}
+
+/// Event stream of a [MockChain].
+pub struct MockMonitoring<A> {
+	listener: UnboundedReceiver<BridgeContractResult<BridgeContractEvent<A>>>,
+}
+
+impl<A> BridgeContractMonitoring for MockMonitoring<A> {
+	type Address = A;
+
+	fn stop(&mut self) {
+		self.listener.close();
+	}
+}
+
+impl<A> Stream for MockMonitoring<A> {
+	type Item = BridgeContractResult<BridgeContractEvent<A>>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut std::task::Context) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+		this.listener.poll_next_unpin(cx)
+	}
+}
+
+impl<A: Clone> MockChain<A> {
+	/// Build a mock chain and the monitoring of its events.
+	pub fn build() -> (Self, MockMonitoring<A>) {
+		MockChain::build_with_filter(EventFilter::all())
+	}
+
+	/// Build a mock chain whose monitoring only emits the event types accepted by `filter`.
+	/// Filtered out events are still recorded by the chain.
+	pub fn build_with_filter(filter: EventFilter) -> (Self, MockMonitoring<A>) {
+		let (sender, listener) = futures::channel::mpsc::unbounded();
+		let state = MockChainState {
+			initiated: HashMap::new(),
+			locked: HashMap::new(),
+			events: Vec::new(),
+			block: 0,
+			filter,
+			listener: sender,
+			hash_algorithm: None,
+			states: MOVE_STATES,
+		};
+		(MockChain { state: Arc::new(Mutex::new(state)) }, MockMonitoring { listener })
+	}
+
+	/// Verify the secrets of the completions with `algorithm`.
+	pub fn with_hash_algorithm(self, algorithm: HashAlgorithm) -> Self {
+		self.with_state(|state| state.hash_algorithm = Some(algorithm));
+		self
+	}
+
+	/// Encode the transfer states like the Solidity contracts, from 0.
+	pub fn with_solidity_states(self) -> Self {
+		self.with_state(|state| state.states = SOLIDITY_STATES);
+		self
+	}
+
+	/// All the events emitted by the chain, in order.
+	pub fn events(&self) -> Vec<BridgeContractEvent<A>> {
+		self.state.lock().expect("Mock chain lock poisoned").events.clone()
+	}
+
+	fn with_state<T>(&self, f: impl FnOnce(&mut MockChainState<A>) -> T) -> T {
+		let mut state = self.state.lock().expect("Mock chain lock poisoned");
+		f(&mut state)
+	}
+}
+
+#[async_trait::async_trait]
+impl<A> BridgeContract<A> for MockChain<A>
+where
+	A: Clone + Send + Sync + Unpin + From<Vec<u8>> + 'static,
+	Vec<u8>: From<A>,
+{
+	async fn initiate_bridge_transfer(
+		&mut self,
+		initiator_address: BridgeAddress<A>,
+		recipient_address: BridgeAddress<Vec<u8>>,
+		hash_lock: HashLock,
+		amount: Amount,
+	) -> BridgeContractResult<BridgeTransferResult> {
+		let bridge_transfer_id = BridgeTransferId::gen_unique_hash(&mut rand::thread_rng());
+		Ok(self.with_state(|state| {
+			let details = BridgeTransferDetails {
+				bridge_transfer_id,
+				initiator_address,
+				recipient_address,
+				hash_lock,
+				time_lock: mock_time_lock(),
+				amount,
+				state: state.states.initialized,
+			};
+			state.initiated.insert(bridge_transfer_id, details.clone());
+			state.emit(BridgeContractEvent::Initiated(details));
+			state.transaction(bridge_transfer_id)
+		}))
+	}
+
+	async fn initiator_complete_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		secret: HashLockPreImage,
+	) -> BridgeContractResult<()> {
+		self.with_state(|state| {
+			let (hash_algorithm, states) = (state.hash_algorithm, state.states);
+			let details = state
+				.initiated
+				.get_mut(&bridge_transfer_id)
+				.filter(|details| details.state == states.initialized)
+				.filter(|details| unlocks(hash_algorithm, details, &secret))
+				.ok_or(BridgeContractError::CompleteTransferError)?;
+			details.state = states.completed;
+			let asset = details.amount.tag();
+			state.emit(BridgeContractEvent::InitialtorCompleted(bridge_transfer_id, asset));
+			Ok(())
+		})
+	}
+
+	async fn counterparty_complete_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		secret: HashLockPreImage,
+	) -> BridgeContractResult<BridgeTransferResult> {
+		self.with_state(|state| {
+			let (hash_algorithm, states) = (state.hash_algorithm, state.states);
+			let details = state
+				.locked
+				.get_mut(&bridge_transfer_id)
+				.filter(|details| details.state == states.initialized)
+				.filter(|details| unlocks(hash_algorithm, details, &secret))
+				.ok_or(BridgeContractError::CompleteTransferError)?;
+			details.state = states.completed;
+			let asset = details.amount.tag();
+			state.emit(BridgeContractEvent::CounterPartCompleted(
+				bridge_transfer_id,
+				secret,
+				asset,
+			));
+			Ok(state.transaction(bridge_transfer_id))
+		})
+	}
+
+	async fn counterparty_complete_bridge_transfers(
+		&mut self,
+		transfers: Vec<(BridgeTransferId, HashLockPreImage)>,
+	) -> Vec<BridgeContractResult<BridgeTransferResult>> {
+		let batch = self.with_state(|state| {
+			// Like the contracts, the batch reverts as a whole if one transfer can't be completed.
+			let mut ids = HashSet::new();
+			let completable = transfers.iter().all(|(bridge_transfer_id, secret)| {
+				ids.insert(*bridge_transfer_id)
+					&& state.locked.get(bridge_transfer_id).is_some_and(|details| {
+						details.state == state.states.initialized
+							&& unlocks(state.hash_algorithm, details, secret)
+					})
+			});
+			if transfers.is_empty() || !completable {
+				return None;
+			}
+			let transaction = state.transaction(transfers[0].0);
+			let mut results = Vec::with_capacity(transfers.len());
+			for (bridge_transfer_id, secret) in &transfers {
+				let details = state.locked.get_mut(bridge_transfer_id)?;
+				details.state = state.states.completed;
+				let asset = details.amount.tag();
+				state.emit(BridgeContractEvent::CounterPartCompleted(
+					*bridge_transfer_id,
+					*secret,
+					asset,
+				));
+				results.push(Ok(BridgeTransferResult {
+					transfer_id: *bridge_transfer_id,
+					..transaction.clone()
+				}));
+			}
+			Some(results)
+		});
+		match batch {
+			Some(results) => results,
+			None => counterparty_complete_individually(self, transfers).await,
+		}
+	}
+
+	async fn refund_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<()> {
+		self.with_state(|state| {
+			let states = state.states;
+			let details = state
+				.initiated
+				.get_mut(&bridge_transfer_id)
+				.filter(|details| details.state == states.initialized)
+				.ok_or(BridgeContractError::GenericError(format!(
+					"No refundable transfer for {bridge_transfer_id}"
+				)))?;
+			if unix_now() < details.time_lock.0 {
+				return Err(BridgeContractError::RefundBeforeExpiry(
+					bridge_transfer_id,
+					details.time_lock.0,
+				));
+			}
+			details.state = states.refunded;
+			let asset = details.amount.tag();
+			state.emit(BridgeContractEvent::Refunded(bridge_transfer_id, asset));
+			Ok(())
+		})
+	}
+
+	async fn get_bridge_transfer_details_initiator(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<BridgeTransferDetails<A>>> {
+		Ok(self.with_state(|state| state.initiated.get(&bridge_transfer_id).cloned()))
+	}
+
+	async fn get_bridge_transfer_details_counterparty(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<BridgeTransferDetails<A>>> {
+		Ok(self.with_state(|state| state.locked.get(&bridge_transfer_id).cloned()))
+	}
+
+	async fn get_bridge_transfer_details(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<BridgeTransferStatus> {
+		Ok(self.with_state(|state| {
+			let (details, initiator) = match (
+				state.initiated.get(&bridge_transfer_id),
+				state.locked.get(&bridge_transfer_id),
+			) {
+				(Some(details), _) => (details, true),
+				(None, Some(details)) => (details, false),
+				(None, None) => return BridgeTransferStatus::NotFound,
+			};
+			let status_details = TransferStatusDetails {
+				initiator_address: details.initiator_address.0.clone().into(),
+				recipient_address: details.recipient_address.0.clone(),
+				hash_lock: details.hash_lock,
+				time_lock: details.time_lock,
+				amount: details.amount,
+			};
+			match (details.state, initiator) {
+				(completed, _) if completed == state.states.completed => {
+					BridgeTransferStatus::Completed(status_details)
+				}
+				(refunded, _) if refunded == state.states.refunded => {
+					BridgeTransferStatus::Refunded(status_details)
+				}
+				(_, true) => BridgeTransferStatus::Initiated(status_details),
+				(_, false) => BridgeTransferStatus::Locked(status_details),
+			}
+		}))
+	}
+
+	async fn lock_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+		hash_lock: HashLock,
+		initiator: BridgeAddress<Vec<u8>>,
+		recipient: BridgeAddress<A>,
+		amount: Amount,
+	) -> BridgeContractResult<()> {
+		self.with_state(|state| {
+			if state.locked.contains_key(&bridge_transfer_id) {
+				return Err(BridgeContractError::LockTransferError);
+			}
+			let details = BridgeTransferDetails {
+				bridge_transfer_id,
+				initiator_address: BridgeAddress(initiator.0.clone().into()),
+				recipient_address: BridgeAddress(recipient.0.clone().into()),
+				hash_lock,
+				time_lock: mock_time_lock(),
+				amount,
+				state: state.states.initialized,
+			};
+			state.locked.insert(bridge_transfer_id, details);
+			state.emit(BridgeContractEvent::Locked(LockDetails {
+				bridge_transfer_id,
+				initiator_address: initiator,
+				recipient_address: recipient,
+				hash_lock,
+				time_lock: mock_time_lock(),
+				amount,
+			}));
+			Ok(())
+		})
+	}
+
+	async fn abort_bridge_transfer(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<()> {
+		self.with_state(|state| {
+			let states = state.states;
+			let details = state
+				.locked
+				.get_mut(&bridge_transfer_id)
+				.filter(|details| details.state == states.initialized)
+				.ok_or(BridgeContractError::AbortTransferError)?;
+			details.state = states.refunded;
+			let asset = details.amount.tag();
+			state.emit(BridgeContractEvent::Cancelled(bridge_transfer_id, asset));
+			Ok(())
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chains::bridge_contracts::BridgeContractEventType;
+	use crate::types::AssetType;
+
+	#[tokio::test]
+	async fn test_filtered_out_events_are_not_emitted() {
+		let (mut chain, mut monitoring) =
+			MockChain::<Vec<u8>>::build_with_filter(EventFilter::only([
+				BridgeContractEventType::Locked,
+				BridgeContractEventType::Cancelled,
+			]));
+		let transfer_id = BridgeTransferId([1; 32]);
+
+		chain
+			.initiate_bridge_transfer(
+				BridgeAddress(vec![1]),
+				BridgeAddress(vec![2]),
+				HashLock([3; 32]),
+				Amount(AssetType::Moveth(1)),
+			)
+			.await
+			.unwrap();
+		chain
+			.lock_bridge_transfer(
+				transfer_id,
+				HashLock([3; 32]),
+				BridgeAddress(vec![1]),
+				BridgeAddress(vec![2]),
+				Amount(AssetType::Moveth(1)),
+			)
+			.await
+			.unwrap();
+		chain
+			.counterparty_complete_bridge_transfer(transfer_id, HashLockPreImage([4; 32]))
+			.await
+			.unwrap();
+		drop(chain);
+
+		let emitted: Vec<_> =
+			monitoring.by_ref().map(|event| event.unwrap().event_type()).collect().await;
+		assert_eq!(emitted, vec![BridgeContractEventType::Locked]);
+	}
+
+	#[tokio::test]
+	async fn test_transfer_calls_return_their_transaction() {
+		let (mut chain, _monitoring) = MockChain::<Vec<u8>>::build();
+		let initiated = chain
+			.initiate_bridge_transfer(
+				BridgeAddress(vec![1]),
+				BridgeAddress(vec![2]),
+				HashLock([3; 32]),
+				Amount(AssetType::Moveth(1)),
+			)
+			.await
+			.unwrap();
+		assert!(matches!(
+			chain.events().as_slice(),
+			[BridgeContractEvent::Initiated(details)] if details.bridge_transfer_id == initiated.transfer_id
+		));
+		assert_ne!(initiated.tx_hash, [0; 32]);
+		assert_eq!(initiated.block, 1);
+		assert_eq!(initiated.gas_used, MOCK_GAS_USED);
+
+		let transfer_id = BridgeTransferId([1; 32]);
+		chain
+			.lock_bridge_transfer(
+				transfer_id,
+				HashLock([3; 32]),
+				BridgeAddress(vec![1]),
+				BridgeAddress(vec![2]),
+				Amount(AssetType::Moveth(1)),
+			)
+			.await
+			.unwrap();
+		let completed = chain
+			.counterparty_complete_bridge_transfer(transfer_id, HashLockPreImage([4; 32]))
+			.await
+			.unwrap();
+		assert_eq!(completed.transfer_id, transfer_id);
+		assert_ne!(completed.tx_hash, initiated.tx_hash);
+		assert_eq!(completed.block, 2);
+		assert_eq!(completed.gas_used, MOCK_GAS_USED);
+	}
+
+	#[tokio::test]
+	async fn test_batch_completion_emits_an_event_per_transfer() {
+		let (mut chain, mut monitoring) =
+			MockChain::<Vec<u8>>::build_with_filter(EventFilter::only([
+				BridgeContractEventType::CounterPartCompleted,
+			]));
+		let transfer_ids: Vec<_> = (1..=3).map(|i| BridgeTransferId([i; 32])).collect();
+		for transfer_id in &transfer_ids {
+			chain
+				.lock_bridge_transfer(
+					*transfer_id,
+					HashLock([3; 32]),
+					BridgeAddress(vec![1]),
+					BridgeAddress(vec![2]),
+					Amount(AssetType::Moveth(1)),
+				)
+				.await
+				.unwrap();
+		}
+
+		let results = chain
+			.counterparty_complete_bridge_transfers(
+				transfer_ids.iter().map(|id| (*id, HashLockPreImage(id.0))).collect(),
+			)
+			.await;
+		let results: Vec<_> = results.into_iter().map(Result::unwrap).collect();
+		// A single transaction completed the three transfers.
+		assert_eq!(
+			results.iter().map(|result| result.transfer_id).collect::<Vec<_>>(),
+			transfer_ids
+		);
+		assert!(results.iter().all(|result| result.tx_hash == results[0].tx_hash));
+		assert!(results.iter().all(|result| result.block == results[0].block));
+		drop(chain);
+
+		let completed: Vec<_> = monitoring
+			.by_ref()
+			.map(|event| match event.unwrap() {
+				BridgeContractEvent::CounterPartCompleted(transfer_id, secret, _) => {
+					(transfer_id, secret)
+				}
+				event => panic!("Unexpected event {event:?}"),
+			})
+			.collect()
+			.await;
+		assert_eq!(
+			completed,
+			transfer_ids.iter().map(|id| (*id, HashLockPreImage(id.0))).collect::<Vec<_>>()
+		);
+	}
+
+	#[tokio::test]
+	async fn test_reverted_batch_completes_the_transfers_one_by_one() {
+		let (mut chain, _monitoring) = MockChain::<Vec<u8>>::build();
+		let locked = BridgeTransferId([1; 32]);
+		chain
+			.lock_bridge_transfer(
+				locked,
+				HashLock([3; 32]),
+				BridgeAddress(vec![1]),
+				BridgeAddress(vec![2]),
+				Amount(AssetType::Moveth(1)),
+			)
+			.await
+			.unwrap();
+
+		// The unknown transfer reverts the batch, the locked one is still completed.
+		let results = chain
+			.counterparty_complete_bridge_transfers(vec![
+				(BridgeTransferId([2; 32]), HashLockPreImage([4; 32])),
+				(locked, HashLockPreImage([4; 32])),
+			])
+			.await;
+		assert!(matches!(
+			results.as_slice(),
+			[Err(BridgeContractError::CompleteTransferError), Ok(result)] if result.transfer_id == locked
+		));
+		assert!(matches!(
+			chain.events().as_slice(),
+			[BridgeContractEvent::Locked(_), BridgeContractEvent::CounterPartCompleted(transfer_id, ..)]
+				if *transfer_id == locked
+		));
+	}
+
+	#[tokio::test]
+	async fn test_stopped_monitoring_ends_promptly() {
+		let (mut chain, mut monitoring) = MockChain::<Vec<u8>>::build();
+		chain
+			.initiate_bridge_transfer(
+				BridgeAddress(vec![1]),
+				BridgeAddress(vec![2]),
+				HashLock([3; 32]),
+				Amount(AssetType::Moveth(1)),
+			)
+			.await
+			.unwrap();
+		monitoring.stop();
+		assert!(chain.state.lock().unwrap().listener.is_closed());
+
+		// The event received before the stop is still delivered, then the stream ends.
+		let events =
+			tokio::time::timeout(std::time::Duration::from_secs(1), monitoring.collect::<Vec<_>>())
+				.await
+				.expect("Stopped monitoring didn't end");
+		assert!(matches!(events.as_slice(), [Ok(BridgeContractEvent::Initiated(_))]));
+	}
+
+	#[tokio::test]
+	async fn test_refund_is_rejected_before_the_time_lock_expires() {
+		let (mut chain, monitoring) = MockChain::<Vec<u8>>::build();
+		let transfer_id = chain
+			.initiate_bridge_transfer(
+				BridgeAddress(vec![1]),
+				BridgeAddress(vec![2]),
+				HashLock([3; 32]),
+				Amount(AssetType::Moveth(1)),
+			)
+			.await
+			.unwrap()
+			.transfer_id;
+
+		assert!(matches!(
+			chain.refund_bridge_transfer(transfer_id).await,
+			Err(BridgeContractError::RefundBeforeExpiry(id, _)) if id == transfer_id
+		));
+
+		// Let the time lock pass.
+		chain.state.lock().unwrap().initiated.get_mut(&transfer_id).unwrap().time_lock =
+			TimeLock(unix_now());
+		chain.refund_bridge_transfer(transfer_id).await.unwrap();
+		drop(chain);
+
+		let events: Vec<_> = monitoring.map(|event| event.unwrap()).collect().await;
+		assert!(matches!(
+			events.as_slice(),
+			[BridgeContractEvent::Initiated(_), BridgeContractEvent::Refunded(id, _)] if *id == transfer_id
+		));
+	}
+
+	#[tokio::test]
+	async fn test_transfer_status_follows_the_transfer() {
+		let (mut eth, _eth_monitoring) = MockChain::<Vec<u8>>::build();
+		let (mut movement, _movement_monitoring) = MockChain::<Vec<u8>>::build();
+		let amount = Amount(AssetType::EthAndWeth((0, 7)));
+		let transfer_id = eth
+			.initiate_bridge_transfer(
+				BridgeAddress(vec![1]),
+				BridgeAddress(vec![2]),
+				HashLock([3; 32]),
+				amount,
+			)
+			.await
+			.unwrap()
+			.transfer_id;
+		movement
+			.lock_bridge_transfer(
+				transfer_id,
+				HashLock([3; 32]),
+				BridgeAddress(vec![1]),
+				BridgeAddress(vec![2]),
+				amount,
+			)
+			.await
+			.unwrap();
+
+		let BridgeTransferStatus::Locked(locked) =
+			movement.get_bridge_transfer_details(transfer_id).await.unwrap()
+		else {
+			panic!("The transfer isn't locked");
+		};
+		assert_eq!(
+			(locked.initiator_address, locked.recipient_address, locked.hash_lock, locked.amount),
+			(vec![1], vec![2], HashLock([3; 32]), amount)
+		);
+		assert!(matches!(
+			eth.get_bridge_transfer_details(transfer_id).await.unwrap(),
+			BridgeTransferStatus::Initiated(initiated) if initiated.amount == amount
+		));
+
+		movement
+			.counterparty_complete_bridge_transfer(transfer_id, HashLockPreImage([4; 32]))
+			.await
+			.unwrap();
+		assert!(matches!(
+			movement.get_bridge_transfer_details(transfer_id).await.unwrap(),
+			BridgeTransferStatus::Completed(_)
+		));
+		// Unknown ids aren't an error.
+		let unknown = BridgeTransferId::gen_unique_hash(&mut rand::thread_rng());
+		assert_eq!(
+			eth.get_bridge_transfer_details(unknown).await.unwrap(),
+			BridgeTransferStatus::NotFound
+		);
+	}
+}
@@ -1,3 +1,6 @@
 pub mod bridge_contracts;
 pub mod ethereum;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 pub mod movement;
+pub mod rpc_headers;
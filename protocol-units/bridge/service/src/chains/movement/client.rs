@@ -1,7 +1,12 @@
 use super::utils::{self, MovementAddress};
+use crate::chains::bridge_contracts::counterparty_complete_individually;
 use crate::chains::bridge_contracts::BridgeContract;
 use crate::chains::bridge_contracts::BridgeContractError;
 use crate::chains::bridge_contracts::BridgeContractResult;
+use crate::chains::bridge_contracts::BridgeTransferResult;
+use crate::chains::bridge_contracts::{BridgeTransferStatus, TransferStatusDetails};
+use crate::chains::rpc_headers::{rpc_headers, rpc_http_client};
+use crate::self_check::ChainHealthCheck;
 use crate::types::{
 	Amount, AssetType, BridgeAddress, BridgeTransferDetails, BridgeTransferId, HashLock,
 	HashLockPreImage, TimeLock,
@@ -9,8 +14,9 @@ use crate::types::{
 use anyhow::Result;
 use aptos_api_types::{EntryFunctionId, MoveModuleId, ViewRequest};
 use aptos_sdk::{
+	coin_client::CoinClient,
 	move_types::identifier::Identifier,
-	rest_client::{Client, Response},
+	rest_client::{error::RestError, AptosBaseUrl, Client, Response},
 	types::LocalAccount,
 };
 use aptos_types::account_address::AccountAddress;
@@ -18,7 +24,7 @@ use bridge_config::common::movement::MovementConfig;
 use rand::prelude::*;
 use std::str::FromStr;
 use std::sync::Arc;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use url::Url;
 
 const INITIATOR_MODULE_NAME: &str = "atomic_bridge_initiator";
@@ -42,6 +48,8 @@ pub struct MovementClient {
 	pub non_native_address: Vec<u8>,
 	///The Apotos Rest Client
 	pub rest_client: Client,
+	/// Client of the REST requests the rest client doesn't cover, sends the same headers.
+	http_client: reqwest::Client,
 	///The signer account
 	signer: Arc<LocalAccount>,
 }
@@ -51,7 +59,13 @@ impl MovementClient {
 		let node_connection_url = Url::from_str(config.mvt_rpc_connection_url().as_str())
 			.map_err(|_| BridgeContractError::SerializationError)?;
 
-		let rest_client = Client::new(node_connection_url.clone());
+		let headers = rpc_headers(&config.mvt_rpc_headers, &config.mvt_rpc_bearer_token)?;
+		let mut rest_client_builder = Client::builder(AptosBaseUrl::Custom(node_connection_url));
+		for (name, value) in headers.iter() {
+			rest_client_builder = rest_client_builder.header(name.as_str(), value.to_str()?)?;
+		}
+		let rest_client = rest_client_builder.build();
+		let http_client = rpc_http_client(headers)?;
 
 		let signer =
 			utils::create_local_account(config.movement_signer_address.clone(), &rest_client)
@@ -61,6 +75,7 @@ impl MovementClient {
 			native_address,
 			non_native_address: Vec::new(), //dummy for now
 			rest_client,
+			http_client,
 			signer: Arc::new(signer),
 		})
 	}
@@ -69,6 +84,10 @@ impl MovementClient {
 		&self.rest_client
 	}
 
+	pub fn http_client(&self) -> &reqwest::Client {
+		&self.http_client
+	}
+
 	pub fn signer(&self) -> &LocalAccount {
 		&self.signer
 	}
@@ -114,6 +133,130 @@ impl MovementClient {
 
 		Ok(())
 	}
+
+	/// Status of the transfer in the store of `module`, None if the module doesn't know it.
+	async fn view_transfer_status(
+		&self,
+		module: &str,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<Option<BridgeTransferStatus>> {
+		let view_request = ViewRequest {
+			function: EntryFunctionId {
+				module: MoveModuleId {
+					address: self.native_address.into(),
+					name: aptos_api_types::IdentifierWrapper(
+						Identifier::new(module)
+							.map_err(|_| BridgeContractError::FunctionViewError)?,
+					),
+				},
+				name: aptos_api_types::IdentifierWrapper(
+					Identifier::new("bridge_transfers")
+						.map_err(|_| BridgeContractError::FunctionViewError)?,
+				),
+			},
+			type_arguments: vec![],
+			arguments: vec![serde_json::json!(format!("0x{}", hex::encode(bridge_transfer_id.0)))],
+		};
+		let values = match self.rest_client.view(&view_request, None).await {
+			Ok(response) => response.into_inner(),
+			// The view aborts on the ids missing from the store.
+			Err(RestError::Api(err)) if err.error.message.to_lowercase().contains("abort") => {
+				return Ok(None)
+			}
+			Err(_) => return Err(BridgeContractError::CallError),
+		};
+		if values.len() != 6 {
+			return Err(BridgeContractError::InvalidResponseLength);
+		}
+
+		// The initiator stores the Movement originator, the counterparty the Movement recipient.
+		let initiator = module == INITIATOR_MODULE_NAME;
+		let account_bytes = |value: Option<&serde_json::Value>| {
+			AccountAddress::from_hex_literal(utils::val_as_str_initiator(value)?)
+				.map(|address| address.to_vec())
+				.map_err(|_| BridgeContractError::SerializationError)
+		};
+		let hex_bytes = |value: Option<&serde_json::Value>| {
+			hex::decode(utils::val_as_str_initiator(value)?.trim_start_matches("0x"))
+				.map_err(|_| BridgeContractError::SerializationError)
+		};
+		let u64_value = |value: Option<&serde_json::Value>| {
+			utils::val_as_str_initiator(value)?
+				.parse::<u64>()
+				.map_err(|_| BridgeContractError::SerializationError)
+		};
+		let (initiator_address, recipient_address) = if initiator {
+			(account_bytes(values.first())?, hex_bytes(values.get(1))?)
+		} else {
+			(hex_bytes(values.first())?, account_bytes(values.get(1))?)
+		};
+		let details = TransferStatusDetails {
+			initiator_address,
+			recipient_address,
+			amount: Amount(AssetType::Moveth(u64_value(values.get(2))?)),
+			hash_lock: HashLock(
+				hex_bytes(values.get(3))?
+					.try_into()
+					.map_err(|_| BridgeContractError::SerializationError)?,
+			),
+			time_lock: TimeLock(u64_value(values.get(4))?),
+		};
+		Ok(Some(match (utils::val_as_u64_initiator(values.get(5))?, initiator) {
+			(2, _) => BridgeTransferStatus::Completed(details),
+			(3, _) => BridgeTransferStatus::Refunded(details),
+			(_, true) => BridgeTransferStatus::Initiated(details),
+			(_, false) => BridgeTransferStatus::Locked(details),
+		}))
+	}
+}
+
+#[async_trait::async_trait]
+impl ChainHealthCheck for MovementClient {
+	fn chain_name(&self) -> &'static str {
+		"Movement"
+	}
+
+	async fn check_rpc(&self) -> Result<(), String> {
+		self.rest_client
+			.get_ledger_information()
+			.await
+			.map(|_| ())
+			.map_err(|err| err.to_string())
+	}
+
+	// The bridge modules are published at the native address.
+	fn contracts(&self) -> Vec<(&'static str, String)> {
+		[("initiator", INITIATOR_MODULE_NAME), ("counterparty", COUNTERPARTY_MODULE_NAME)]
+			.into_iter()
+			.map(|(name, module)| {
+				(name, format!("{}::{module}", self.native_address.to_hex_literal()))
+			})
+			.collect()
+	}
+
+	async fn has_code(&self, address: &str) -> Result<bool, String> {
+		let (account, module) =
+			address.split_once("::").ok_or(format!("{address} isn't a module id"))?;
+		let account = AccountAddress::from_hex_literal(account).map_err(|err| err.to_string())?;
+		let module = self
+			.rest_client
+			.get_account_module(account, module)
+			.await
+			.map_err(|err| err.to_string())?;
+		Ok(!module.inner().bytecode.inner().is_empty())
+	}
+
+	fn signer_address(&self) -> String {
+		self.signer.address().to_hex_literal()
+	}
+
+	async fn signer_balance(&self) -> Result<u128, String> {
+		CoinClient::new(&self.rest_client)
+			.get_account_balance(&self.signer.address())
+			.await
+			.map(u128::from)
+			.map_err(|err| err.to_string())
+	}
 }
 
 #[async_trait::async_trait]
@@ -124,7 +267,7 @@ impl BridgeContract<MovementAddress> for MovementClient {
 		recipient: BridgeAddress<Vec<u8>>,
 		hash_lock: HashLock,
 		amount: Amount,
-	) -> BridgeContractResult<()> {
+	) -> BridgeContractResult<BridgeTransferResult> {
 		let amount_value = match amount.0 {
 			AssetType::Moveth(value) => value,
 			_ => return Err(BridgeContractError::ConversionFailed("Amount".to_string())),
@@ -145,15 +288,15 @@ impl BridgeContract<MovementAddress> for MovementClient {
 			args,
 		);
 
-		let _ = utils::send_and_confirm_aptos_transaction(
+		let txn = utils::send_and_confirm_aptos_transaction(
 			&self.rest_client,
 			self.signer.as_ref(),
 			payload,
 		)
 		.await
-		.map_err(|_| BridgeContractError::InitiateTransferError)?;
+		.map_err(BridgeContractError::from_message)?;
 
-		Ok(())
+		utils::transfer_result(utils::initiated_transfer_id(&txn)?, &txn)
 	}
 
 	async fn initiator_complete_bridge_transfer(
@@ -161,13 +304,7 @@ impl BridgeContract<MovementAddress> for MovementClient {
 		bridge_transfer_id: BridgeTransferId,
 		preimage: HashLockPreImage,
 	) -> BridgeContractResult<()> {
-		let unpadded_preimage = {
-			let mut end = preimage.0.len();
-			while end > 0 && preimage.0[end - 1] == 0 {
-				end -= 1;
-			}
-			&preimage.0[..end]
-		};
+		let unpadded_preimage = unpadded_preimage(&preimage);
 		let args2 = vec![
 			utils::serialize_vec_initiator(&bridge_transfer_id.0[..])?,
 			utils::serialize_vec_initiator(unpadded_preimage)?,
@@ -181,13 +318,9 @@ impl BridgeContract<MovementAddress> for MovementClient {
 			args2,
 		);
 
-		let _ = utils::send_and_confirm_aptos_transaction(
-			&self.rest_client,
-			self.signer.as_ref(),
-			payload,
-		)
-		.await
-		.map_err(|_| BridgeContractError::CompleteTransferError);
+		utils::send_and_confirm_aptos_transaction(&self.rest_client, self.signer.as_ref(), payload)
+			.await
+			.map_err(BridgeContractError::from_message)?;
 
 		Ok(())
 	}
@@ -196,17 +329,11 @@ impl BridgeContract<MovementAddress> for MovementClient {
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 		preimage: HashLockPreImage,
-	) -> BridgeContractResult<()> {
-		let unpadded_preimage = {
-			let mut end = preimage.0.len();
-			while end > 0 && preimage.0[end - 1] == 0 {
-				end -= 1;
-			}
-			&preimage.0[..end]
-		};
+	) -> BridgeContractResult<BridgeTransferResult> {
+		let unpadded_preimage = unpadded_preimage(&preimage);
 		let args2 = vec![
 			utils::serialize_vec(&bridge_transfer_id.0[..])?,
-			utils::serialize_vec(&unpadded_preimage)?,
+			utils::serialize_vec(unpadded_preimage)?,
 		];
 
 		let payload = utils::make_aptos_payload(
@@ -223,7 +350,7 @@ impl BridgeContract<MovementAddress> for MovementClient {
 			payload,
 		)
 		.await
-		.map_err(|_| BridgeContractError::CompleteTransferError);
+		.map_err(BridgeContractError::from_message);
 
 		match &result {
 			Ok(tx_result) => {
@@ -234,7 +361,59 @@ impl BridgeContract<MovementAddress> for MovementClient {
 			}
 		}
 
-		Ok(())
+		utils::transfer_result(bridge_transfer_id, &result?)
+	}
+
+	async fn counterparty_complete_bridge_transfers(
+		&mut self,
+		transfers: Vec<(BridgeTransferId, HashLockPreImage)>,
+	) -> Vec<BridgeContractResult<BridgeTransferResult>> {
+		if transfers.len() < 2 {
+			return counterparty_complete_individually(self, transfers).await;
+		}
+		let bridge_transfer_ids: Vec<&[u8]> = transfers
+			.iter()
+			.map(|(bridge_transfer_id, _)| &bridge_transfer_id.0[..])
+			.collect();
+		let preimages: Vec<&[u8]> =
+			transfers.iter().map(|(_, preimage)| unpadded_preimage(preimage)).collect();
+		let args =
+			match (utils::serialize_vec(&bridge_transfer_ids), utils::serialize_vec(&preimages)) {
+				(Ok(bridge_transfer_ids), Ok(preimages)) => vec![bridge_transfer_ids, preimages],
+				(Err(e), _) | (_, Err(e)) => {
+					return transfers.iter().map(|_| Err(e.clone())).collect()
+				}
+			};
+
+		let payload = utils::make_aptos_payload(
+			self.native_address,
+			COUNTERPARTY_MODULE_NAME,
+			"batch_complete_bridge_transfers",
+			Vec::new(),
+			args,
+		);
+
+		match utils::send_and_confirm_aptos_transaction(
+			&self.rest_client,
+			self.signer.as_ref(),
+			payload,
+		)
+		.await
+		{
+			Ok(tx_result) => transfers
+				.iter()
+				.map(|(bridge_transfer_id, _)| {
+					utils::transfer_result(*bridge_transfer_id, &tx_result)
+				})
+				.collect(),
+			Err(err) => {
+				warn!(
+					"Batch completion of {} transfers failed, completing them one by one: {err}",
+					transfers.len()
+				);
+				counterparty_complete_individually(self, transfers).await
+			}
+		}
 	}
 
 	async fn lock_bridge_transfer(
@@ -245,10 +424,11 @@ impl BridgeContract<MovementAddress> for MovementClient {
 		recipient: BridgeAddress<MovementAddress>,
 		amount: Amount,
 	) -> BridgeContractResult<()> {
-		let amount_value = match amount.0 {
-			AssetType::Moveth(value) => value,
-			_ => return Err(BridgeContractError::SerializationError),
-		};
+		// The counterparty mints the MOVETH representation of the asset locked on the other chain.
+		let amount_value = amount.value();
+		if let Some(token_address) = amount.token_address() {
+			debug!("Locking {amount_value} of the token {}", token_address.0);
+		}
 
 		let args = vec![
 			utils::serialize_vec(&initiator.0)?,
@@ -266,13 +446,9 @@ impl BridgeContract<MovementAddress> for MovementClient {
 			args,
 		);
 
-		let _ = utils::send_and_confirm_aptos_transaction(
-			&self.rest_client,
-			self.signer.as_ref(),
-			payload,
-		)
-		.await
-		.map_err(|_| BridgeContractError::LockTransferError);
+		utils::send_and_confirm_aptos_transaction(&self.rest_client, self.signer.as_ref(), payload)
+			.await
+			.map_err(BridgeContractError::from_message)?;
 
 		Ok(())
 	}
@@ -281,6 +457,24 @@ impl BridgeContract<MovementAddress> for MovementClient {
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
 	) -> BridgeContractResult<()> {
+		// The module aborts the refunds until the ledger time is past the time lock.
+		let time_lock = self
+			.get_bridge_transfer_details_initiator(bridge_transfer_id)
+			.await?
+			.ok_or(BridgeContractError::GenericError(format!(
+				"No initiated transfer for {bridge_transfer_id}"
+			)))?
+			.time_lock
+			.0;
+		let ledger_info = self
+			.rest_client
+			.get_ledger_information()
+			.await
+			.map_err(|err| BridgeContractError::OnChainError(err.to_string()))?;
+		if ledger_info.inner().timestamp_usecs / 1_000_000 <= time_lock {
+			return Err(BridgeContractError::RefundBeforeExpiry(bridge_transfer_id, time_lock));
+		}
+
 		let args = vec![utils::serialize_vec_initiator(&bridge_transfer_id.0[..])?];
 
 		let payload = utils::make_aptos_payload(
@@ -293,7 +487,7 @@ impl BridgeContract<MovementAddress> for MovementClient {
 
 		utils::send_and_confirm_aptos_transaction(&self.rest_client, self.signer.as_ref(), payload)
 			.await
-			.map_err(|err| BridgeContractError::OnChainError(err.to_string()))?;
+			.map_err(BridgeContractError::from_message)?;
 
 		Ok(())
 	}
@@ -316,7 +510,7 @@ impl BridgeContract<MovementAddress> for MovementClient {
 			payload,
 		)
 		.await
-		.map_err(|_| BridgeContractError::AbortTransferError);
+		.map_err(BridgeContractError::from_message);
 
 		info!("Abort bridge transfer result: {:?}", &result);
 
@@ -394,6 +588,18 @@ impl BridgeContract<MovementAddress> for MovementClient {
 		Ok(Some(details))
 	}
 
+	async fn get_bridge_transfer_details(
+		&mut self,
+		bridge_transfer_id: BridgeTransferId,
+	) -> BridgeContractResult<BridgeTransferStatus> {
+		for module in [INITIATOR_MODULE_NAME, COUNTERPARTY_MODULE_NAME] {
+			if let Some(status) = self.view_transfer_status(module, bridge_transfer_id).await? {
+				return Ok(status);
+			}
+		}
+		Ok(BridgeTransferStatus::NotFound)
+	}
+
 	async fn get_bridge_transfer_details_counterparty(
 		&mut self,
 		bridge_transfer_id: BridgeTransferId,
@@ -466,6 +672,15 @@ impl BridgeContract<MovementAddress> for MovementClient {
 	}
 }
 
+// The Move modules hash the pre-image without its zero padding.
+fn unpadded_preimage(preimage: &HashLockPreImage) -> &[u8] {
+	let mut end = preimage.0.len();
+	while end > 0 && preimage.0[end - 1] == 0 {
+		end -= 1;
+	}
+	&preimage.0[..end]
+}
+
 use std::{
 	env, fs,
 	io::Write,
@@ -797,6 +1012,7 @@ impl MovementClient {
 				native_address: DUMMY_ADDRESS,
 				non_native_address: Vec::new(),
 				rest_client,
+				http_client: reqwest::Client::new(),
 				signer: Arc::new(LocalAccount::generate(&mut rng)),
 			},
 			child,
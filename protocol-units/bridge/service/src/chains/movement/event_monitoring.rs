@@ -1,10 +1,17 @@
 use super::client::MovementClient;
+use super::event_stream::{connect_grpc, module_event_stream, EventSource, GrpcClient};
 use super::utils::MovementAddress;
 use crate::chains::bridge_contracts::BridgeContractError;
 use crate::chains::bridge_contracts::BridgeContractEvent;
+use crate::chains::bridge_contracts::BridgeContractEventType;
 use crate::chains::bridge_contracts::BridgeContractMonitoring;
 use crate::chains::bridge_contracts::BridgeContractResult;
+use crate::chains::bridge_contracts::EventFilter;
+use crate::checkpoint::InFlightEvents;
+use crate::dedup::EventDedup;
+use crate::metrics::RelayerMetrics;
 use crate::types::Amount;
+use crate::types::AssetTag;
 use crate::types::AssetType;
 use crate::types::BridgeAddress;
 use crate::types::BridgeTransferDetails;
@@ -24,11 +31,15 @@ use hex::FromHex;
 use serde::Deserialize;
 use serde::Deserializer;
 use serde::Serialize;
+use std::path::{Path, PathBuf};
 use std::{pin::Pin, task::Poll};
 use tokio::fs::{self, File};
 use tokio::io::{self, AsyncReadExt, AsyncWriteExt};
+use tokio::task::JoinHandle;
 
 const PULL_STATE_FILE_NAME: &str = "pullstate.store";
+/// Number of the last delivered events remembered to drop their duplicates.
+const EVENT_DEDUP_CAPACITY: usize = 4096;
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 struct MvtPullingState {
@@ -41,24 +52,21 @@ struct MvtPullingState {
 }
 
 impl MvtPullingState {
-	async fn save_to_store_file(&self) -> io::Result<()> {
-		let path = MvtPullingState::get_store_file_path();
+	async fn save_to(&self, path: &Path) -> io::Result<()> {
 		if let Some(parent) = path.parent() {
 			fs::create_dir_all(parent).await?;
 		}
 
 		let json = serde_json::to_string(self)?;
-		let mut file = File::create(path.as_path()).await?;
+		let mut file = File::create(path).await?;
 		file.write_all(json.as_bytes()).await?;
 		Ok(())
 	}
 
 	// Read the state from a JSON file
-	async fn build_from_store_file() -> io::Result<MvtPullingState> {
-		let path = MvtPullingState::get_store_file_path();
-
-		let state = if fs::try_exists(&path).await? {
-			let mut file = File::open(path.as_path()).await?;
+	async fn load_from(path: &Path) -> io::Result<MvtPullingState> {
+		let state = if fs::try_exists(path).await? {
+			let mut file = File::open(path).await?;
 			let mut json = String::new();
 			file.read_to_string(&mut json).await?;
 			let state = serde_json::from_str(&json)?;
@@ -76,6 +84,23 @@ impl MvtPullingState {
 		bridge_config::get_config_path(&dot_movement).join(PULL_STATE_FILE_NAME)
 	}
 
+	// Next sequence number expected on the event handle of the event.
+	fn next_sequence_number(&self, event: &BridgeContractEvent<MovementAddress>) -> u64 {
+		match event {
+			BridgeContractEvent::Initiated(_) => self.initiator_init,
+			BridgeContractEvent::Locked(_) => self.counterpart_lock,
+			BridgeContractEvent::InitialtorCompleted(..) => self.initiator_complete,
+			BridgeContractEvent::CounterPartCompleted(..) => self.counterpart_complete,
+			BridgeContractEvent::Cancelled(..) => self.counterpart_cancel,
+			BridgeContractEvent::Refunded(..) => self.initiator_refund,
+		}
+	}
+
+	/// Return false if the event was already delivered.
+	fn is_new(&self, event: &BridgeContractEvent<MovementAddress>, sequence_number: u64) -> bool {
+		sequence_number >= self.next_sequence_number(event)
+	}
+
 	fn update_state_with_event(
 		&mut self,
 		event: &BridgeContractEvent<MovementAddress>,
@@ -93,22 +118,22 @@ impl MvtPullingState {
 					self.counterpart_lock = sequence_number + 1
 				}
 			}
-			BridgeContractEvent::InitialtorCompleted(_) => {
+			BridgeContractEvent::InitialtorCompleted(..) => {
 				if self.initiator_complete <= sequence_number {
 					self.initiator_complete = sequence_number + 1
 				}
 			}
-			BridgeContractEvent::CounterPartCompleted(_, _) => {
+			BridgeContractEvent::CounterPartCompleted(..) => {
 				if self.counterpart_complete <= sequence_number {
 					self.counterpart_complete = sequence_number + 1
 				}
 			}
-			BridgeContractEvent::Cancelled(_) => {
+			BridgeContractEvent::Cancelled(..) => {
 				if self.counterpart_cancel <= sequence_number {
 					self.counterpart_cancel = sequence_number + 1
 				}
 			}
-			BridgeContractEvent::Refunded(_) => {
+			BridgeContractEvent::Refunded(..) => {
 				if self.initiator_refund <= sequence_number {
 					self.initiator_refund = sequence_number + 1
 				}
@@ -119,31 +144,105 @@ impl MvtPullingState {
 
 pub struct MovementMonitoring {
 	listener: mpsc::UnboundedReceiver<BridgeContractResult<BridgeContractEvent<MovementAddress>>>,
+	// Tells the pulling task each time the relayer takes an event.
+	taken: mpsc::UnboundedSender<()>,
+	// Pulling task, it owns the rest and gRPC clients.
+	task: JoinHandle<()>,
+	metrics: Option<RelayerMetrics>,
 }
 
 impl BridgeContractMonitoring for MovementMonitoring {
 	type Address = MovementAddress;
+
+	fn stop(&mut self) {
+		// Dropping the task drops the clients and their connections.
+		self.task.abort();
+		self.listener.close();
+	}
 }
 
 impl MovementMonitoring {
 	pub async fn build(config: &MovementConfig) -> Result<Self, anyhow::Error> {
+		MovementMonitoring::build_with_filter(config, EventFilter::all()).await
+	}
+
+	/// Build a monitoring counting the observed events, and the fall backs from the event
+	/// stream to polling as reconnections, in `metrics`.
+	pub async fn build_with_metrics(
+		config: &MovementConfig,
+		metrics: RelayerMetrics,
+	) -> Result<Self, anyhow::Error> {
+		let state_path = MvtPullingState::get_store_file_path();
+		MovementMonitoring::build_monitoring(config, EventFilter::all(), Some(metrics), state_path)
+			.await
+	}
+
+	/// Build a monitoring that only pulls the event types accepted by `filter`.
+	pub async fn build_with_filter(
+		config: &MovementConfig,
+		filter: EventFilter,
+	) -> Result<Self, anyhow::Error> {
+		let state_path = MvtPullingState::get_store_file_path();
+		MovementMonitoring::build_monitoring(config, filter, None, state_path).await
+	}
+
+	async fn build_monitoring(
+		config: &MovementConfig,
+		filter: EventFilter,
+		metrics: Option<RelayerMetrics>,
+		state_path: PathBuf,
+	) -> Result<Self, anyhow::Error> {
 		// Spawn a task to forward events to the listener channel
-		let (mut sender, listener) = futures::channel::mpsc::unbounded::<
+		let (sender, listener) = futures::channel::mpsc::unbounded::<
 			BridgeContractResult<BridgeContractEvent<MovementAddress>>,
 		>();
+		let (taken, taken_events) = futures::channel::mpsc::unbounded::<()>();
 
 		//read the pull state
-		let mut pull_state = MvtPullingState::build_from_store_file().await?;
+		let mut pull_state = MvtPullingState::load_from(&state_path).await?;
+		let mut delivery = EventDelivery {
+			sender,
+			taken_events,
+			in_flight: InFlightEvents::new(),
+			processed: pull_state.clone(),
+			path: state_path,
+		};
+		let asset: AssetTag = config.movement_asset.parse()?;
+		let mut grpc_client = match config.movement_event_source.parse()? {
+			EventSource::Polling => None,
+			EventSource::Streaming => connect_grpc(&config.mvt_grpc_connection_url()).await,
+		};
 
-		tokio::spawn({
+		let confirmation_versions = config.movement_confirmation_versions;
+		let reconnects = metrics.as_ref().map(|metrics| metrics.monitor_reconnects("Movement"));
+		let task = tokio::spawn({
 			let config = config.clone();
 			async move {
 				let mvt_client = MovementClient::new(&config).await.unwrap();
+				let mut dedup = EventDedup::new(EVENT_DEDUP_CAPACITY);
 				loop {
+					// Persist the events the relayer processed since the last poll.
+					while let Ok(Some(())) = delivery.taken_events.try_next() {
+						delivery.taken().await;
+					}
+					// Ledger version before the poll, the stream starts from it once polling caught up.
+					// It's also the reference of the events finality.
+					let ledger_version = if grpc_client.is_some() || confirmation_versions > 0 {
+						mvt_client
+							.rest_client()
+							.get_ledger_information()
+							.await
+							.ok()
+							.map(|info| info.into_inner().version)
+					} else {
+						None
+					};
 					let mut init_event_list = match pool_initiator_contract(
 						&mvt_client,
 						&config.mvt_rpc_connection_url(),
 						&pull_state,
+						&filter,
+						asset,
 					)
 					.await
 					{
@@ -154,6 +253,8 @@ impl MovementMonitoring {
 						&mvt_client,
 						&config.mvt_rpc_connection_url(),
 						&pull_state,
+						&filter,
+						asset,
 					)
 					.await
 					{
@@ -167,9 +268,16 @@ impl MovementMonitoring {
 							(Vec::new(), pull_state.clone()),
 							|(mut events, mut state), event| {
 								match event {
-									Ok((ev, seq)) => {
+									// Polled again once it's final.
+									Ok((_, _, version))
+										if !is_final(
+											version,
+											ledger_version,
+											confirmation_versions,
+										) => {}
+									Ok((ev, seq, _)) => {
 										state.update_state_with_event(&ev, seq);
-										events.push(Ok(ev));
+										events.push(Ok((ev, seq)));
 									}
 									Err(err) => events.push(Err(err)),
 								}
@@ -177,23 +285,145 @@ impl MovementMonitoring {
 							},
 						);
 
+					let caught_up = event_list.is_empty();
 					for event in event_list {
-						if sender.send(event).await.is_err() {
+						match &event {
+							// Polled again in overlapping ranges.
+							Ok((event, seq)) if !dedup.insert(event, Some(*seq)) => continue,
+							_ => {}
+						}
+						if !delivery.send(event).await {
 							tracing::error!("Failed to send event to listener channel");
 							break;
 						}
 					}
+					// The pull state of the processed events is persisted, the polled one only
+					// moves the next poll.
 					pull_state = new_pull_state;
 
-					if let Err(err) = pull_state.save_to_store_file().await {
-						tracing::error!("MVT monitoring unable to store the file state because:{err} for state:{pull_state:?}");
+					// Every event before the ledger version has been polled, switch to the stream.
+					// The streamed events aren't held until final, so it's only used without
+					// a confirmation depth.
+					if let (true, Some(version), 0) =
+						(caught_up, ledger_version, confirmation_versions)
+					{
+						if let Some(client) = grpc_client.take() {
+							stream_events(
+								client,
+								version,
+								mvt_client.native_address,
+								filter.clone(),
+								asset,
+								&mut pull_state,
+								&mut dedup,
+								&mut delivery,
+							)
+							.await;
+							tracing::warn!("MVT event stream closed, falling back to polling");
+							if let Some(reconnects) = &reconnects {
+								reconnects.inc();
+							}
+							continue;
+						}
 					}
 					let _ = tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 				}
 			}
 		});
 
-		Ok(MovementMonitoring { listener })
+		Ok(MovementMonitoring { listener, taken, task, metrics })
+	}
+}
+
+/// Sends the events to the relayer and persists the pull state of the ones it processed, so a
+/// rebuilt monitoring delivers again the events it sent that weren't processed.
+struct EventDelivery {
+	sender: mpsc::UnboundedSender<BridgeContractResult<BridgeContractEvent<MovementAddress>>>,
+	// Receives a message each time the relayer takes an event.
+	taken_events: mpsc::UnboundedReceiver<()>,
+	// Events sent and not processed yet with their sequence number, None for the errors.
+	in_flight: InFlightEvents<Option<(BridgeContractEvent<MovementAddress>, u64)>>,
+	processed: MvtPullingState,
+	path: PathBuf,
+}
+
+impl EventDelivery {
+	/// Returns false once the listener is closed.
+	async fn send(
+		&mut self,
+		event: BridgeContractResult<(BridgeContractEvent<MovementAddress>, u64)>,
+	) -> bool {
+		let event = match event {
+			Ok((event, sequence_number)) => {
+				self.in_flight.sent(Some((event.clone(), sequence_number)));
+				Ok(event)
+			}
+			Err(err) => {
+				self.in_flight.sent(None);
+				Err(err)
+			}
+		};
+		self.sender.send(event).await.is_ok()
+	}
+
+	/// The relayer took an event, persist the one it processed before.
+	async fn taken(&mut self) {
+		let Some(Some((event, sequence_number))) = self.in_flight.taken() else {
+			return;
+		};
+		self.processed.update_state_with_event(&event, sequence_number);
+		if let Err(err) = self.processed.save_to(&self.path).await {
+			tracing::error!(
+				"MVT monitoring unable to store the file state because:{err} for state:{:?}",
+				self.processed
+			);
+		}
+	}
+}
+
+/// An event of `version` is final once the ledger is `confirmations` versions above it.
+/// Without a known ledger version, only the events that need no confirmation are.
+fn is_final(version: u64, ledger_version: Option<u64>, confirmations: u64) -> bool {
+	confirmations == 0
+		|| ledger_version.map_or(false, |ledger| version.saturating_add(confirmations) <= ledger)
+}
+
+// Forward the streamed events until the stream ends.
+// The events polled after the stream start version are delivered twice and dropped.
+async fn stream_events(
+	client: GrpcClient,
+	starting_version: u64,
+	module_address: aptos_types::account_address::AccountAddress,
+	filter: EventFilter,
+	asset: AssetTag,
+	pull_state: &mut MvtPullingState,
+	dedup: &mut EventDedup,
+	delivery: &mut EventDelivery,
+) {
+	let mut events = module_event_stream(client, starting_version, module_address, filter, asset);
+	loop {
+		let event = tokio::select! {
+			event = events.next() => match event {
+				Some(event) => event,
+				None => return,
+			},
+			Some(()) = delivery.taken_events.next() => {
+				delivery.taken().await;
+				continue;
+			}
+		};
+		if let Ok((event, sequence_number)) = &event {
+			if !pull_state.is_new(event, *sequence_number)
+				|| !dedup.insert(event, Some(*sequence_number))
+			{
+				continue;
+			}
+			pull_state.update_state_with_event(event, *sequence_number);
+		}
+		if !delivery.send(event).await {
+			tracing::error!("Failed to send event to listener channel");
+			return;
+		}
 	}
 }
 
@@ -202,7 +432,14 @@ impl Stream for MovementMonitoring {
 
 	fn poll_next(self: Pin<&mut Self>, cx: &mut std::task::Context) -> Poll<Option<Self::Item>> {
 		let this = self.get_mut();
-		this.listener.poll_next_unpin(cx)
+		let poll = this.listener.poll_next_unpin(cx);
+		if let Poll::Ready(Some(_)) = &poll {
+			let _ = this.taken.unbounded_send(());
+		}
+		if let (Poll::Ready(Some(Ok(event))), Some(metrics)) = (&poll, &this.metrics) {
+			metrics.observe_event("Movement", event);
+		}
+		poll
 	}
 }
 
@@ -220,14 +457,19 @@ async fn pool_initiator_contract(
 	client: &MovementClient,
 	rest_url: &str,
 	pull_state: &MvtPullingState,
-) -> BridgeContractResult<Vec<(BridgeContractEvent<MovementAddress>, u64)>> {
+	filter: &EventFilter,
+	asset: AssetTag,
+) -> BridgeContractResult<Vec<(BridgeContractEvent<MovementAddress>, u64, u64)>> {
 	let struct_tag = format!(
 		"{}::atomic_bridge_initiator::BridgeTransferStore",
 		client.native_address.to_standard_string(),
 	);
 
 	// Get initiated events
-	let initiated_events = get_account_events(
+	let initiated_events = get_filtered_account_events(
+		filter,
+		BridgeContractEventType::Initiated,
+		client.http_client(),
 		rest_url,
 		&client.native_address.to_standard_string(),
 		&struct_tag,
@@ -239,8 +481,7 @@ async fn pool_initiator_contract(
 	.map(|e| {
 		println!("Initiate event data: {:?} sequence_number:{}", e.data, e.sequence_number);
 		let data: BridgeInitEventData = serde_json::from_str(&e.data.to_string())?;
-		let transfer_details = BridgeTransferDetails::try_from(data)?;
-		Ok((BridgeContractEvent::Initiated(transfer_details), e.sequence_number.into()))
+		Ok((initiated_event(data, asset)?, e.sequence_number.into(), e.version.into()))
 	})
 	.collect::<Result<Vec<_>>>()
 	.map_err(|e| {
@@ -251,7 +492,10 @@ async fn pool_initiator_contract(
 	})?;
 
 	// Get completed events
-	let completed_events = get_account_events(
+	let completed_events = get_filtered_account_events(
+		filter,
+		BridgeContractEventType::InitialtorCompleted,
+		client.http_client(),
 		rest_url,
 		&client.native_address.to_standard_string(),
 		&struct_tag,
@@ -270,8 +514,9 @@ async fn pool_initiator_contract(
 				err
 			))
 			})?,
+			asset,
 		);
-		Ok((event, e.sequence_number.into()))
+		Ok((event, e.sequence_number.into(), e.version.into()))
 	})
 	.collect::<Result<Vec<_>>>()
 	.map_err(|e| {
@@ -282,7 +527,10 @@ async fn pool_initiator_contract(
 	})?;
 
 	// Get refunded events
-	let refunded_events = get_account_events(
+	let refunded_events = get_filtered_account_events(
+		filter,
+		BridgeContractEventType::Refunded,
+		client.http_client(),
 		rest_url,
 		&client.native_address.to_standard_string(),
 		&struct_tag,
@@ -294,13 +542,16 @@ async fn pool_initiator_contract(
 	.map(|e| {
 		println!("refund event data: {:?} sequence_number:{}", e.data, e.sequence_number);
 		let data = deserialize_hex_vec(e.data)?;
-		let event = BridgeContractEvent::Refunded(data.try_into().map_err(|err| {
-			BridgeContractError::OnChainError(format!(
+		let event = BridgeContractEvent::Refunded(
+			data.try_into().map_err(|err| {
+				BridgeContractError::OnChainError(format!(
 				"MVT bridge_transfer_refunded_events bridge_transfer_id can't be reconstructed:{:?}",
 				err
 			))
-		})?);
-		Ok((event, e.sequence_number.into()))
+			})?,
+			asset,
+		);
+		Ok((event, e.sequence_number.into(), e.version.into()))
 	})
 	.collect::<Result<Vec<_>>>()
 	.map_err(|e| {
@@ -322,14 +573,19 @@ async fn pool_counterpart_contract(
 	client: &MovementClient,
 	rest_url: &str,
 	pull_state: &MvtPullingState,
-) -> BridgeContractResult<Vec<(BridgeContractEvent<MovementAddress>, u64)>> {
+	filter: &EventFilter,
+	asset: AssetTag,
+) -> BridgeContractResult<Vec<(BridgeContractEvent<MovementAddress>, u64, u64)>> {
 	let struct_tag = format!(
 		"{}::atomic_bridge_counterparty::BridgeTransferStore",
 		client.native_address.to_standard_string()
 	);
 
 	// Get locked events
-	let locked_events = get_account_events(
+	let locked_events = get_filtered_account_events(
+		filter,
+		BridgeContractEventType::Locked,
+		client.http_client(),
 		rest_url,
 		&client.native_address.to_standard_string(),
 		&struct_tag,
@@ -341,8 +597,7 @@ async fn pool_counterpart_contract(
 	.map(|e| {
 		println!("Lock event data: {:?} sequence_number:{}", e.data, e.sequence_number);
 		let data: BridgeInitEventData = serde_json::from_str(&e.data.to_string())?;
-		let transfer_details = LockDetails::try_from(data)?;
-		Ok((BridgeContractEvent::Locked(transfer_details), e.sequence_number.into()))
+		Ok((locked_event(data, asset)?, e.sequence_number.into(), e.version.into()))
 	})
 	.collect::<Result<Vec<_>>>()
 	.map_err(|e| {
@@ -353,7 +608,10 @@ async fn pool_counterpart_contract(
 	})?;
 
 	// Get completed events
-	let completed_events = get_account_events(
+	let completed_events = get_filtered_account_events(
+		filter,
+		BridgeContractEventType::CounterPartCompleted,
+		client.http_client(),
 		rest_url,
 		&client.native_address.to_standard_string(),
 		&struct_tag,
@@ -381,8 +639,9 @@ async fn pool_counterpart_contract(
 				err
 			))
 			})?),
+			asset,
 		);
-		Ok((event, e.sequence_number.into()))
+		Ok((event, e.sequence_number.into(), e.version.into()))
 	})
 	.collect::<Result<Vec<_>>>()
 	.map_err(|e| {
@@ -393,7 +652,10 @@ async fn pool_counterpart_contract(
 	})?;
 
 	// Get cancelled events
-	let cancelled_events = get_account_events(
+	let cancelled_events = get_filtered_account_events(
+		filter,
+		BridgeContractEventType::Cancelled,
+		client.http_client(),
 		rest_url,
 		&client.native_address.to_standard_string(),
 		&struct_tag,
@@ -405,13 +667,16 @@ async fn pool_counterpart_contract(
 	.map(|e| {
 		println!("refund event data: {:?} sequence_number:{}", e.data, e.sequence_number);
 		let data = deserialize_hex_vec(e.data)?;
-		let event = BridgeContractEvent::Cancelled(data.try_into().map_err(|err| {
-			BridgeContractError::OnChainError(format!(
+		let event = BridgeContractEvent::Cancelled(
+			data.try_into().map_err(|err| {
+				BridgeContractError::OnChainError(format!(
 				"MVT bridge_transfer_cancelled_events bridge_transfer_id can't be reconstructed:{:?}",
 				err
 			))
-		})?);
-		Ok((event, e.sequence_number.into()))
+			})?,
+			asset,
+		);
+		Ok((event, e.sequence_number.into(), e.version.into()))
 	})
 	.collect::<Result<Vec<_>>>()
 	.map_err(|e| {
@@ -455,7 +720,7 @@ pub struct BridgeInitEventData {
 }
 
 // Custom deserialization function to convert a hex string to Vec<u8>
-fn deserialize_hex_vec<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+pub(super) fn deserialize_hex_vec<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
 where
 	D: Deserializer<'de>,
 {
@@ -527,6 +792,26 @@ impl TryFrom<BridgeInitEventData> for LockDetails<MovementAddress> {
 	}
 }
 
+// The on chain event has no token field, its amount is of the asset the modules are configured for.
+pub(super) fn initiated_event(
+	data: BridgeInitEventData,
+	asset: AssetTag,
+) -> BridgeContractResult<BridgeContractEvent<MovementAddress>> {
+	let amount = asset.amount(data.amount);
+	Ok(BridgeContractEvent::Initiated(BridgeTransferDetails {
+		amount,
+		..BridgeTransferDetails::try_from(data)?
+	}))
+}
+
+pub(super) fn locked_event(
+	data: BridgeInitEventData,
+	asset: AssetTag,
+) -> BridgeContractResult<BridgeContractEvent<MovementAddress>> {
+	let amount = asset.amount(data.amount);
+	Ok(BridgeContractEvent::Locked(LockDetails { amount, ..LockDetails::try_from(data)? }))
+}
+
 // Example of return string.
 // [
 //     {
@@ -550,7 +835,33 @@ impl TryFrom<BridgeInitEventData> for LockDetails<MovementAddress> {
 //         }
 //     }
 // ]
+// Skip the request when the filter doesn't accept the event type.
+async fn get_filtered_account_events(
+	filter: &EventFilter,
+	accepted_type: BridgeContractEventType,
+	http_client: &reqwest::Client,
+	rest_url: &str,
+	account_address: &str,
+	event_type: &str,
+	field_name: &str,
+	start_version: u64,
+) -> Result<Vec<VersionedEvent>, BridgeContractError> {
+	if !filter.accepts(accepted_type) {
+		return Ok(Vec::new());
+	}
+	get_account_events(
+		http_client,
+		rest_url,
+		account_address,
+		event_type,
+		field_name,
+		start_version,
+	)
+	.await
+}
+
 async fn get_account_events(
+	client: &reqwest::Client,
 	rest_url: &str,
 	account_address: &str,
 	event_type: &str,
@@ -563,7 +874,6 @@ async fn get_account_events(
 	);
 
 	//	tracing::info!("ICI url: {:?}", url);
-	let client = reqwest::Client::new();
 
 	// Send the GET request
 	let response: Vec<VersionedEvent> = client
@@ -587,3 +897,185 @@ async fn get_account_events(
 		})?;
 	Ok(response)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::{json, Value};
+	use std::sync::{Arc, Mutex};
+	use std::time::Duration;
+	use tokio::net::TcpListener;
+
+	const INITIATED_EVENT_DATA: &str = r#"{
+		"amount": "100",
+		"bridge_transfer_id": "0xeaefd189df98d57b8f4619584cff1fd67f2787c664ac8e9761ecfd7a6ae1fa2b",
+		"hash_lock": "0xfb54fb738082d0214980feb4055e779d7d4722cb0809d5fbe79df8117801c3bb",
+		"originator": "0xf90391c81027f03cdea491ed8b36ffaced26b6df208a9b569e5baf2590eb9b16",
+		"recipient": "0x3078313233",
+		"time_lock": "1",
+		"state": 1
+	}"#;
+
+	fn decode(
+		asset: AssetTag,
+	) -> (BridgeContractEvent<MovementAddress>, BridgeContractEvent<MovementAddress>) {
+		let initiated =
+			initiated_event(serde_json::from_str(INITIATED_EVENT_DATA).unwrap(), asset).unwrap();
+		let locked =
+			locked_event(serde_json::from_str(INITIATED_EVENT_DATA).unwrap(), asset).unwrap();
+		(initiated, locked)
+	}
+
+	#[test]
+	fn test_decoded_events_carry_their_asset() {
+		let (initiated, locked) = decode(AssetTag::Moveth);
+		assert_eq!(initiated.asset(), AssetTag::Moveth);
+		assert_eq!(locked.asset(), AssetTag::Moveth);
+		assert!(
+			matches!(initiated, BridgeContractEvent::Initiated(details) if details.amount == Amount(AssetType::Moveth(100)))
+		);
+
+		let (initiated, locked) = decode(AssetTag::EthAndWeth);
+		assert_eq!(initiated.asset(), AssetTag::EthAndWeth);
+		assert_eq!(locked.asset(), AssetTag::EthAndWeth);
+		assert!(
+			matches!(locked, BridgeContractEvent::Locked(details) if details.amount.value() == 100)
+		);
+	}
+
+	#[test]
+	fn test_already_delivered_events_are_not_new() {
+		let (initiated, locked) = decode(AssetTag::Moveth);
+		let mut pull_state = MvtPullingState::default();
+		assert!(pull_state.is_new(&initiated, 0));
+
+		// Delivered by polling, then again by the stream.
+		pull_state.update_state_with_event(&initiated, 0);
+		assert!(!pull_state.is_new(&initiated, 0));
+		assert!(pull_state.is_new(&initiated, 1));
+		// Each event handle has its own sequence.
+		assert!(pull_state.is_new(&locked, 0));
+	}
+
+	#[test]
+	fn test_events_wait_for_the_confirmation_versions() {
+		assert!(is_final(100, None, 0));
+		assert!(!is_final(100, None, 10));
+		assert!(!is_final(100, Some(109), 10));
+		assert!(is_final(100, Some(110), 10));
+	}
+
+	// Initiated event of the transfer `id`, the `id`th of its event handle.
+	fn versioned_initiated_event(id: u8) -> Value {
+		let mut data: Value = serde_json::from_str(INITIATED_EVENT_DATA).unwrap();
+		data["bridge_transfer_id"] = json!(format!("0x{id:064x}"));
+		json!({
+			"version": (100 + u64::from(id)).to_string(),
+			"guid": {"creation_number": "5", "account_address": "0xface"},
+			"sequence_number": id.to_string(),
+			"type": "0xface::atomic_bridge_initiator::BridgeTransferInitiatedEvent",
+			"data": data,
+		})
+	}
+
+	/// Node serving the account of the signer and the initiated `events`, 10 per page like the
+	/// REST API.
+	async fn mock_node(events: Arc<Mutex<Vec<Value>>>) -> u16 {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let port = listener.local_addr().unwrap().port();
+		tokio::spawn(async move {
+			while let Ok((mut socket, _)) = listener.accept().await {
+				let mut buf = vec![0; 64 * 1024];
+				let Ok(len) = socket.read(&mut buf).await else { continue };
+				let request = String::from_utf8_lossy(&buf[..len]).to_string();
+				let path = request.split_whitespace().nth(1).unwrap_or_default().to_string();
+				let (body, headers) = if path.contains("/events/") {
+					let start = path
+						.split_once("start=")
+						.and_then(|(_, start)| start.split('&').next())
+						.and_then(|start| start.parse::<usize>().ok())
+						.unwrap_or(0);
+					let page: Vec<Value> =
+						events.lock().unwrap().iter().skip(start).take(10).cloned().collect();
+					(json!(page).to_string(), String::new())
+				} else {
+					// The account of the signer, with the ledger state the client expects.
+					let account = json!({
+						"sequence_number": "0",
+						"authentication_key": format!("0x{}", "00".repeat(32)),
+					});
+					let state = "X-Aptos-Chain-Id: 4\r\nX-Aptos-Ledger-Version: 200\r\nX-Aptos-Ledger-Oldest-Version: 0\r\nX-Aptos-Ledger-TimestampUsec: 0\r\nX-Aptos-Epoch: 1\r\nX-Aptos-Block-Height: 100\r\nX-Aptos-Oldest-Block-Height: 0\r\n";
+					(account.to_string(), state.to_string())
+				};
+				let response = format!(
+					"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n{headers}connection: close\r\n\r\n{body}",
+					body.len()
+				);
+				let _ = socket.write_all(response.as_bytes()).await;
+			}
+		});
+		port
+	}
+
+	// Transfer of the next event delivered by `monitoring`.
+	async fn next_transfer(monitoring: &mut MovementMonitoring) -> u8 {
+		let event = tokio::time::timeout(Duration::from_secs(10), monitoring.next())
+			.await
+			.expect("no event delivered")
+			.unwrap()
+			.unwrap();
+		event.bridge_transfer_id().0[31]
+	}
+
+	#[tokio::test]
+	async fn test_killed_monitoring_is_rebuilt_without_skipping_events() {
+		let path =
+			std::env::temp_dir().join(format!("bridge-pullstate-kill-{}", std::process::id()));
+		let _ = fs::remove_file(&path).await;
+		let events = Arc::new(Mutex::new((0..3).map(versioned_initiated_event).collect()));
+		let config = MovementConfig {
+			mvt_rpc_connection_protocol: "http".to_string(),
+			mvt_rpc_connection_hostname: "127.0.0.1".to_string(),
+			mvt_rpc_connection_port: mock_node(events.clone()).await,
+			movement_native_address: "0xface".to_string(),
+			movement_event_source: "polling".to_string(),
+			movement_confirmation_versions: 0,
+			movement_asset: "moveth".to_string(),
+			..MovementConfig::default()
+		};
+		let filter = EventFilter::only([BridgeContractEventType::Initiated]);
+
+		// The relayer processed the first transfer and is killed while it processes the second,
+		// the third one is still in the channel.
+		let mut monitoring =
+			MovementMonitoring::build_monitoring(&config, filter.clone(), None, path.clone())
+				.await
+				.unwrap();
+		assert_eq!(next_transfer(&mut monitoring).await, 0);
+		assert_eq!(next_transfer(&mut monitoring).await, 1);
+		tokio::time::timeout(Duration::from_secs(10), async {
+			while MvtPullingState::load_from(&path).await.unwrap().initiator_init == 0 {
+				tokio::time::sleep(Duration::from_millis(50)).await;
+			}
+		})
+		.await
+		.expect("no pull state persisted");
+		monitoring.stop();
+
+		// A transfer is initiated while the relayer is down.
+		events.lock().unwrap().push(versioned_initiated_event(3));
+
+		// The rebuilt monitoring delivers again from the transfer being processed.
+		let mut monitoring =
+			MovementMonitoring::build_monitoring(&config, filter, None, path.clone())
+				.await
+				.unwrap();
+		let mut delivered = Vec::new();
+		for _ in 0..3 {
+			delivered.push(next_transfer(&mut monitoring).await);
+		}
+		assert_eq!(delivered, vec![1, 2, 3]);
+		monitoring.stop();
+		fs::remove_file(&path).await.unwrap();
+	}
+}
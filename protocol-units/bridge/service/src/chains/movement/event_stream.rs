@@ -0,0 +1,314 @@
+use super::event_monitoring::{initiated_event, locked_event, BridgeCompletEventData};
+use super::utils::MovementAddress;
+use crate::chains::bridge_contracts::{
+	BridgeContractError, BridgeContractEvent, BridgeContractEventType, BridgeContractResult,
+	EventFilter,
+};
+use crate::types::{AssetTag, BridgeTransferId, HashLockPreImage};
+use aptos_protos::internal::fullnode::v1::fullnode_data_client::FullnodeDataClient;
+use aptos_protos::internal::fullnode::v1::transactions_from_node_response::Response;
+use aptos_protos::internal::fullnode::v1::GetTransactionsFromNodeRequest;
+use aptos_protos::transaction::v1::transaction::TxnData;
+use aptos_protos::transaction::v1::Event;
+use aptos_types::account_address::AccountAddress;
+use futures::{Stream, StreamExt};
+use serde::Deserialize;
+use std::pin::Pin;
+use std::str::FromStr;
+use tonic::transport::Channel;
+
+/// Transactions batches can be larger than the tonic default limit.
+const MAX_DECODING_MESSAGE_SIZE: usize = 256 * 1024 * 1024;
+
+pub type GrpcClient = FullnodeDataClient<Channel>;
+
+/// Bridge events with the sequence number of their event handle.
+pub type ModuleEventStream = Pin<
+	Box<
+		dyn Stream<Item = BridgeContractResult<(BridgeContractEvent<MovementAddress>, u64)>> + Send,
+	>,
+>;
+
+/// How the Movement monitoring observes the bridge module events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventSource {
+	/// Poll the account event handles over the REST api.
+	Polling,
+	/// Stream the transactions from the node indexer gRPC service, polling if it's unavailable.
+	Streaming,
+}
+
+impl FromStr for EventSource {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"polling" => Ok(EventSource::Polling),
+			"streaming" => Ok(EventSource::Streaming),
+			_ => Err(anyhow::anyhow!("Unknown Movement event source: {s}")),
+		}
+	}
+}
+
+/// Connect the indexer gRPC service of the node.
+/// Returns None if the node can't be reached, the monitoring then polls.
+pub async fn connect_grpc(grpc_url: &str) -> Option<GrpcClient> {
+	match FullnodeDataClient::connect(grpc_url.to_string()).await {
+		Ok(client) => Some(client.max_decoding_message_size(MAX_DECODING_MESSAGE_SIZE)),
+		Err(err) => {
+			tracing::warn!("Movement gRPC {grpc_url} unavailable, falling back to polling:{err}");
+			None
+		}
+	}
+}
+
+/// Stream the bridge events of the transactions from `starting_version`.
+/// The stream ends on the first transport error.
+pub fn module_event_stream(
+	mut client: GrpcClient,
+	starting_version: u64,
+	module_address: AccountAddress,
+	filter: EventFilter,
+	asset: AssetTag,
+) -> ModuleEventStream {
+	Box::pin(async_stream::stream! {
+		let request = GetTransactionsFromNodeRequest {
+			starting_version: Some(starting_version),
+			transactions_count: None,
+		};
+		let mut responses = match client.get_transactions_from_node(request).await {
+			Ok(response) => response.into_inner(),
+			Err(status) => {
+				yield Err(BridgeContractError::OnChainError(format!(
+					"MVT transaction stream request error:{status}"
+				)));
+				return;
+			}
+		};
+		while let Some(response) = responses.next().await {
+			let transactions = match response {
+				Ok(response) => match response.response {
+					Some(Response::Data(output)) => output.transactions,
+					// Stream status, no transaction.
+					_ => continue,
+				},
+				Err(status) => {
+					yield Err(BridgeContractError::OnChainError(format!(
+						"MVT transaction stream error:{status}"
+					)));
+					return;
+				}
+			};
+			for transaction in transactions {
+				// Only user transactions call the bridge modules.
+				let Some(TxnData::User(user_transaction)) = transaction.txn_data else {
+					continue;
+				};
+				for event in user_transaction.events {
+					if let Some(event) = decode_event(&event, module_address, &filter, asset) {
+						yield event;
+					}
+				}
+			}
+		}
+	})
+}
+
+#[derive(Debug, Deserialize)]
+struct BridgeTransferIdEventData {
+	#[serde(deserialize_with = "super::event_monitoring::deserialize_hex_vec")]
+	bridge_transfer_id: Vec<u8>,
+}
+
+// Kind of the event if it's emitted by the bridge modules, from its `address::module::Struct` type.
+fn bridge_event_type(
+	type_str: &str,
+	module_address: AccountAddress,
+) -> Option<BridgeContractEventType> {
+	let mut parts = type_str.splitn(3, "::");
+	let (address, module, name) = (parts.next()?, parts.next()?, parts.next()?);
+	if AccountAddress::from_str(address).ok()? != module_address {
+		return None;
+	}
+	match (module, name) {
+		("atomic_bridge_initiator", "BridgeTransferInitiatedEvent") => {
+			Some(BridgeContractEventType::Initiated)
+		}
+		("atomic_bridge_initiator", "BridgeTransferCompletedEvent") => {
+			Some(BridgeContractEventType::InitialtorCompleted)
+		}
+		("atomic_bridge_initiator", "BridgeTransferRefundedEvent") => {
+			Some(BridgeContractEventType::Refunded)
+		}
+		("atomic_bridge_counterparty", "BridgeTransferLockedEvent") => {
+			Some(BridgeContractEventType::Locked)
+		}
+		("atomic_bridge_counterparty", "BridgeTransferCompletedEvent") => {
+			Some(BridgeContractEventType::CounterPartCompleted)
+		}
+		("atomic_bridge_counterparty", "BridgeTransferCancelledEvent") => {
+			Some(BridgeContractEventType::Cancelled)
+		}
+		_ => None,
+	}
+}
+
+// None if the event isn't a bridge event accepted by the filter.
+fn decode_event(
+	event: &Event,
+	module_address: AccountAddress,
+	filter: &EventFilter,
+	asset: AssetTag,
+) -> Option<BridgeContractResult<(BridgeContractEvent<MovementAddress>, u64)>> {
+	let event_type = bridge_event_type(&event.type_str, module_address)?;
+	if !filter.accepts(event_type) {
+		return None;
+	}
+	Some(
+		decode_event_data(event_type, &event.data, asset)
+			.map(|decoded| (decoded, event.sequence_number)),
+	)
+}
+
+fn decode_event_data(
+	event_type: BridgeContractEventType,
+	data: &str,
+	asset: AssetTag,
+) -> BridgeContractResult<BridgeContractEvent<MovementAddress>> {
+	let deserialization_error = |err: serde_json::Error| {
+		BridgeContractError::OnChainError(format!(
+			"MVT streamed {event_type:?} event de-serialization error:{err}"
+		))
+	};
+	let transfer_id = |id: Vec<u8>| {
+		BridgeTransferId::try_from(id).map_err(|err| {
+			BridgeContractError::OnChainError(format!(
+				"MVT streamed {event_type:?} event bridge_transfer_id can't be reconstructed:{err:?}"
+			))
+		})
+	};
+	match event_type {
+		BridgeContractEventType::Initiated => {
+			initiated_event(serde_json::from_str(data).map_err(deserialization_error)?, asset)
+		}
+		BridgeContractEventType::Locked => {
+			locked_event(serde_json::from_str(data).map_err(deserialization_error)?, asset)
+		}
+		BridgeContractEventType::InitialtorCompleted => {
+			let data: BridgeCompletEventData =
+				serde_json::from_str(data).map_err(deserialization_error)?;
+			Ok(BridgeContractEvent::InitialtorCompleted(
+				transfer_id(data.bridge_transfer_id)?,
+				asset,
+			))
+		}
+		BridgeContractEventType::CounterPartCompleted => {
+			let data: BridgeCompletEventData =
+				serde_json::from_str(data).map_err(deserialization_error)?;
+			let pre_image = data.pre_image.try_into().map_err(|err| {
+				BridgeContractError::OnChainError(format!(
+					"MVT streamed counterparty completed event pre_image can't be reconstructed:{err:?}"
+				))
+			})?;
+			Ok(BridgeContractEvent::CounterPartCompleted(
+				transfer_id(data.bridge_transfer_id)?,
+				HashLockPreImage(pre_image),
+				asset,
+			))
+		}
+		BridgeContractEventType::Cancelled => {
+			let data: BridgeTransferIdEventData =
+				serde_json::from_str(data).map_err(deserialization_error)?;
+			Ok(BridgeContractEvent::Cancelled(transfer_id(data.bridge_transfer_id)?, asset))
+		}
+		BridgeContractEventType::Refunded => {
+			let data: BridgeTransferIdEventData =
+				serde_json::from_str(data).map_err(deserialization_error)?;
+			Ok(BridgeContractEvent::Refunded(transfer_id(data.bridge_transfer_id)?, asset))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::types::Amount;
+	use crate::types::AssetType;
+
+	const MODULE_ADDRESS: &str = "0xface";
+
+	fn event(type_str: &str, data: &str, sequence_number: u64) -> Event {
+		Event {
+			type_str: type_str.to_string(),
+			data: data.to_string(),
+			sequence_number,
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn test_streamed_bridge_events_are_decoded() {
+		let module_address = AccountAddress::from_hex_literal(MODULE_ADDRESS).unwrap();
+		let initiated = event(
+			"0xface::atomic_bridge_initiator::BridgeTransferInitiatedEvent",
+			r#"{
+				"amount": "100",
+				"bridge_transfer_id": "0xeaefd189df98d57b8f4619584cff1fd67f2787c664ac8e9761ecfd7a6ae1fa2b",
+				"hash_lock": "0xfb54fb738082d0214980feb4055e779d7d4722cb0809d5fbe79df8117801c3bb",
+				"originator": "0xf90391c81027f03cdea491ed8b36ffaced26b6df208a9b569e5baf2590eb9b16",
+				"recipient": "0x3078313233",
+				"time_lock": "1",
+				"state": 1
+			}"#,
+			3,
+		);
+		let (decoded, sequence_number) =
+			decode_event(&initiated, module_address, &EventFilter::all(), AssetTag::Moveth)
+				.unwrap()
+				.unwrap();
+		assert_eq!(sequence_number, 3);
+		assert!(
+			matches!(decoded, BridgeContractEvent::Initiated(details) if details.amount == Amount(AssetType::Moveth(100)))
+		);
+
+		// Same struct name, the module tells the side.
+		let completed = event(
+			"0x000000000000000000000000000000000000000000000000000000000000face::atomic_bridge_counterparty::BridgeTransferCompletedEvent",
+			r#"{
+				"bridge_transfer_id": "0xeaefd189df98d57b8f4619584cff1fd67f2787c664ac8e9761ecfd7a6ae1fa2b",
+				"pre_image": "0x0101010101010101010101010101010101010101010101010101010101010101"
+			}"#,
+			0,
+		);
+		let (decoded, _) =
+			decode_event(&completed, module_address, &EventFilter::all(), AssetTag::Moveth)
+				.unwrap()
+				.unwrap();
+		assert!(
+			matches!(decoded, BridgeContractEvent::CounterPartCompleted(_, secret, _) if secret == HashLockPreImage([1; 32]))
+		);
+	}
+
+	#[test]
+	fn test_other_streamed_events_are_skipped() {
+		let module_address = AccountAddress::from_hex_literal(MODULE_ADDRESS).unwrap();
+		let refunded = event(
+			"0xface::atomic_bridge_initiator::BridgeTransferRefundedEvent",
+			r#"{"bridge_transfer_id": "0xeaefd189df98d57b8f4619584cff1fd67f2787c664ac8e9761ecfd7a6ae1fa2b"}"#,
+			0,
+		);
+		// Filtered out.
+		let filter = EventFilter::only([BridgeContractEventType::Initiated]);
+		assert!(decode_event(&refunded, module_address, &filter, AssetTag::Moveth).is_none());
+		// Same module deployed at another address.
+		let other_address = AccountAddress::from_hex_literal("0xbeef").unwrap();
+		assert!(
+			decode_event(&refunded, other_address, &EventFilter::all(), AssetTag::Moveth).is_none()
+		);
+		// Not a bridge event.
+		let deposit = event("0x1::coin::DepositEvent", r#"{"amount": "100"}"#, 0);
+		assert!(
+			decode_event(&deposit, module_address, &EventFilter::all(), AssetTag::Moveth).is_none()
+		);
+	}
+}
@@ -1,3 +1,4 @@
 pub mod client;
 pub mod event_monitoring;
+pub mod event_stream;
 pub mod utils;
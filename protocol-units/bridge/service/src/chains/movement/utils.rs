@@ -1,6 +1,7 @@
 use super::client::MovementClient;
 use crate::chains::bridge_contracts::BridgeContractError;
-use crate::types::{BridgeAddress, HashLockPreImage};
+use crate::chains::bridge_contracts::BridgeTransferResult;
+use crate::types::{BridgeAddress, BridgeTransferId, HashLockPreImage};
 use anyhow::{Context, Result};
 use aptos_sdk::crypto::ed25519::Ed25519PrivateKey;
 use aptos_sdk::crypto::ed25519::Ed25519PublicKey;
@@ -230,6 +231,35 @@ pub fn extract_bridge_transfer_id(txn: Transaction) -> Option<String> {
 	None
 }
 
+/// Id of the transfer created by an `initiate_bridge_transfer` transaction.
+pub fn initiated_transfer_id(
+	txn: &AptosTransaction,
+) -> Result<BridgeTransferId, BridgeContractError> {
+	let transfer_id = extract_bridge_transfer_id(txn.clone())
+		.ok_or(BridgeContractError::TransferIdExtractionError)?;
+	let transfer_id = hex::decode(transfer_id.trim_start_matches("0x"))
+		.map_err(|_| BridgeContractError::TransferIdExtractionError)?;
+	BridgeTransferId::try_from(transfer_id)
+		.map_err(|_| BridgeContractError::TransferIdExtractionError)
+}
+
+pub fn transfer_result(
+	transfer_id: BridgeTransferId,
+	txn: &AptosTransaction,
+) -> Result<BridgeTransferResult, BridgeContractError> {
+	match txn {
+		AptosTransaction::UserTransaction(user_txn) => Ok(BridgeTransferResult {
+			transfer_id,
+			tx_hash: *user_txn.info.hash.0,
+			block: user_txn.info.version.into(),
+			gas_used: user_txn.info.gas_used.into(),
+		}),
+		_ => Err(BridgeContractError::OnChainError(
+			"Expected a UserTransaction, but got a different transaction type.".to_string(),
+		)),
+	}
+}
+
 pub fn val_as_str(value: Option<&Value>) -> Result<&str, BridgeContractError> {
 	value
 		.as_ref()
@@ -0,0 +1,71 @@
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use std::collections::BTreeMap;
+
+/// Headers added to every RPC request of a chain client, for endpoints behind an
+/// authenticating proxy. A non empty `bearer_token` is sent as the `Authorization` header.
+pub fn rpc_headers(
+	headers: &BTreeMap<String, String>,
+	bearer_token: &str,
+) -> Result<HeaderMap, anyhow::Error> {
+	let mut header_map = HeaderMap::new();
+	for (name, value) in headers {
+		header_map.insert(HeaderName::from_bytes(name.as_bytes())?, HeaderValue::from_str(value)?);
+	}
+	if !bearer_token.is_empty() {
+		let mut authorization = HeaderValue::from_str(&format!("Bearer {bearer_token}"))?;
+		authorization.set_sensitive(true);
+		header_map.insert(AUTHORIZATION, authorization);
+	}
+	Ok(header_map)
+}
+
+/// HTTP client sending `headers` with every request.
+pub fn rpc_http_client(headers: HeaderMap) -> Result<reqwest::Client, anyhow::Error> {
+	Ok(reqwest::Client::builder().default_headers(headers).build()?)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+	use tokio::net::TcpListener;
+
+	/// Serve one HTTP request with `body` as JSON response, return the received request.
+	pub(crate) async fn mock_endpoint(
+		body: &'static str,
+	) -> (String, tokio::task::JoinHandle<String>) {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = format!("http://{}", listener.local_addr().unwrap());
+		let request = tokio::spawn(async move {
+			let (mut socket, _) = listener.accept().await.unwrap();
+			let mut buf = vec![0; 64 * 1024];
+			let len = socket.read(&mut buf).await.unwrap();
+			let response = format!(
+				"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+				body.len()
+			);
+			socket.write_all(response.as_bytes()).await.unwrap();
+			String::from_utf8_lossy(&buf[..len]).to_lowercase()
+		});
+		(url, request)
+	}
+
+	#[tokio::test]
+	async fn test_configured_headers_are_sent() {
+		let headers = BTreeMap::from([("X-Api-Key".to_string(), "secret-key".to_string())]);
+		let client = rpc_http_client(rpc_headers(&headers, "token").unwrap()).unwrap();
+		let (url, request) = mock_endpoint("[]").await;
+
+		client.get(format!("{url}/v1/events")).send().await.unwrap();
+		let request = request.await.unwrap();
+		assert!(request.contains("x-api-key: secret-key"), "{request}");
+		assert!(request.contains("authorization: bearer token"), "{request}");
+	}
+
+	#[test]
+	fn test_invalid_header_is_rejected() {
+		let headers = BTreeMap::from([("bad header".to_string(), "value".to_string())]);
+		assert!(rpc_headers(&headers, "").is_err());
+		assert!(rpc_headers(&BTreeMap::new(), "").unwrap().is_empty());
+	}
+}
@@ -0,0 +1,254 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use tokio::fs;
+
+/// How often the checkpoint is persisted: once it moved `every_blocks` blocks forward or
+/// `every_secs` seconds after the last write, whichever comes first. A 0 bound is ignored,
+/// both at 0 persist every advance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckpointCadence {
+	pub every_blocks: u64,
+	pub every_secs: u64,
+}
+
+/// Tracks the last block whose events can't be reorged anymore and are all processed.
+/// The monitoring restarts after it, so no event is skipped by a reorg of the unsafe blocks
+/// or by a restart before the relayer processed the observed events.
+#[derive(Debug, Clone)]
+pub struct CheckpointTracker {
+	confirmation_depth: u64,
+	cadence: CheckpointCadence,
+	persisted: Option<u64>,
+	persisted_at: u64,
+	// Highest block of a processed event, the log streams polled at least up to it.
+	processed: Option<u64>,
+}
+
+impl CheckpointTracker {
+	/// `persisted` is the checkpoint loaded at startup, if any.
+	pub fn new(
+		confirmation_depth: u64,
+		cadence: CheckpointCadence,
+		persisted: Option<u64>,
+		now: u64,
+	) -> Self {
+		CheckpointTracker {
+			confirmation_depth,
+			cadence,
+			persisted,
+			persisted_at: now,
+			processed: None,
+		}
+	}
+
+	/// Block the monitoring restarts from, None if there is no checkpoint.
+	pub fn start_block(&self) -> Option<u64> {
+		self.persisted.map(|block| block + 1)
+	}
+
+	/// Record that an event of `block` was processed by the relayer.
+	pub fn processed(&mut self, block: u64) {
+		self.processed = Some(self.processed.map_or(block, |processed| processed.max(block)));
+	}
+
+	/// Highest safe block at chain head `head`: `confirmation_depth` below the head, before the
+	/// last processed event, whose block may hold events not polled yet, and before the oldest
+	/// event observed but not processed yet.
+	/// None until an event was processed, nothing proves the blocks were polled before.
+	pub fn safe_block(&self, head: u64, oldest_unprocessed: Option<u64>) -> Option<u64> {
+		let confirmed = head.checked_sub(self.confirmation_depth)?;
+		let polled = self.processed?.checked_sub(1)?;
+		let safe = confirmed.min(polled);
+		match oldest_unprocessed {
+			Some(block) => Some(safe.min(block.checked_sub(1)?)),
+			None => Some(safe),
+		}
+	}
+
+	/// Return the checkpoint to persist at `head` if it moved forward and the cadence is reached.
+	pub fn advance(&mut self, head: u64, oldest_unprocessed: Option<u64>, now: u64) -> Option<u64> {
+		let safe = self.safe_block(head, oldest_unprocessed)?;
+		if let Some(persisted) = self.persisted {
+			if safe <= persisted {
+				return None;
+			}
+			let CheckpointCadence { every_blocks, every_secs } = self.cadence;
+			let blocks_due = every_blocks != 0 && safe - persisted >= every_blocks;
+			let time_due = every_secs != 0 && now.saturating_sub(self.persisted_at) >= every_secs;
+			let unbounded = every_blocks == 0 && every_secs == 0;
+			if !(blocks_due || time_due || unbounded) {
+				return None;
+			}
+		}
+		self.persisted = Some(safe);
+		self.persisted_at = now;
+		Some(safe)
+	}
+}
+
+/// Positions of the events sent to the relayer that it hasn't processed yet, in sending order.
+/// The relayer processes an event before it takes the next one, so an event is processed once
+/// the event sent after it is taken.
+pub struct InFlightEvents<T> {
+	positions: VecDeque<T>,
+	// The first event was taken and is being processed.
+	first_taken: bool,
+}
+
+impl<T> InFlightEvents<T> {
+	pub fn new() -> Self {
+		InFlightEvents { positions: VecDeque::new(), first_taken: false }
+	}
+
+	pub fn sent(&mut self, position: T) {
+		self.positions.push_back(position);
+	}
+
+	/// The relayer took the next event, return the position of the event it processed before.
+	pub fn taken(&mut self) -> Option<T> {
+		let processed = if self.first_taken { self.positions.pop_front() } else { None };
+		self.first_taken = !self.positions.is_empty();
+		processed
+	}
+
+	/// Positions of the events not processed yet, including the one being processed.
+	pub fn unprocessed(&self) -> impl Iterator<Item = &T> {
+		self.positions.iter()
+	}
+}
+
+impl<T> Default for InFlightEvents<T> {
+	fn default() -> Self {
+		InFlightEvents::new()
+	}
+}
+
+/// Checkpoint written in a file holding the block number.
+pub struct CheckpointFile {
+	path: PathBuf,
+}
+
+impl CheckpointFile {
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		CheckpointFile { path: path.into() }
+	}
+
+	/// None if no checkpoint was written yet.
+	pub async fn load(&self) -> Result<Option<u64>, anyhow::Error> {
+		if !fs::try_exists(&self.path).await? {
+			return Ok(None);
+		}
+		let content = fs::read_to_string(&self.path).await?;
+		Ok(Some(content.trim().parse()?))
+	}
+
+	/// Replace the checkpoint, the previous one is kept if the write is interrupted.
+	pub async fn save(&self, block: u64) -> Result<(), std::io::Error> {
+		if let Some(parent) = self.path.parent() {
+			fs::create_dir_all(parent).await?;
+		}
+		let mut tmp_path = self.path.clone().into_os_string();
+		tmp_path.push(".tmp");
+		fs::write(&tmp_path, block.to_string()).await?;
+		fs::rename(&tmp_path, &self.path).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const EVERY_BLOCK: CheckpointCadence = CheckpointCadence { every_blocks: 1, every_secs: 0 };
+
+	#[test]
+	fn test_checkpoint_lags_head_and_unprocessed_events() {
+		let mut tracker = CheckpointTracker::new(12, EVERY_BLOCK, None, 0);
+		// Nothing processed, the streams may not have polled any block.
+		assert_eq!(tracker.advance(100, None, 0), None);
+		tracker.processed(200);
+		// Not enough blocks to be safe.
+		assert_eq!(tracker.advance(10, None, 0), None);
+		assert_eq!(tracker.advance(100, None, 0), Some(88));
+		// An event of block 95 waits for its confirmations or for the relayer.
+		assert_eq!(tracker.advance(110, Some(95), 0), Some(94));
+		assert_eq!(tracker.advance(120, Some(95), 0), None);
+		assert_eq!(tracker.advance(120, None, 0), Some(108));
+	}
+
+	#[test]
+	fn test_checkpoint_stays_before_the_last_processed_event() {
+		let mut tracker = CheckpointTracker::new(0, EVERY_BLOCK, None, 0);
+		tracker.processed(50);
+		// The head is far ahead but the streams are only known to have polled block 50.
+		assert_eq!(tracker.advance(1_000, None, 0), Some(49));
+		// An event of an older block doesn't move it back.
+		tracker.processed(40);
+		assert_eq!(tracker.advance(1_000, None, 0), None);
+		tracker.processed(60);
+		assert_eq!(tracker.advance(1_000, None, 0), Some(59));
+	}
+
+	#[test]
+	fn test_cadence_bounds_the_writes() {
+		let cadence = CheckpointCadence { every_blocks: 50, every_secs: 60 };
+		let mut tracker = CheckpointTracker::new(0, cadence, Some(100), 1_000);
+		tracker.processed(1_000);
+		assert_eq!(tracker.advance(120, None, 1_010), None);
+		// Enough blocks.
+		assert_eq!(tracker.advance(150, None, 1_020), Some(150));
+		assert_eq!(tracker.advance(160, None, 1_030), None);
+		// Enough time.
+		assert_eq!(tracker.advance(160, None, 1_080), Some(160));
+	}
+
+	#[test]
+	fn test_event_is_processed_once_the_next_one_is_taken() {
+		let mut in_flight = InFlightEvents::new();
+		in_flight.sent(10);
+		in_flight.sent(20);
+		// The first event is being processed.
+		assert_eq!(in_flight.taken(), None);
+		assert_eq!(in_flight.unprocessed().collect::<Vec<_>>(), vec![&10, &20]);
+		assert_eq!(in_flight.taken(), Some(10));
+		in_flight.sent(30);
+		assert_eq!(in_flight.taken(), Some(20));
+		assert_eq!(in_flight.unprocessed().collect::<Vec<_>>(), vec![&30]);
+	}
+
+	#[tokio::test]
+	async fn test_reorg_within_confirmation_window_is_replayed_on_restart() {
+		let path =
+			std::env::temp_dir().join(format!("bridge-checkpoint-reorg-{}", std::process::id()));
+		let _ = fs::remove_file(&path).await;
+		let file = CheckpointFile::new(&path);
+		// Events of the chain by block.
+		let mut chain = vec![(90, "initiated"), (96, "locked"), (100, "completed")];
+
+		// At head 110 the relayer processed the events up to block 96, the one of block 100
+		// waits for its confirmations.
+		let mut tracker = CheckpointTracker::new(12, EVERY_BLOCK, file.load().await.unwrap(), 0);
+		tracker.processed(90);
+		tracker.processed(96);
+		let checkpoint = tracker.advance(110, Some(100), 0).unwrap();
+		file.save(checkpoint).await.unwrap();
+
+		// A reorg within the confirmation window moves the event of block 100 to block 103.
+		// The pending event is dropped and the head moves on before the streams polled block 103.
+		chain.retain(|(block, _)| *block < 100);
+		chain.push((103, "completed"));
+		if let Some(checkpoint) = tracker.advance(130, None, 0) {
+			file.save(checkpoint).await.unwrap();
+		}
+
+		// The relayer restarts, the event of the reorged block is delivered again.
+		let tracker = CheckpointTracker::new(12, EVERY_BLOCK, file.load().await.unwrap(), 0);
+		let start_block = tracker.start_block().unwrap();
+		let replayed: Vec<_> = chain
+			.iter()
+			.filter(|(block, _)| *block >= start_block)
+			.map(|(_, event)| *event)
+			.collect();
+		assert_eq!(replayed, vec!["locked", "completed"]);
+		fs::remove_file(&path).await.unwrap();
+	}
+}
@@ -0,0 +1,452 @@
+use crate::chains::bridge_contracts::BridgeContractEvent;
+use crate::types::{AssetTag, BridgeTransferId};
+use bridge_config::common::eth::{AssetConfirmation, ConfirmationTier};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Maps a transfer asset and amount to the number of confirmations required before acting on it.
+#[derive(Debug, Clone, Default)]
+pub struct ConfirmationPolicy {
+	// Sorted by ascending `min_amount`.
+	tiers: Vec<ConfirmationTier>,
+	asset_overrides: HashMap<AssetTag, u64>,
+	min_confirmations: u64,
+}
+
+impl ConfirmationPolicy {
+	pub fn new(mut tiers: Vec<ConfirmationTier>) -> Self {
+		tiers.sort_by_key(|tier| tier.min_amount);
+		ConfirmationPolicy { tiers, asset_overrides: HashMap::new(), min_confirmations: 0 }
+	}
+
+	/// Set the depth required for every event, whatever its tier or asset.
+	pub fn with_min_confirmations(mut self, confirmations: u64) -> Self {
+		self.min_confirmations = confirmations;
+		self
+	}
+
+	/// Add the per asset depths on top of the tiers.
+	/// Fails if an asset is unknown or configured twice, its depth couldn't be resolved.
+	pub fn with_asset_overrides(
+		mut self,
+		overrides: &[AssetConfirmation],
+	) -> Result<Self, anyhow::Error> {
+		for asset_override in overrides {
+			let asset: AssetTag = asset_override.asset.parse()?;
+			if self.asset_overrides.insert(asset, asset_override.confirmations).is_some() {
+				anyhow::bail!("Confirmations of asset {asset} are configured more than once");
+			}
+		}
+		Ok(self)
+	}
+
+	/// Depth of the asset override if any, else of the highest tier whose threshold
+	/// is reached by `amount`, never below the minimum depth.
+	/// Amounts below every threshold only need the minimum depth.
+	pub fn required_confirmations(&self, asset: AssetTag, amount: u64) -> u64 {
+		let confirmations = match self.asset_overrides.get(&asset) {
+			Some(confirmations) => *confirmations,
+			None => self
+				.tiers
+				.iter()
+				.take_while(|tier| tier.min_amount <= amount)
+				.last()
+				.map(|tier| tier.confirmations)
+				.unwrap_or(0),
+		};
+		confirmations.max(self.min_confirmations)
+	}
+
+	/// Deepest confirmation requirement of the policy.
+	pub fn max_confirmations(&self) -> u64 {
+		let tiers = self.tiers.iter().map(|tier| tier.confirmations);
+		tiers
+			.chain(self.asset_overrides.values().copied())
+			.fold(self.min_confirmations, u64::max)
+	}
+}
+
+/// Value used to select the confirmation tier of an event.
+/// Events that don't carry an amount use the lowest tier.
+pub fn event_amount<A>(event: &BridgeContractEvent<A>) -> u64 {
+	match event {
+		BridgeContractEvent::Initiated(details) => details.amount.value(),
+		BridgeContractEvent::Locked(details) => details.amount.value(),
+		_ => 0,
+	}
+}
+
+/// Event held by a `ConfirmationGate`, keyed by its transfer.
+pub trait GatedEvent {
+	type Key: Eq + Hash;
+
+	fn transfer_key(&self) -> Self::Key;
+}
+
+impl<A> GatedEvent for BridgeContractEvent<A> {
+	type Key = BridgeTransferId;
+
+	fn transfer_key(&self) -> BridgeTransferId {
+		self.bridge_transfer_id()
+	}
+}
+
+// An event tagged with its block, e.g. to track it once released, is keyed by the event.
+impl<E: GatedEvent, B> GatedEvent for (E, B) {
+	type Key = E::Key;
+
+	fn transfer_key(&self) -> E::Key {
+		self.0.transfer_key()
+	}
+}
+
+struct PendingEvent<E> {
+	event: E,
+	block: u64,
+	release_block: u64,
+	// Set when confirming by chain time.
+	release_timestamp: Option<u64>,
+}
+
+/// Holds observed events until the chain head is deep enough above them,
+/// or until enough chain time elapsed since their block.
+/// The events of a transfer are released in arrival order: a later event, even with a lower
+/// tier, waits for the earlier ones of its transfer.
+pub struct ConfirmationGate<E> {
+	policy: ConfirmationPolicy,
+	pending: Vec<PendingEvent<E>>,
+	head: u64,
+	chain_time_secs: Option<u64>,
+	head_timestamp: u64,
+}
+
+impl<E: GatedEvent> ConfirmationGate<E> {
+	pub fn new(policy: ConfirmationPolicy) -> Self {
+		ConfirmationGate {
+			policy,
+			pending: Vec::new(),
+			head: 0,
+			chain_time_secs: None,
+			head_timestamp: 0,
+		}
+	}
+
+	/// Confirm the events once `secs` of chain time elapsed since the timestamp of their block,
+	/// instead of by block count. The chain time is the timestamp of the latest block.
+	pub fn with_chain_time(mut self, secs: u64) -> Self {
+		self.chain_time_secs = Some(secs);
+		self
+	}
+
+	pub fn confirms_by_chain_time(&self) -> bool {
+		self.chain_time_secs.is_some()
+	}
+
+	/// Queue an event observed in `block_number`.
+	/// If no confirmation is required the event is returned immediately.
+	/// An unknown block number is treated as the last known head.
+	pub fn push(
+		&mut self,
+		event: E,
+		block_number: Option<u64>,
+		asset: AssetTag,
+		amount: u64,
+	) -> Option<E> {
+		let confirmations = self.policy.required_confirmations(asset, amount);
+		if confirmations == 0 && !self.holds_transfer(&event) {
+			return Some(event);
+		}
+		let block = block_number.unwrap_or(self.head);
+		let release_block = block.saturating_add(confirmations);
+		self.pending
+			.push(PendingEvent { event, block, release_block, release_timestamp: None });
+		None
+	}
+
+	/// Queue an event observed in `block_number` whose block has `block_timestamp`.
+	/// Without chain time confirmation this is `push`. With it the amount and asset are ignored,
+	/// and an unknown timestamp is treated as the last known head's.
+	pub fn push_timed(
+		&mut self,
+		event: E,
+		block_number: Option<u64>,
+		block_timestamp: Option<u64>,
+		asset: AssetTag,
+		amount: u64,
+	) -> Option<E> {
+		let Some(secs) = self.chain_time_secs else {
+			return self.push(event, block_number, asset, amount);
+		};
+		if secs == 0 && !self.holds_transfer(&event) {
+			return Some(event);
+		}
+		let block = block_number.unwrap_or(self.head);
+		let release_timestamp = block_timestamp.unwrap_or(self.head_timestamp).saturating_add(secs);
+		self.pending.push(PendingEvent {
+			event,
+			block,
+			release_block: block,
+			release_timestamp: Some(release_timestamp),
+		});
+		None
+	}
+
+	/// Return, in arrival order, all events that are confirmed at chain head `head`.
+	pub fn release(&mut self, head: u64) -> Vec<E> {
+		self.head = self.head.max(head);
+		self.release_confirmed()
+	}
+
+	/// Return, in arrival order, all events that are confirmed at chain head `head` whose block
+	/// has `head_timestamp`.
+	pub fn release_timed(&mut self, head: u64, head_timestamp: u64) -> Vec<E> {
+		self.head = self.head.max(head);
+		self.head_timestamp = self.head_timestamp.max(head_timestamp);
+		self.release_confirmed()
+	}
+
+	fn release_confirmed(&mut self) -> Vec<E> {
+		let (head, head_timestamp) = (self.head, self.head_timestamp);
+		// Transfers with an earlier event still pending.
+		let mut held = HashSet::new();
+		let mut ready = Vec::new();
+		for pending in std::mem::take(&mut self.pending) {
+			let confirmed = match pending.release_timestamp {
+				Some(release_timestamp) => release_timestamp <= head_timestamp,
+				None => pending.release_block <= head,
+			};
+			let key = pending.event.transfer_key();
+			if confirmed && !held.contains(&key) {
+				ready.push(pending.event);
+			} else {
+				held.insert(key);
+				self.pending.push(pending);
+			}
+		}
+		ready
+	}
+
+	fn holds_transfer(&self, event: &E) -> bool {
+		let key = event.transfer_key();
+		self.pending.iter().any(|pending| pending.event.transfer_key() == key)
+	}
+
+	/// Drop the pending events equal to `event`, once its block was reorged out.
+	/// Returns false if none was pending, the event may already have been released.
+	pub fn drop_reorged(&mut self, event: &E) -> bool
+	where
+		E: PartialEq,
+	{
+		let pending_count = self.pending.len();
+		self.pending.retain(|pending| pending.event != *event);
+		self.pending.len() < pending_count
+	}
+
+	pub fn has_pending(&self) -> bool {
+		!self.pending.is_empty()
+	}
+
+	/// Block of the oldest event waiting for its confirmations.
+	pub fn oldest_pending_block(&self) -> Option<u64> {
+		self.pending.iter().map(|pending| pending.block).min()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::types::{
+		Amount, AssetType, BridgeAddress, HashLock, HashLockPreImage, LockDetails, TimeLock,
+	};
+
+	// Every test event is a transfer of its own.
+	impl GatedEvent for &'static str {
+		type Key = &'static str;
+
+		fn transfer_key(&self) -> &'static str {
+			self
+		}
+	}
+
+	fn tiered_gate() -> ConfirmationGate<&'static str> {
+		ConfirmationGate::new(ConfirmationPolicy::new(vec![
+			ConfirmationTier { min_amount: 1_000, confirmations: 12 },
+			ConfirmationTier { min_amount: 0, confirmations: 2 },
+		]))
+	}
+
+	#[test]
+	fn test_small_transfer_uses_low_depth() {
+		let mut gate = tiered_gate();
+		assert!(gate.push("small", Some(100), AssetTag::EthAndWeth, 10).is_none());
+		assert!(gate.release(101).is_empty());
+		assert_eq!(gate.release(102), vec!["small"]);
+		assert!(!gate.has_pending());
+	}
+
+	#[test]
+	fn test_large_transfer_uses_high_depth() {
+		let mut gate = tiered_gate();
+		assert!(gate.push("large", Some(100), AssetTag::EthAndWeth, 5_000).is_none());
+		assert!(gate.release(102).is_empty());
+		assert!(gate.release(111).is_empty());
+		assert_eq!(gate.release(112), vec!["large"]);
+	}
+
+	#[test]
+	fn test_oldest_pending_block() {
+		let mut gate = tiered_gate();
+		assert_eq!(gate.oldest_pending_block(), None);
+		gate.push("large", Some(100), AssetTag::EthAndWeth, 5_000);
+		gate.push("small", Some(105), AssetTag::EthAndWeth, 10);
+		assert_eq!(gate.oldest_pending_block(), Some(100));
+		assert_eq!(gate.release(107), vec!["small"]);
+		assert_eq!(gate.oldest_pending_block(), Some(100));
+		assert_eq!(gate.release(112), vec!["large"]);
+		assert_eq!(gate.oldest_pending_block(), None);
+	}
+
+	#[test]
+	fn test_asset_override_takes_precedence() {
+		let policy =
+			ConfirmationPolicy::new(vec![ConfirmationTier { min_amount: 0, confirmations: 2 }])
+				.with_asset_overrides(&[AssetConfirmation {
+					asset: "moveth".to_string(),
+					confirmations: 30,
+				}])
+				.unwrap();
+		assert_eq!(policy.required_confirmations(AssetTag::Moveth, 10), 30);
+		assert_eq!(policy.required_confirmations(AssetTag::EthAndWeth, 10), 2);
+		assert_eq!(policy.max_confirmations(), 30);
+
+		let mut gate = ConfirmationGate::new(policy);
+		assert!(gate.push("moveth", Some(100), AssetTag::Moveth, 10).is_none());
+		assert!(gate.release(102).is_empty());
+		assert_eq!(gate.release(130), vec!["moveth"]);
+	}
+
+	#[test]
+	fn test_unresolvable_asset_override_is_rejected() {
+		let unknown = [AssetConfirmation { asset: "doge".to_string(), confirmations: 30 }];
+		assert!(ConfirmationPolicy::default().with_asset_overrides(&unknown).is_err());
+
+		let duplicated = [
+			AssetConfirmation { asset: "moveth".to_string(), confirmations: 30 },
+			AssetConfirmation { asset: "moveth".to_string(), confirmations: 5 },
+		];
+		assert!(ConfirmationPolicy::default().with_asset_overrides(&duplicated).is_err());
+	}
+
+	#[test]
+	fn test_chain_time_holds_events_until_elapsed() {
+		// The tiers are ignored when confirming by chain time.
+		let mut gate = tiered_gate().with_chain_time(120);
+		assert!(gate.confirms_by_chain_time());
+		assert!(gate
+			.push_timed("first", Some(100), Some(1_000), AssetTag::EthAndWeth, 10)
+			.is_none());
+		assert!(gate
+			.push_timed("second", Some(101), Some(1_030), AssetTag::EthAndWeth, 5_000)
+			.is_none());
+
+		// Many blocks but little chain time.
+		assert!(gate.release_timed(200, 1_100).is_empty());
+		assert_eq!(gate.release_timed(201, 1_120), vec!["first"]);
+		// Block count alone doesn't release the event.
+		assert!(gate.release(10_000).is_empty());
+		assert_eq!(gate.oldest_pending_block(), Some(101));
+		assert!(gate.release_timed(202, 1_149).is_empty());
+		assert_eq!(gate.release_timed(203, 1_150), vec!["second"]);
+		assert!(!gate.has_pending());
+	}
+
+	#[test]
+	fn test_min_confirmations_applies_to_every_event() {
+		let policy = ConfirmationPolicy::new(vec![ConfirmationTier {
+			min_amount: 1_000,
+			confirmations: 12,
+		}])
+		.with_min_confirmations(6);
+		assert_eq!(policy.required_confirmations(AssetTag::EthAndWeth, 10), 6);
+		assert_eq!(policy.required_confirmations(AssetTag::EthAndWeth, 5_000), 12);
+		assert_eq!(policy.max_confirmations(), 12);
+		assert_eq!(ConfirmationPolicy::default().with_min_confirmations(6).max_confirmations(), 6);
+	}
+
+	#[test]
+	fn test_reorged_event_is_never_released() {
+		let mut gate =
+			ConfirmationGate::new(ConfirmationPolicy::default().with_min_confirmations(3));
+		assert!(gate.push("reorged", Some(100), AssetTag::EthAndWeth, 10).is_none());
+		assert!(gate.push("kept", Some(100), AssetTag::EthAndWeth, 10).is_none());
+		assert!(gate.release(101).is_empty());
+
+		// Block 100 is replaced, the event is replayed as removed before its confirmations.
+		assert!(gate.drop_reorged(&"reorged"));
+		assert!(!gate.drop_reorged(&"reorged"));
+		assert_eq!(gate.release(1_000), vec!["kept"]);
+		assert!(!gate.has_pending());
+	}
+
+	#[test]
+	fn test_push_timed_without_chain_time_uses_depth() {
+		let mut gate = tiered_gate();
+		assert!(!gate.confirms_by_chain_time());
+		assert!(gate
+			.push_timed("small", Some(100), Some(1_000), AssetTag::EthAndWeth, 10)
+			.is_none());
+		assert_eq!(gate.release_timed(102, 1_000), vec!["small"]);
+	}
+
+	#[test]
+	fn test_transfer_events_are_released_in_order() {
+		let transfer_id = BridgeTransferId([1; 32]);
+		let locked = BridgeContractEvent::Locked(LockDetails {
+			bridge_transfer_id: transfer_id,
+			initiator_address: BridgeAddress(vec![1; 32]),
+			recipient_address: BridgeAddress(vec![2; 20]),
+			hash_lock: HashLock([3; 32]),
+			time_lock: TimeLock(100),
+			amount: Amount(AssetType::EthAndWeth((0, 5_000))),
+		});
+		let completed = BridgeContractEvent::CounterPartCompleted(
+			transfer_id,
+			HashLockPreImage([4; 32]),
+			AssetTag::EthAndWeth,
+		);
+		let other = BridgeContractEvent::Refunded(BridgeTransferId([2; 32]), AssetTag::EthAndWeth);
+
+		let mut gate = ConfirmationGate::new(ConfirmationPolicy::new(vec![
+			ConfirmationTier { min_amount: 1_000, confirmations: 12 },
+			ConfirmationTier { min_amount: 0, confirmations: 2 },
+		]));
+		assert!(gate.push(locked.clone(), Some(100), AssetTag::EthAndWeth, 5_000).is_none());
+		assert!(gate.push(completed.clone(), Some(101), AssetTag::EthAndWeth, 0).is_none());
+		assert!(gate.push(other.clone(), Some(101), AssetTag::EthAndWeth, 0).is_none());
+
+		// The completion is confirmed but waits behind the lock, other transfers don't.
+		assert_eq!(gate.release(103), vec![other]);
+		assert_eq!(gate.release(112), vec![locked, completed]);
+		assert!(!gate.has_pending());
+	}
+
+	#[test]
+	fn test_unconfirmed_transfer_holds_its_immediate_events() {
+		// Small amounts don't need confirmations.
+		let mut gate = ConfirmationGate::new(ConfirmationPolicy::new(vec![ConfirmationTier {
+			min_amount: 1_000,
+			confirmations: 12,
+		}]));
+		assert!(gate.push("transfer", Some(100), AssetTag::EthAndWeth, 5_000).is_none());
+		assert!(gate.push("transfer", Some(101), AssetTag::EthAndWeth, 10).is_none());
+		assert_eq!(gate.push("other", Some(101), AssetTag::EthAndWeth, 10), Some("other"));
+		assert!(gate.release(111).is_empty());
+		assert_eq!(gate.release(112), vec!["transfer", "transfer"]);
+	}
+
+	#[test]
+	fn test_no_tier_releases_immediately() {
+		let mut gate = ConfirmationGate::new(ConfirmationPolicy::default());
+		assert_eq!(gate.push("event", Some(100), AssetTag::EthAndWeth, 5_000), Some("event"));
+	}
+}
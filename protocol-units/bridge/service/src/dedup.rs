@@ -0,0 +1,102 @@
+use crate::chains::bridge_contracts::{BridgeContractEvent, BridgeContractEventType};
+use crate::types::BridgeTransferId;
+use std::collections::{HashSet, VecDeque};
+
+// A logical event: its type, its transfer and where it was emitted.
+type EventKey = (BridgeContractEventType, BridgeTransferId, Option<u64>);
+
+/// Bounded set of the last events a monitoring delivered, so an event observed again after a
+/// reconnect or in overlapping ranges is only delivered once.
+/// The position of an event is its block on Eth and its event sequence number on Movement.
+pub struct EventDedup {
+	order: VecDeque<EventKey>,
+	keys: HashSet<EventKey>,
+	capacity: usize,
+}
+
+impl EventDedup {
+	pub fn new(capacity: usize) -> Self {
+		EventDedup { order: VecDeque::new(), keys: HashSet::new(), capacity }
+	}
+
+	/// Returns false if the event was already delivered.
+	/// Once the capacity is reached, the oldest event is forgotten.
+	pub fn insert<A>(&mut self, event: &BridgeContractEvent<A>, position: Option<u64>) -> bool {
+		let key = (event.event_type(), event.bridge_transfer_id(), position);
+		if !self.keys.insert(key) {
+			return false;
+		}
+		self.order.push_back(key);
+		if self.order.len() > self.capacity {
+			if let Some(oldest) = self.order.pop_front() {
+				self.keys.remove(&oldest);
+			}
+		}
+		true
+	}
+
+	/// Forget a reorged out event, it's delivered again if it's included in the new chain.
+	pub fn forget<A>(&mut self, event: &BridgeContractEvent<A>, position: Option<u64>) {
+		let key = (event.event_type(), event.bridge_transfer_id(), position);
+		if self.keys.remove(&key) {
+			self.order.retain(|seen| *seen != key);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::types::AssetTag;
+
+	fn completed(id: u8) -> BridgeContractEvent<Vec<u8>> {
+		BridgeContractEvent::InitialtorCompleted(BridgeTransferId([id; 32]), AssetTag::EthAndWeth)
+	}
+
+	#[test]
+	fn test_duplicated_events_are_delivered_once() {
+		let mut dedup = EventDedup::new(16);
+		let observed = [
+			(completed(1), Some(100)),
+			(completed(1), Some(100)),
+			(
+				BridgeContractEvent::Refunded(BridgeTransferId([1; 32]), AssetTag::EthAndWeth),
+				Some(100),
+			),
+			(completed(2), Some(100)),
+			// The overlapping range replays the first block.
+			(completed(1), Some(100)),
+			(completed(2), Some(100)),
+		];
+		let delivered: Vec<_> = observed
+			.into_iter()
+			.filter(|(event, position)| dedup.insert(event, *position))
+			.map(|(event, _)| event)
+			.collect();
+		assert_eq!(delivered.len(), 3);
+		assert_eq!(delivered[0], completed(1));
+		assert_eq!(delivered[2], completed(2));
+	}
+
+	#[test]
+	fn test_dedup_window_is_bounded() {
+		let mut dedup = EventDedup::new(2);
+		assert!(dedup.insert(&completed(1), Some(100)));
+		assert!(dedup.insert(&completed(2), Some(100)));
+		assert!(!dedup.insert(&completed(1), Some(100)));
+		// The oldest event is forgotten once the capacity is reached.
+		assert!(dedup.insert(&completed(3), Some(101)));
+		assert_eq!(dedup.keys.len(), 2);
+		assert!(dedup.insert(&completed(1), Some(100)));
+	}
+
+	#[test]
+	fn test_reorged_event_is_delivered_again() {
+		let mut dedup = EventDedup::new(16);
+		assert!(dedup.insert(&completed(1), Some(100)));
+		dedup.forget(&completed(1), Some(100));
+		assert!(dedup.insert(&completed(1), Some(100)));
+		// Included in another block of the new chain.
+		assert!(dedup.insert(&completed(1), Some(101)));
+	}
+}
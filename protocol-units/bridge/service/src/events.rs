@@ -1,5 +1,6 @@
 use crate::chains::bridge_contracts::BridgeContractEvent;
-use crate::types::ChainId;
+use crate::states::TransferStateType;
+use crate::types::{ChainId, HashAlgorithm};
 use std::fmt;
 use thiserror::Error;
 
@@ -13,6 +14,12 @@ pub enum InvalidEventError {
 	BadEvent,
 	#[error("No existing state found for a non init event")]
 	StateNotFound,
+	#[error("Event id collides with an existing transfer with other parameters")]
+	TransferIdCollision,
+	#[error("Illegal transfer state transition from {0:?} to {1:?}")]
+	IllegalTransition(TransferStateType, TransferStateType),
+	#[error("Revealed secret doesn't match the hash lock with {0}")]
+	InvalidSecret(HashAlgorithm),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -2,28 +2,239 @@ use crate::actions::process_action;
 use crate::actions::ActionExecError;
 use crate::actions::TransferAction;
 use crate::actions::TransferActionType;
+use crate::balance::{BalanceGuard, DegradedChains};
 use crate::chains::bridge_contracts::BridgeContract;
 use crate::chains::bridge_contracts::BridgeContractEvent;
 use crate::chains::bridge_contracts::BridgeContractMonitoring;
 use crate::events::InvalidEventError;
 use crate::events::TransferEvent;
+use crate::metrics::RelayerMetrics;
+use crate::ordering::check_action;
+use crate::rate_limit::{CompletionRateLimits, CompletionThrottle};
+use crate::slo::CompletionSlo;
 use crate::states::TransferState;
 use crate::states::TransferStateType;
+use crate::store::{MemoryStore, TransferRecord, TransferStore};
+use crate::types::Amount;
+use crate::types::AssetTag;
 use crate::types::BridgeTransferId;
 use crate::types::ChainId;
+use crate::types::HashAlgorithm;
 use futures::stream::FuturesUnordered;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::select;
 use tokio::task::JoinError;
 use tokio::task::JoinHandle;
 use tokio_stream::StreamExt;
 
 mod actions;
+pub mod balance;
 pub mod chains;
+pub mod checkpoint;
+pub mod confirmation;
+pub mod dedup;
 mod events;
-mod states;
+pub mod metrics;
+pub mod ordering;
+pub mod rate_limit;
+pub mod self_check;
+pub mod slo;
+pub mod states;
+pub mod store;
 pub mod types;
 
+/// Interval between two checks of the expired and stalled transfers.
+const TIMEOUT_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Define, per initiating chain, what to do with the transfers whose time lock expired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefundPolicy {
+	/// Refund automatically the transfers initiated on chain ONE.
+	pub auto_refund_one: bool,
+	/// Refund automatically the transfers initiated on chain TWO.
+	pub auto_refund_two: bool,
+}
+
+impl RefundPolicy {
+	pub fn auto_refund(&self, init_chain: ChainId) -> bool {
+		match init_chain {
+			ChainId::ONE => self.auto_refund_one,
+			ChainId::TWO => self.auto_refund_two,
+		}
+	}
+}
+
+/// Hash function of the contracts of each chain.
+/// A transfer uses the one of its initiating chain and is only locked if the other chain
+/// uses the same.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HashAlgorithms {
+	pub one: HashAlgorithm,
+	pub two: HashAlgorithm,
+}
+
+impl HashAlgorithms {
+	/// Parse the configured hash functions of the Eth and Movement contracts.
+	/// Fails on sha256, the contracts only verify keccak256 hash locks for now.
+	// Chain ONE is Eth, chain TWO is Movement.
+	pub fn from_config(
+		eth_hash_algorithm: &str,
+		movement_hash_algorithm: &str,
+	) -> Result<Self, anyhow::Error> {
+		let supported = |configured: &str| match configured.parse()? {
+			HashAlgorithm::Sha256 => {
+				anyhow::bail!("Hash algorithm {configured} isn't supported by the bridge contracts")
+			}
+			algorithm => Ok(algorithm),
+		};
+		Ok(HashAlgorithms {
+			one: supported(eth_hash_algorithm)?,
+			two: supported(movement_hash_algorithm)?,
+		})
+	}
+
+	pub fn of(&self, chain: ChainId) -> HashAlgorithm {
+		match chain {
+			ChainId::ONE => self.one,
+			ChainId::TWO => self.two,
+		}
+	}
+}
+
+/// Asset of the bridge contract of each chain.
+/// The counterparty lock of a transfer carries its value in the asset of the other chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainAssets {
+	pub one: AssetTag,
+	pub two: AssetTag,
+}
+
+impl ChainAssets {
+	pub fn of(&self, chain: ChainId) -> AssetTag {
+		match chain {
+			ChainId::ONE => self.one,
+			ChainId::TWO => self.two,
+		}
+	}
+}
+
+impl Default for RefundPolicy {
+	fn default() -> Self {
+		RefundPolicy { auto_refund_one: true, auto_refund_two: true }
+	}
+}
+
+/// Re-submission of the initiator completions whose event isn't observed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NudgePolicy {
+	/// Seconds without the completion event before a nudge.
+	pub delay_secs: u64,
+	pub max_attempts: u32,
+}
+
+impl NudgePolicy {
+	/// None if nudging is disabled.
+	pub fn from_config(config: &bridge_config::common::nudge::NudgeConfig) -> Option<Self> {
+		config.nudge_enabled.then_some(NudgePolicy {
+			delay_secs: config.nudge_delay_secs,
+			max_attempts: config.nudge_max_attempts,
+		})
+	}
+}
+
+/// Seconds, per initiating chain, after which an incomplete transfer is flagged as stuck.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StuckPolicy {
+	pub grace_secs_one: u64,
+	pub grace_secs_two: u64,
+}
+
+impl StuckPolicy {
+	pub fn grace_secs(&self, init_chain: ChainId) -> u64 {
+		match init_chain {
+			ChainId::ONE => self.grace_secs_one,
+			ChainId::TWO => self.grace_secs_two,
+		}
+	}
+
+	/// The grace windows are extended by the time `eth_confirmations` Eth blocks take,
+	/// both directions wait for them.
+	// Chain ONE is Eth, chain TWO is Movement.
+	pub fn from_config(
+		config: &bridge_config::common::stuck::StuckConfig,
+		eth_confirmations: u64,
+	) -> Self {
+		let confirmation_secs = eth_confirmations.saturating_mul(config.eth_block_time_secs);
+		StuckPolicy {
+			grace_secs_one: config
+				.eth_to_movement_stuck_grace_secs
+				.saturating_add(confirmation_secs),
+			grace_secs_two: config
+				.movement_to_eth_stuck_grace_secs
+				.saturating_add(confirmation_secs),
+		}
+	}
+}
+
+impl Default for StuckPolicy {
+	fn default() -> Self {
+		StuckPolicy::from_config(&bridge_config::common::stuck::StuckConfig::default(), 0)
+	}
+}
+
+pub(crate) fn unix_now() -> u64 {
+	std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.map(|since_epoch| since_epoch.as_secs())
+		.unwrap_or(0)
+}
+
+// Chain ONE is Eth, chain TWO is Movement.
+impl From<&bridge_config::common::refund::RefundConfig> for RefundPolicy {
+	fn from(config: &bridge_config::common::refund::RefundConfig) -> Self {
+		RefundPolicy {
+			auto_refund_one: config.eth_to_movement_auto_refund,
+			auto_refund_two: config.movement_to_eth_auto_refund,
+		}
+	}
+}
+
+/// Settings of the relayer, the defaults keep the transfers in memory, disable the
+/// nudges, the completion SLO and the metrics, and expect the locks in the initiated asset.
+pub struct RelayerOptions {
+	pub refund_policy: RefundPolicy,
+	pub nudge_policy: Option<NudgePolicy>,
+	pub stuck_policy: StuckPolicy,
+	/// Chains whose completions are paused, shared with the balance monitoring.
+	pub degraded_chains: DegradedChains,
+	pub completion_slo: Option<CompletionSlo>,
+	pub metrics: Option<RelayerMetrics>,
+	pub store: Arc<dyn TransferStore>,
+	pub hash_algorithms: HashAlgorithms,
+	pub rate_limits: CompletionRateLimits,
+	pub assets: Option<ChainAssets>,
+}
+
+impl Default for RelayerOptions {
+	fn default() -> Self {
+		RelayerOptions {
+			refund_policy: RefundPolicy::default(),
+			nudge_policy: None,
+			stuck_policy: StuckPolicy::default(),
+			degraded_chains: DegradedChains::default(),
+			completion_slo: None,
+			metrics: None,
+			store: Arc::new(MemoryStore::default()),
+			hash_algorithms: HashAlgorithms::default(),
+			rate_limits: CompletionRateLimits::default(),
+			assets: None,
+		}
+	}
+}
+
+/// Relay the transfers between both chains until `shutdown` resolves.
 pub async fn run_bridge<
 	A1: Send + From<Vec<u8>> + std::clone::Clone + 'static + std::fmt::Debug,
 	A2: Send + From<Vec<u8>> + std::clone::Clone + 'static + std::fmt::Debug,
@@ -32,21 +243,72 @@ pub async fn run_bridge<
 	mut one_stream: impl BridgeContractMonitoring<Address = A1>,
 	two_client: impl BridgeContract<A2> + 'static,
 	mut two_stream: impl BridgeContractMonitoring<Address = A2>,
+	options: RelayerOptions,
+	shutdown: impl std::future::Future<Output = ()>,
 ) -> Result<(), anyhow::Error>
 where
 	Vec<u8>: From<A1>,
 	Vec<u8>: From<A2>,
 {
-	let mut state_runtime = Runtime::new();
+	let RelayerOptions {
+		refund_policy,
+		nudge_policy,
+		stuck_policy,
+		degraded_chains,
+		completion_slo,
+		metrics,
+		store,
+		hash_algorithms,
+		rate_limits,
+		assets,
+	} = options;
+	let mut state_runtime = Runtime::new(refund_policy, nudge_policy, stuck_policy)
+		.with_hash_algorithms(hash_algorithms)
+		.with_rate_limits(rate_limits);
+	if let Some(completion_slo) = completion_slo {
+		state_runtime = state_runtime.with_completion_slo(completion_slo);
+	}
+	if let Some(metrics) = metrics {
+		state_runtime = state_runtime.with_metrics(metrics);
+	}
+	if let Some(assets) = assets {
+		state_runtime = state_runtime.with_assets(assets);
+	}
+	let mut balance_guard = BalanceGuard::new(degraded_chains);
+	let mut timeout_check_interval = tokio::time::interval(TIMEOUT_CHECK_INTERVAL);
 
 	let mut client_exec_result_futures_one = FuturesUnordered::new();
 	let mut client_exec_result_futures_two = FuturesUnordered::new();
 
+	// Resume the transfers that were in flight when the relayer stopped.
+	let pending = store.pending().await?;
+	tracing::info!("Resuming {} pending transfers", pending.len());
+	for action in state_runtime.restore(&pending) {
+		execute_action(
+			&mut state_runtime,
+			action,
+			&mut balance_guard,
+			&one_client,
+			&mut client_exec_result_futures_one,
+			&two_client,
+			&mut client_exec_result_futures_two,
+		);
+	}
+
 	// let mut action_to_exec_futures_one = FuturesUnordered::new();
 	// let mut action_to_exec_futures_two = FuturesUnordered::new();
 
+	tokio::pin!(shutdown);
+
 	loop {
 		select! {
+			// Close the monitoring connections before exiting.
+			_ = &mut shutdown => {
+				tracing::info!("Shutdown requested, stopping the event monitoring");
+				one_stream.stop();
+				two_stream.stop();
+				return Ok(());
+			}
 			// Wait on chain one events.
 			Some(one_event_res) = one_stream.next() =>{
 				match one_event_res {
@@ -55,25 +317,17 @@ where
 						tracing::info!("Receive event from chain ONE:{}", event.contract_event.bridge_transfer_id());
 						match state_runtime.process_event(event) {
 							Ok(action) => {
-								//Execute action
-								match action.chain {
-									ChainId::ONE => {
-										let fut = process_action(action, one_client.clone());
-										if let Some(fut) = fut {
-											let jh = tokio::spawn(fut);
-											client_exec_result_futures_one.push(jh);
-										}
-
-									},
-									ChainId::TWO => {
-										let fut = process_action(action, two_client.clone());
-										if let Some(fut) = fut {
-											let jh = tokio::spawn(fut);
-											client_exec_result_futures_two.push(jh);
-										}
-									}
-								}
-							},
+								state_runtime.persist(store.as_ref(), action.transfer_id).await;
+								execute_action(
+									&mut state_runtime,
+									action,
+									&mut balance_guard,
+									&one_client,
+									&mut client_exec_result_futures_one,
+									&two_client,
+									&mut client_exec_result_futures_two,
+								);
+							}
 							Err(err) => tracing::warn!("Received an invalid event: {err}"),
 						}
 					}
@@ -88,31 +342,48 @@ where
 						tracing::info!("Receive event from chain TWO id:{}", event.contract_event.bridge_transfer_id());
 						match state_runtime.process_event(event) {
 							Ok(action) => {
-								//Execute action
-								match action.chain {
-									ChainId::ONE => {
-										let fut = process_action(action, one_client.clone());
-										if let Some(fut) = fut {
-											let jh = tokio::spawn(fut);
-											client_exec_result_futures_one.push(jh);
-										}
-
-									},
-									ChainId::TWO => {
-										let fut = process_action(action, two_client.clone());
-										if let Some(fut) = fut {
-											let jh = tokio::spawn(fut);
-											client_exec_result_futures_two.push(jh);
-										}
-									}
-								}
-							},
+								state_runtime.persist(store.as_ref(), action.transfer_id).await;
+								execute_action(
+									&mut state_runtime,
+									action,
+									&mut balance_guard,
+									&one_client,
+									&mut client_exec_result_futures_one,
+									&two_client,
+									&mut client_exec_result_futures_two,
+								);
+							}
 							Err(err) => tracing::warn!("Received an invalid event: {err}"),
 						}
 					}
 					Err(err) => tracing::error!("Chain two event stream return an error:{err}"),
 				}
 			}
+			// Refund the transfers whose time lock expired, nudge the stalled ones
+			// and alert on the stuck ones and the completion SLO.
+			_ = timeout_check_interval.tick() => {
+				let now = unix_now();
+				state_runtime.process_stuck_transfers(now);
+				state_runtime.check_completion_slo(now);
+				let mut actions = state_runtime.process_expired_transfers(now);
+				actions.extend(state_runtime.process_nudges(now));
+				// Resume the completions of the chains topped up since
+				// and the ones the rate limits have room for.
+				actions.extend(balance_guard.release());
+				actions.extend(state_runtime.completion_throttle.release(now));
+				for action in actions {
+					state_runtime.persist(store.as_ref(), action.transfer_id).await;
+					execute_action(
+						&mut state_runtime,
+						action,
+						&mut balance_guard,
+						&one_client,
+						&mut client_exec_result_futures_one,
+						&two_client,
+						&mut client_exec_result_futures_two,
+					);
+				}
+			}
 			// Wait on client tx execution result.
 			Some(res) = client_exec_result_futures_one.next() => {
 				match res {
@@ -120,9 +391,18 @@ where
 					Ok(Ok(_)) => (),
 					Ok(Err(err)) => {
 						// Manage Tx execution error
-						let action = state_runtime.process_action_exec_error(err);
-						// TODO execute action the same way as normal event.
-						// TODO refactor to avopid code duplication.
+						if let Some(action) = state_runtime.process_action_exec_error(err) {
+							state_runtime.persist(store.as_ref(), action.transfer_id).await;
+							execute_action(
+								&mut state_runtime,
+								action,
+								&mut balance_guard,
+								&one_client,
+								&mut client_exec_result_futures_one,
+								&two_client,
+								&mut client_exec_result_futures_two,
+							);
+						}
 					}
 					Err(err)=>{
 						// Tokio execution fail. Process should exit.
@@ -137,9 +417,18 @@ where
 					Ok(Ok(_)) => (),
 					Ok(Err(err)) => {
 						// Manage Tx execution error
-						let action = state_runtime.process_action_exec_error(err);
-						// TODO execute action the same way as normal event.
-						// TODO refactor to avopid code duplication.
+						if let Some(action) = state_runtime.process_action_exec_error(err) {
+							state_runtime.persist(store.as_ref(), action.transfer_id).await;
+							execute_action(
+								&mut state_runtime,
+								action,
+								&mut balance_guard,
+								&one_client,
+								&mut client_exec_result_futures_one,
+								&two_client,
+								&mut client_exec_result_futures_two,
+							);
+						}
 					}
 					Err(err)=>{
 						// Tokio execution fail. Process should exit.
@@ -152,13 +441,167 @@ where
 	}
 }
 
+type ActionFutures = FuturesUnordered<JoinHandle<Result<(), ActionExecError>>>;
+
+// Spawn the execution of the action on the client of the action's chain,
+// if the canonical ordering allows it.
+fn execute_action<A1, A2>(
+	state_runtime: &mut Runtime,
+	action: TransferAction,
+	balance_guard: &mut BalanceGuard,
+	one_client: &(impl BridgeContract<A1> + 'static),
+	one_futures: &mut ActionFutures,
+	two_client: &(impl BridgeContract<A2> + 'static),
+	two_futures: &mut ActionFutures,
+) where
+	A1: Clone + Send + From<Vec<u8>>,
+	A2: Clone + Send + From<Vec<u8>>,
+{
+	let now = unix_now();
+	let Some(action) = state_runtime.authorize(action, now) else {
+		return;
+	};
+	let Some(action) = balance_guard.hold(action) else {
+		return;
+	};
+	let Some(action) = state_runtime.completion_throttle.hold(action, now) else {
+		return;
+	};
+	match action.chain {
+		ChainId::ONE => {
+			if let Some(fut) = process_action(action, one_client.clone()) {
+				one_futures.push(tokio::spawn(fut));
+			}
+		}
+		ChainId::TWO => {
+			if let Some(fut) = process_action(action, two_client.clone()) {
+				two_futures.push(tokio::spawn(fut));
+			}
+		}
+	}
+}
+
 struct Runtime {
 	swap_state_map: HashMap<BridgeTransferId, TransferState>,
+	refund_policy: RefundPolicy,
+	nudge_policy: Option<NudgePolicy>,
+	stuck_policy: StuckPolicy,
+	completion_slo: Option<CompletionSlo>,
+	metrics: Option<RelayerMetrics>,
+	hash_algorithms: HashAlgorithms,
+	assets: Option<ChainAssets>,
+	completion_throttle: CompletionThrottle,
 }
 
 impl Runtime {
-	pub fn new() -> Self {
-		Runtime { swap_state_map: HashMap::new() }
+	pub fn new(
+		refund_policy: RefundPolicy,
+		nudge_policy: Option<NudgePolicy>,
+		stuck_policy: StuckPolicy,
+	) -> Self {
+		Runtime {
+			swap_state_map: HashMap::new(),
+			refund_policy,
+			nudge_policy,
+			stuck_policy,
+			completion_slo: None,
+			metrics: None,
+			hash_algorithms: HashAlgorithms::default(),
+			assets: None,
+			completion_throttle: CompletionThrottle::new(CompletionRateLimits::default()),
+		}
+	}
+
+	/// Reload the persisted pending transfers, returns the chain actions to send again.
+	fn restore(&mut self, records: &[TransferRecord]) -> Vec<TransferAction> {
+		let mut actions = vec![];
+		for record in records {
+			let mut state = TransferState::from_record(record);
+			state.locked_amount = self.locked_amount(&state);
+			actions.extend(state.resume_action());
+			self.swap_state_map.insert(state.transfer_id, state);
+		}
+		actions
+	}
+
+	// Drop the actions the canonical ordering forbids and the locks the counterparty can't verify.
+	fn authorize(&self, action: TransferAction, now: u64) -> Option<TransferAction> {
+		if let TransferActionType::NoAction = action.kind {
+			return Some(action);
+		}
+		let Some(state) = self.swap_state_map.get(&action.transfer_id) else {
+			tracing::error!("{action} dropped, its transfer is unknown");
+			return None;
+		};
+		// The recipient could never complete a lock hashed differently by the counterparty.
+		if let TransferActionType::LockBridgeTransfer { .. } = action.kind {
+			let counterparty_algorithm = self.hash_algorithms.of(action.chain);
+			if counterparty_algorithm != state.hash_algorithm {
+				tracing::error!(
+					"{action} dropped, the hash lock uses {} but the chain {} contracts use {counterparty_algorithm}",
+					state.hash_algorithm,
+					action.chain
+				);
+				return None;
+			}
+		}
+		match check_action(state, &action.kind, now) {
+			Ok(()) => Some(action),
+			Err(err) => {
+				tracing::error!("Illegal {action} dropped: {err}");
+				None
+			}
+		}
+	}
+
+	// Save the transfer before its action is sent, and after each event or failed action
+	// that updates it.
+	async fn persist(&self, store: &dyn TransferStore, transfer_id: BridgeTransferId) {
+		let Some(state) = self.swap_state_map.get(&transfer_id) else {
+			return;
+		};
+		if let Err(err) = store.save(&state.to_record()).await {
+			tracing::error!("Failed to persist transfer:{transfer_id}:{err}");
+		}
+	}
+
+	/// Hash the locks of the transfers with the hash function of their initiating chain.
+	pub fn with_hash_algorithms(mut self, hash_algorithms: HashAlgorithms) -> Self {
+		self.hash_algorithms = hash_algorithms;
+		self
+	}
+
+	/// Expect the counterparty locks in the asset of their chain.
+	pub fn with_assets(mut self, assets: ChainAssets) -> Self {
+		self.assets = Some(assets);
+		self
+	}
+
+	// Amount the counterparty lock of `state` must carry, the initiated one if the chain
+	// assets aren't known.
+	fn locked_amount(&self, state: &TransferState) -> Amount {
+		match self.assets {
+			Some(assets) => assets.of(state.init_chain.other()).amount(state.amount.value()),
+			None => state.amount,
+		}
+	}
+
+	/// Cap the completions submitted per chain, the ones over the limits are queued.
+	pub fn with_rate_limits(mut self, rate_limits: CompletionRateLimits) -> Self {
+		self.completion_throttle = CompletionThrottle::new(rate_limits);
+		self
+	}
+
+	/// Track the lock to completion durations of the transfers against `completion_slo`.
+	pub fn with_completion_slo(mut self, completion_slo: CompletionSlo) -> Self {
+		self.completion_slo = Some(completion_slo);
+		self
+	}
+
+	/// Count the completed and refunded transfers in `metrics`.
+	pub fn with_metrics(mut self, metrics: RelayerMetrics) -> Self {
+		self.metrics = Some(metrics);
+		self
 	}
 
 	pub fn process_event<A>(
@@ -169,12 +612,15 @@ impl Runtime {
 		A: Into<Vec<u8>> + std::clone::Clone,
 	{
 		self.validate_state(&event)?;
+		self.check_transition(&event)?;
 		let event_transfer_id = event.contract_event.bridge_transfer_id();
 		let state_opt = self.swap_state_map.remove(&event_transfer_id);
 		//create swap state if need
 		let mut state = if let BridgeContractEvent::Initiated(detail) = event.contract_event {
-			let (state, mut action) =
+			let (mut state, mut action) =
 				TransferState::transition_from_initiated(event.chain, event_transfer_id, detail);
+			state.hash_algorithm = self.hash_algorithms.of(state.init_chain);
+			state.locked_amount = self.locked_amount(&state);
 			action.chain = state.init_chain.other();
 			self.swap_state_map.insert(state.transfer_id, state);
 			return Ok(action);
@@ -189,29 +635,49 @@ impl Runtime {
 				let (new_state, action_kind) =
 					state.transition_from_locked_done(event_transfer_id, detail);
 				state = new_state;
+				state.locked_at = Some(unix_now());
 				(action_kind, state.init_chain)
 			}
-			BridgeContractEvent::CounterPartCompleted(_, preimage) => {
+			BridgeContractEvent::CounterPartCompleted(_, preimage, _) => {
 				let (new_state, action_kind) =
 					state.transition_from_counterpart_completed(event_transfer_id, preimage);
 				state = new_state;
+				state.nudge_at = self.nudge_policy.map(|policy| unix_now() + policy.delay_secs);
 				(action_kind, state.init_chain)
 			}
-			BridgeContractEvent::InitialtorCompleted(_) => {
+			BridgeContractEvent::InitialtorCompleted(..) => {
 				let (new_state, action_kind) =
 					state.transition_from_initiator_completed(event_transfer_id);
 				state = new_state;
 				//transfer done remove the state.
 				if state.state == TransferStateType::Done {
 					self.swap_state_map.remove(&event_transfer_id);
+					if let (Some(slo), Some(locked_at)) =
+						(&mut self.completion_slo, state.locked_at)
+					{
+						let now = unix_now();
+						slo.record(now.saturating_sub(locked_at), now);
+					}
+					if let Some(metrics) = &self.metrics {
+						metrics.transfer_completed(unix_now().saturating_sub(state.initiated_at));
+					}
 				}
 				(action_kind, state.init_chain)
 			}
-			BridgeContractEvent::Cancelled(_) => {
-				todo!()
+			BridgeContractEvent::Cancelled(..) => {
+				let auto_refund = self.refund_policy.auto_refund(state.init_chain);
+				let (new_state, action_kind) =
+					state.transition_from_cancelled(event_transfer_id, unix_now(), auto_refund);
+				state = new_state;
+				(action_kind, state.init_chain)
 			}
-			BridgeContractEvent::Refunded(_) => {
-				todo!()
+			BridgeContractEvent::Refunded(..) => {
+				let (new_state, action_kind) = state.transition_from_refunded(event_transfer_id);
+				state = new_state;
+				if let Some(metrics) = &self.metrics {
+					metrics.transfer_refunded();
+				}
+				(action_kind, state.init_chain)
 			}
 		};
 
@@ -223,7 +689,122 @@ impl Runtime {
 		Ok(action)
 	}
 
-	fn validate_state<A>(&mut self, event: &TransferEvent<A>) -> Result<(), InvalidEventError> {
+	// Refund the expired transfers or, if auto refund is disabled for their direction,
+	// flag them for a manual refund.
+	fn process_expired_transfers(&mut self, now: u64) -> Vec<TransferAction> {
+		let mut actions = vec![];
+		for state in self.swap_state_map.values_mut().filter(|state| state.is_expired(now)) {
+			if self.refund_policy.auto_refund(state.init_chain) {
+				tracing::info!(
+					"Transfer:{} time lock expired, refunding initiator",
+					state.transfer_id
+				);
+				let (new_state_type, action_kind) = state.transition_to_refund();
+				state.state = new_state_type;
+				actions.push(TransferAction {
+					chain: state.init_chain,
+					transfer_id: state.transfer_id,
+					kind: action_kind,
+				});
+			} else {
+				tracing::error!(
+					"Transfer:{} initiated on chain {} expired and auto refund is disabled, manual refund required",
+					state.transfer_id,
+					state.init_chain
+				);
+				state.state = TransferStateType::RefundRequired;
+			}
+		}
+		actions
+	}
+
+	// Re-submit the initiator completions whose event didn't come in time.
+	fn process_nudges(&mut self, now: u64) -> Vec<TransferAction> {
+		let Some(policy) = self.nudge_policy else {
+			return vec![];
+		};
+		let mut actions = vec![];
+		for state in self.swap_state_map.values_mut() {
+			let (TransferStateType::SecretReceived, Some(nudge_at), Some(secret)) =
+				(state.state, state.nudge_at, state.secret)
+			else {
+				continue;
+			};
+			if nudge_at > now {
+				continue;
+			}
+			if state.nudge_attempts >= policy.max_attempts {
+				tracing::warn!(
+					"Transfer:{} completion still not observed after {} nudges",
+					state.transfer_id,
+					state.nudge_attempts
+				);
+				state.nudge_at = None;
+				continue;
+			}
+			state.nudge_attempts += 1;
+			state.nudge_at = Some(now + policy.delay_secs);
+			tracing::info!(
+				"Transfer:{} completion not observed, nudge {}/{}",
+				state.transfer_id,
+				state.nudge_attempts,
+				policy.max_attempts
+			);
+			actions.push(TransferAction {
+				chain: state.init_chain,
+				transfer_id: state.transfer_id,
+				kind: TransferActionType::NudgeCompleteInitiator(secret),
+			});
+		}
+		actions
+	}
+
+	fn check_completion_slo(&mut self, now: u64) {
+		if let Some(slo) = &mut self.completion_slo {
+			slo.check(now);
+		}
+	}
+
+	// Alert once on the transfers still not completed after the grace window of their direction.
+	fn process_stuck_transfers(&mut self, now: u64) -> Vec<BridgeTransferId> {
+		let mut stuck = vec![];
+		for state in self.swap_state_map.values_mut() {
+			if state.stuck || !state.is_stuck(now, self.stuck_policy.grace_secs(state.init_chain)) {
+				continue;
+			}
+			tracing::error!(
+				"Transfer:{} initiated on chain {} is stuck in state {:?} after {}s",
+				state.transfer_id,
+				state.init_chain,
+				state.state,
+				now - state.initiated_at
+			);
+			state.stuck = true;
+			stuck.push(state.transfer_id);
+		}
+		stuck
+	}
+
+	// Defensive guard on top of the event validation: never move a transfer through
+	// a transition the state machine doesn't allow.
+	fn check_transition<A>(&self, event: &TransferEvent<A>) -> Result<(), InvalidEventError> {
+		let event_transfer_id = event.contract_event.bridge_transfer_id();
+		let (Some(state), Some(next)) = (
+			self.swap_state_map.get(&event_transfer_id),
+			TransferStateType::after_event(&event.contract_event),
+		) else {
+			return Ok(());
+		};
+		state.state.check_transition(next).map_err(|err| {
+			tracing::error!("Transfer:{event_transfer_id} rejected {} event: {err}", event.chain);
+			err
+		})
+	}
+
+	fn validate_state<A: Into<Vec<u8>> + Clone>(
+		&mut self,
+		event: &TransferEvent<A>,
+	) -> Result<(), InvalidEventError> {
 		let event_transfer_id = event.contract_event.bridge_transfer_id();
 		let swap_state_opt = self.swap_state_map.get(&event_transfer_id);
 		//validate the associated swap_state.
@@ -239,7 +820,17 @@ impl Runtime {
 						.ok_or(InvalidEventError::StateNotFound),
 				)
 			})
-			.transpose()?;
+			.transpose()
+			.map_err(|err| {
+				if let InvalidEventError::TransferIdCollision = err {
+					tracing::error!(
+						"Security alert: rejected {} event {}, its id collides with an existing transfer",
+						event.chain,
+						event.contract_event.bridge_transfer_id()
+					);
+				}
+				err
+			})?;
 		Ok(())
 	}
 
@@ -247,28 +838,33 @@ impl Runtime {
 		// Manage Tx execution error
 		let (action, err) = action_err.inner();
 		tracing::warn!("Client execution error for action:{action:?} err:{err:?}");
-		// retry 5 time an action in error then abort.
+		// retry 5 time an action on a transient error then abort, abort directly on the others.
 		match self.swap_state_map.get_mut(&action.transfer_id) {
 			Some(state) => {
 				state.retry_on_error += 1;
-				if state.retry_on_error > 5 {
+				if !err.is_retryable() || state.retry_on_error > 5 {
 					// Depending on the action cancel transfer
 					match action.kind {
 						TransferActionType::LockBridgeTransfer { .. } => {
-							//Lock fail. Refund initiator
-							let (new_state_type, action_kind) = state.transition_to_refund();
-							state.state = new_state_type;
-							let action = TransferAction {
-								chain: state.init_chain,
-								transfer_id: state.transfer_id,
-								kind: action_kind,
-							};
-							Some(action)
+							// The lock never succeeded: the initiator is refunded once its time
+							// lock expires, a refund is rejected by the contract before.
+							tracing::error!(
+								"Lock failed for transfer:{} err:{err}, refunded at its time lock expiry",
+								state.transfer_id
+							);
+							None
 						}
 						TransferActionType::WaitAndCompleteInitiator(..) => {
-							todo!()
+							// The completion never succeeded: the transfer stays pending
+							// with its secret so it can be reconciled or refunded.
+							tracing::error!(
+								"Initiator completion failed for transfer:{} err:{err}, transfer kept pending",
+								state.transfer_id
+							);
+							None
 						}
-						TransferActionType::RefundInitiator => None, //will wait automatic refund
+						TransferActionType::NudgeCompleteInitiator(..) => None, //will be nudged again
+						TransferActionType::RefundInitiator => None,            //will wait automatic refund
 						TransferActionType::TransferDone => None,
 						TransferActionType::NoAction => None,
 					}
@@ -287,3 +883,760 @@ impl Runtime {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::chains::bridge_contracts::BridgeContractError;
+	use crate::chains::ethereum::types::EthAddress;
+	use crate::slo::SloPolicy;
+	use crate::store::MemoryStore;
+	use crate::types::{
+		Amount, AssetTag, AssetType, BridgeAddress, BridgeTransferDetails, HashLock,
+		HashLockPreImage, LockDetails, TimeLock,
+	};
+	use prometheus::Registry;
+
+	// Keccak256 hash lock of the secret revealed in the tests.
+	fn hash_lock() -> HashLock {
+		HashLockPreImage([4; 32]).hash_lock(HashAlgorithm::Keccak256)
+	}
+
+	fn initiated_event(transfer_id: BridgeTransferId) -> TransferEvent<Vec<u8>> {
+		let details = BridgeTransferDetails {
+			bridge_transfer_id: transfer_id,
+			initiator_address: BridgeAddress(vec![1; 20]),
+			recipient_address: BridgeAddress(vec![2; 32]),
+			hash_lock: hash_lock(),
+			time_lock: TimeLock(100),
+			amount: Amount(AssetType::Moveth(10)),
+			state: 1,
+		};
+		(BridgeContractEvent::Initiated(details), ChainId::ONE).into()
+	}
+
+	fn locked_event(transfer_id: BridgeTransferId) -> TransferEvent<Vec<u8>> {
+		let details = LockDetails {
+			bridge_transfer_id: transfer_id,
+			initiator_address: BridgeAddress(vec![1; 20]),
+			recipient_address: BridgeAddress(vec![2; 32]),
+			hash_lock: hash_lock(),
+			time_lock: TimeLock(100),
+			amount: Amount(AssetType::Moveth(10)),
+		};
+		(BridgeContractEvent::Locked(details), ChainId::TWO).into()
+	}
+
+	// Drive a transfer up to the point where the initiator completion is sent.
+	fn runtime_with_secret_received(
+		transfer_id: BridgeTransferId,
+		nudge_policy: Option<NudgePolicy>,
+	) -> (Runtime, TransferAction) {
+		let mut runtime =
+			Runtime::new(RefundPolicy::default(), nudge_policy, StuckPolicy::default());
+		runtime.process_event(initiated_event(transfer_id)).unwrap();
+		runtime.process_event(locked_event(transfer_id)).unwrap();
+		let completed: TransferEvent<Vec<u8>> = (
+			BridgeContractEvent::CounterPartCompleted(
+				transfer_id,
+				HashLockPreImage([4; 32]),
+				AssetTag::Moveth,
+			),
+			ChainId::TWO,
+		)
+			.into();
+		let action = runtime.process_event(completed).unwrap();
+		assert!(matches!(action.kind, TransferActionType::WaitAndCompleteInitiator(..)));
+		(runtime, action)
+	}
+
+	#[test]
+	fn test_expired_transfer_is_refunded() {
+		let transfer_id = BridgeTransferId([8; 32]);
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default());
+		runtime.process_event(initiated_event(transfer_id)).unwrap();
+
+		// Time lock not reached yet.
+		assert!(runtime.process_expired_transfers(99).is_empty());
+
+		let actions = runtime.process_expired_transfers(100);
+		assert_eq!(actions.len(), 1);
+		assert_eq!(actions[0].chain, ChainId::ONE);
+		assert!(matches!(actions[0].kind, TransferActionType::RefundInitiator));
+		assert_eq!(
+			runtime.swap_state_map.get(&transfer_id).unwrap().state,
+			TransferStateType::Refund
+		);
+		// The refund is only sent once.
+		assert!(runtime.process_expired_transfers(200).is_empty());
+
+		let refunded: TransferEvent<Vec<u8>> =
+			(BridgeContractEvent::Refunded(transfer_id, AssetTag::Moveth), ChainId::ONE).into();
+		assert!(matches!(
+			runtime.process_event(refunded).unwrap().kind,
+			TransferActionType::NoAction
+		));
+	}
+
+	#[test]
+	fn test_expired_transfer_requires_manual_refund_when_disabled() {
+		let transfer_id = BridgeTransferId([9; 32]);
+		let mut runtime = Runtime::new(
+			RefundPolicy { auto_refund_one: false, auto_refund_two: true },
+			None,
+			StuckPolicy::default(),
+		);
+		runtime.process_event(initiated_event(transfer_id)).unwrap();
+
+		assert!(runtime.process_expired_transfers(100).is_empty());
+		assert_eq!(
+			runtime.swap_state_map.get(&transfer_id).unwrap().state,
+			TransferStateType::RefundRequired
+		);
+
+		// The operator refund is still tracked.
+		let refunded: TransferEvent<Vec<u8>> =
+			(BridgeContractEvent::Refunded(transfer_id, AssetTag::Moveth), ChainId::ONE).into();
+		runtime.process_event(refunded).unwrap();
+		assert_eq!(
+			runtime.swap_state_map.get(&transfer_id).unwrap().state,
+			TransferStateType::Refund
+		);
+	}
+
+	#[tokio::test]
+	async fn test_transfer_locked_before_a_crash_completes_after_restart() {
+		let transfer_id = BridgeTransferId([5; 32]);
+		let store = MemoryStore::default();
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default());
+		runtime.process_event(initiated_event(transfer_id)).unwrap();
+		runtime.persist(&store, transfer_id).await;
+		runtime.process_event(locked_event(transfer_id)).unwrap();
+		runtime.persist(&store, transfer_id).await;
+		// The relayer dies with the transfer locked.
+		drop(runtime);
+
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default());
+		// Nothing to send again while waiting for the counterparty completion.
+		assert!(runtime.restore(&store.pending().await.unwrap()).is_empty());
+		let completed: TransferEvent<Vec<u8>> = (
+			BridgeContractEvent::CounterPartCompleted(
+				transfer_id,
+				HashLockPreImage([4; 32]),
+				AssetTag::Moveth,
+			),
+			ChainId::TWO,
+		)
+			.into();
+		let action = runtime.process_event(completed).unwrap();
+		assert!(matches!(
+			action.kind,
+			TransferActionType::WaitAndCompleteInitiator(_, secret) if secret.0 == [4; 32]
+		));
+		assert_eq!(action.chain, ChainId::ONE);
+	}
+
+	#[tokio::test]
+	async fn test_restart_sends_the_interrupted_actions_again() {
+		let (initialized, secret_received) = (BridgeTransferId([3; 32]), BridgeTransferId([4; 32]));
+		let store = MemoryStore::default();
+		let (runtime, _) = runtime_with_secret_received(secret_received, None);
+		runtime.persist(&store, secret_received).await;
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default());
+		runtime.process_event(initiated_event(initialized)).unwrap();
+		runtime.persist(&store, initialized).await;
+
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default());
+		let mut actions = runtime.restore(&store.pending().await.unwrap());
+		actions.sort_by_key(|action| action.transfer_id.0);
+		assert_eq!(actions.len(), 2);
+		assert_eq!(actions[0].chain, ChainId::TWO);
+		assert!(matches!(actions[0].kind, TransferActionType::LockBridgeTransfer { .. }));
+		assert_eq!(actions[1].chain, ChainId::ONE);
+		assert!(matches!(
+			actions[1].kind,
+			TransferActionType::WaitAndCompleteInitiator(0, secret) if secret.0 == [4; 32]
+		));
+	}
+
+	#[test]
+	fn test_completed_transfer_is_tracked_against_the_slo() {
+		let transfer_id = BridgeTransferId([6; 32]);
+		let policy = SloPolicy { target_secs: 600, objective_percent: 95.0, window_secs: 3600 };
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default())
+			.with_completion_slo(CompletionSlo::register(&Registry::new(), policy).unwrap());
+		runtime.process_event(initiated_event(transfer_id)).unwrap();
+		runtime.process_event(locked_event(transfer_id)).unwrap();
+		// Locked 15 minutes ago.
+		let locked_at = runtime.swap_state_map.get(&transfer_id).unwrap().locked_at.unwrap();
+		runtime.swap_state_map.get_mut(&transfer_id).unwrap().locked_at = Some(locked_at - 900);
+		let completed: TransferEvent<Vec<u8>> = (
+			BridgeContractEvent::CounterPartCompleted(
+				transfer_id,
+				HashLockPreImage([4; 32]),
+				AssetTag::Moveth,
+			),
+			ChainId::TWO,
+		)
+			.into();
+		runtime.process_event(completed).unwrap();
+		let completed: TransferEvent<Vec<u8>> =
+			(BridgeContractEvent::InitialtorCompleted(transfer_id, AssetTag::Moveth), ChainId::ONE)
+				.into();
+		runtime.process_event(completed).unwrap();
+
+		let slo = runtime.completion_slo.as_mut().unwrap();
+		assert_eq!(slo.compliance(unix_now()), Some(0.0));
+		assert!(slo.check(unix_now()));
+	}
+
+	#[test]
+	fn test_completed_and_refunded_transfers_are_counted() {
+		let registry = Registry::new();
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default())
+			.with_metrics(RelayerMetrics::register(&registry).unwrap());
+		let transfer_id = BridgeTransferId([10; 32]);
+		runtime.process_event(initiated_event(transfer_id)).unwrap();
+		runtime.process_event(locked_event(transfer_id)).unwrap();
+		let completed: TransferEvent<Vec<u8>> = (
+			BridgeContractEvent::CounterPartCompleted(
+				transfer_id,
+				HashLockPreImage([4; 32]),
+				AssetTag::Moveth,
+			),
+			ChainId::TWO,
+		)
+			.into();
+		runtime.process_event(completed).unwrap();
+		let completed: TransferEvent<Vec<u8>> =
+			(BridgeContractEvent::InitialtorCompleted(transfer_id, AssetTag::Moveth), ChainId::ONE)
+				.into();
+		runtime.process_event(completed).unwrap();
+
+		let transfer_id = BridgeTransferId([11; 32]);
+		runtime.process_event(initiated_event(transfer_id)).unwrap();
+		runtime.process_expired_transfers(100);
+		let refunded: TransferEvent<Vec<u8>> =
+			(BridgeContractEvent::Refunded(transfer_id, AssetTag::Moveth), ChainId::ONE).into();
+		runtime.process_event(refunded).unwrap();
+
+		let counter = |name: &str| {
+			let families = registry.gather();
+			let family = families.iter().find(|family| family.get_name() == name).unwrap();
+			family.get_metric()[0].get_counter().get_value()
+		};
+		assert_eq!(counter("bridge_transfers_completed_total"), 1.0);
+		assert_eq!(counter("bridge_transfers_refunded_total"), 1.0);
+	}
+
+	#[test]
+	fn test_colliding_transfer_id_is_rejected() {
+		let transfer_id = BridgeTransferId([7; 32]);
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default());
+		runtime.process_event(initiated_event(transfer_id)).unwrap();
+
+		// Same id locked with another hash lock.
+		let mut colliding_lock = locked_event(transfer_id);
+		if let BridgeContractEvent::Locked(details) = &mut colliding_lock.contract_event {
+			details.hash_lock = HashLock([9; 32]);
+		}
+		assert!(matches!(
+			runtime.process_event(colliding_lock),
+			Err(InvalidEventError::TransferIdCollision)
+		));
+
+		// Same id initiated with another amount.
+		let mut colliding_init = initiated_event(transfer_id);
+		if let BridgeContractEvent::Initiated(details) = &mut colliding_init.contract_event {
+			details.amount = Amount(AssetType::Moveth(1_000_000));
+		}
+		assert!(matches!(
+			runtime.process_event(colliding_init),
+			Err(InvalidEventError::TransferIdCollision)
+		));
+
+		// A duplicated delivery of the same initiation isn't a collision.
+		assert!(matches!(
+			runtime.process_event(initiated_event(transfer_id)),
+			Err(InvalidEventError::InitAnAlreadyExist)
+		));
+
+		// The existing transfer is untouched and still accepts its own lock.
+		let state = runtime.swap_state_map.get(&transfer_id).unwrap();
+		assert_eq!(state.state, TransferStateType::Initialized);
+		assert_eq!(state.hash_lock, hash_lock());
+		assert!(runtime.process_event(locked_event(transfer_id)).is_ok());
+	}
+
+	// A lock with the id of the initiated transfer but `mutate`d details must be rejected.
+	fn assert_lock_collides(mutate: impl FnOnce(&mut LockDetails<Vec<u8>>)) {
+		let transfer_id = BridgeTransferId([15; 32]);
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default());
+		runtime.process_event(initiated_event(transfer_id)).unwrap();
+		let mut colliding_lock = locked_event(transfer_id);
+		if let BridgeContractEvent::Locked(details) = &mut colliding_lock.contract_event {
+			mutate(details);
+		}
+		assert!(matches!(
+			runtime.process_event(colliding_lock),
+			Err(InvalidEventError::TransferIdCollision)
+		));
+		let state = runtime.swap_state_map.get(&transfer_id).unwrap();
+		assert_eq!(state.state, TransferStateType::Initialized);
+	}
+
+	#[test]
+	fn test_lock_with_another_value_is_rejected() {
+		assert_lock_collides(|details| details.amount = Amount(AssetType::Moveth(11)));
+	}
+
+	#[test]
+	fn test_lock_with_another_asset_is_rejected() {
+		assert_lock_collides(|details| details.amount = AssetTag::EthAndWeth.amount(10));
+	}
+
+	#[test]
+	fn test_lock_with_another_initiator_is_rejected() {
+		assert_lock_collides(|details| details.initiator_address = BridgeAddress(vec![3; 20]));
+	}
+
+	#[test]
+	fn test_lock_with_another_recipient_is_rejected() {
+		assert_lock_collides(|details| details.recipient_address = BridgeAddress(vec![3; 32]));
+	}
+
+	#[test]
+	fn test_lock_is_expected_in_the_counterparty_asset() {
+		let token_address = EthAddress(alloy::primitives::Address::repeat_byte(0xee));
+		let other_token_address = EthAddress(alloy::primitives::Address::repeat_byte(0xdd));
+		let assets = ChainAssets { one: AssetTag::Erc20(token_address), two: AssetTag::Moveth };
+		// A Movement to Eth transfer, locked on Eth in the bridged token.
+		let initiated_on_two = |transfer_id| {
+			let mut event = initiated_event(transfer_id);
+			event.chain = ChainId::TWO;
+			event
+		};
+		let locked_on_one = |transfer_id, token_address| {
+			let mut event = locked_event(transfer_id);
+			event.chain = ChainId::ONE;
+			if let BridgeContractEvent::Locked(details) = &mut event.contract_event {
+				details.amount = AssetTag::Erc20(token_address).amount(10);
+			}
+			event
+		};
+
+		let transfer_id = BridgeTransferId([16; 32]);
+		let mut runtime =
+			Runtime::new(RefundPolicy::default(), None, StuckPolicy::default()).with_assets(assets);
+		runtime.process_event(initiated_on_two(transfer_id)).unwrap();
+		assert!(matches!(
+			runtime.process_event(locked_on_one(transfer_id, other_token_address)),
+			Err(InvalidEventError::TransferIdCollision)
+		));
+		assert!(runtime.process_event(locked_on_one(transfer_id, token_address)).is_ok());
+
+		// A restarted relayer expects the same lock.
+		let transfer_id = BridgeTransferId([17; 32]);
+		let mut runtime =
+			Runtime::new(RefundPolicy::default(), None, StuckPolicy::default()).with_assets(assets);
+		runtime.process_event(initiated_on_two(transfer_id)).unwrap();
+		let record = runtime.swap_state_map.get(&transfer_id).unwrap().to_record();
+		let mut restarted =
+			Runtime::new(RefundPolicy::default(), None, StuckPolicy::default()).with_assets(assets);
+		restarted.restore(&[record]);
+		assert!(restarted.process_event(locked_on_one(transfer_id, token_address)).is_ok());
+	}
+
+	fn cancelled(transfer_id: BridgeTransferId, chain: ChainId) -> TransferEvent<Vec<u8>> {
+		(BridgeContractEvent::Cancelled(transfer_id, AssetTag::Moveth), chain).into()
+	}
+
+	#[test]
+	fn test_aborted_lock_refunds_the_expired_transfer() {
+		let transfer_id = BridgeTransferId([18; 32]);
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default());
+		runtime.process_event(initiated_event(transfer_id)).unwrap();
+		runtime.process_event(locked_event(transfer_id)).unwrap();
+
+		// The abort happens on the counterparty chain.
+		assert!(matches!(
+			runtime.process_event(cancelled(transfer_id, ChainId::ONE)),
+			Err(InvalidEventError::BadChain)
+		));
+		let action = runtime.process_event(cancelled(transfer_id, ChainId::TWO)).unwrap();
+		assert_eq!(action.chain, ChainId::ONE);
+		assert!(matches!(action.kind, TransferActionType::RefundInitiator));
+		assert_eq!(
+			runtime.swap_state_map.get(&transfer_id).unwrap().state,
+			TransferStateType::Refund
+		);
+		assert!(runtime.authorize(action, 100).is_some());
+
+		// The refunded event closes it.
+		let refunded: TransferEvent<Vec<u8>> =
+			(BridgeContractEvent::Refunded(transfer_id, AssetTag::Moveth), ChainId::ONE).into();
+		runtime.process_event(refunded).unwrap();
+	}
+
+	#[test]
+	fn test_aborted_lock_requires_manual_refund_when_disabled() {
+		let transfer_id = BridgeTransferId([19; 32]);
+		let refund_policy = RefundPolicy { auto_refund_one: false, auto_refund_two: true };
+		let mut runtime = Runtime::new(refund_policy, None, StuckPolicy::default());
+		runtime.process_event(initiated_event(transfer_id)).unwrap();
+		runtime.process_event(locked_event(transfer_id)).unwrap();
+
+		let action = runtime.process_event(cancelled(transfer_id, ChainId::TWO)).unwrap();
+		assert!(matches!(action.kind, TransferActionType::NoAction));
+		assert_eq!(
+			runtime.swap_state_map.get(&transfer_id).unwrap().state,
+			TransferStateType::RefundRequired
+		);
+	}
+
+	#[test]
+	fn test_aborted_lock_waits_for_the_time_lock() {
+		let transfer_id = BridgeTransferId([20; 32]);
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default());
+		let mut initiated = initiated_event(transfer_id);
+		if let BridgeContractEvent::Initiated(details) = &mut initiated.contract_event {
+			details.time_lock = TimeLock(u64::MAX);
+		}
+
+		// Not locked yet, there is nothing to abort.
+		runtime.process_event(initiated).unwrap();
+		assert!(matches!(
+			runtime.process_event(cancelled(transfer_id, ChainId::TWO)),
+			Err(InvalidEventError::BadEvent)
+		));
+
+		runtime.process_event(locked_event(transfer_id)).unwrap();
+		let action = runtime.process_event(cancelled(transfer_id, ChainId::TWO)).unwrap();
+		assert!(matches!(action.kind, TransferActionType::NoAction));
+		assert_eq!(
+			runtime.swap_state_map.get(&transfer_id).unwrap().state,
+			TransferStateType::Locked
+		);
+	}
+
+	#[test]
+	fn test_secret_not_matching_the_hash_lock_is_rejected() {
+		let transfer_id = BridgeTransferId([10; 32]);
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default());
+		runtime.process_event(initiated_event(transfer_id)).unwrap();
+		runtime.process_event(locked_event(transfer_id)).unwrap();
+
+		let wrong_secret: TransferEvent<Vec<u8>> = (
+			BridgeContractEvent::CounterPartCompleted(
+				transfer_id,
+				HashLockPreImage([5; 32]),
+				AssetTag::Moveth,
+			),
+			ChainId::TWO,
+		)
+			.into();
+		assert!(matches!(
+			runtime.process_event(wrong_secret),
+			Err(InvalidEventError::InvalidSecret(HashAlgorithm::Keccak256))
+		));
+		let state = runtime.swap_state_map.get(&transfer_id).unwrap();
+		assert_eq!(state.state, TransferStateType::Locked);
+		assert_eq!(state.secret, None);
+
+		let action = runtime.process_event(counterpart_completed(transfer_id)).unwrap();
+		assert!(matches!(action.kind, TransferActionType::WaitAndCompleteInitiator(..)));
+	}
+
+	#[test]
+	fn test_sha256_hash_algorithm_is_rejected() {
+		let hash_algorithms = HashAlgorithms::from_config("keccak256", "keccak256").unwrap();
+		assert_eq!(hash_algorithms, HashAlgorithms::default());
+		assert!(HashAlgorithms::from_config("sha256", "keccak256").is_err());
+		assert!(HashAlgorithms::from_config("keccak256", "sha256").is_err());
+		assert!(HashAlgorithms::from_config("md5", "keccak256").is_err());
+	}
+
+	#[test]
+	fn test_transfer_uses_the_hash_algorithm_of_its_init_chain() {
+		let sha256_lock = HashLockPreImage([4; 32]).hash_lock(HashAlgorithm::Sha256);
+		let sha256_initiated = |transfer_id| {
+			let mut event = initiated_event(transfer_id);
+			if let BridgeContractEvent::Initiated(details) = &mut event.contract_event {
+				details.hash_lock = sha256_lock;
+			}
+			event
+		};
+
+		// Both chains hash with sha256: the transfer is locked and completed with it.
+		let transfer_id = BridgeTransferId([11; 32]);
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default())
+			.with_hash_algorithms(HashAlgorithms {
+				one: HashAlgorithm::Sha256,
+				two: HashAlgorithm::Sha256,
+			});
+		let lock = runtime.process_event(sha256_initiated(transfer_id)).unwrap();
+		assert!(runtime.authorize(lock, 50).is_some());
+		assert_eq!(
+			runtime.swap_state_map.get(&transfer_id).unwrap().hash_algorithm,
+			HashAlgorithm::Sha256
+		);
+		let mut locked = locked_event(transfer_id);
+		if let BridgeContractEvent::Locked(details) = &mut locked.contract_event {
+			details.hash_lock = sha256_lock;
+		}
+		runtime.process_event(locked).unwrap();
+		let complete = runtime.process_event(counterpart_completed(transfer_id)).unwrap();
+		assert!(runtime.authorize(complete, 60).is_some());
+
+		// The counterparty hashes with keccak256: the lock isn't sent, the transfer waits
+		// for its refund.
+		let transfer_id = BridgeTransferId([12; 32]);
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default())
+			.with_hash_algorithms(HashAlgorithms {
+				one: HashAlgorithm::Sha256,
+				two: HashAlgorithm::Keccak256,
+			});
+		let lock = runtime.process_event(sha256_initiated(transfer_id)).unwrap();
+		assert!(matches!(lock.kind, TransferActionType::LockBridgeTransfer { .. }));
+		assert!(runtime.authorize(lock, 50).is_none());
+		let refunds = runtime.process_expired_transfers(100);
+		assert!(matches!(refunds.as_slice(), [refund] if refund.transfer_id == transfer_id));
+	}
+
+	// The initiated events of the tests have a time lock of 100.
+	fn assert_legal(runtime: &Runtime, action: &TransferAction, now: u64) {
+		assert!(runtime.authorize(action.clone(), now).is_some(), "{action} emitted at {now}");
+	}
+
+	fn counterpart_completed(transfer_id: BridgeTransferId) -> TransferEvent<Vec<u8>> {
+		(
+			BridgeContractEvent::CounterPartCompleted(
+				transfer_id,
+				HashLockPreImage([4; 32]),
+				AssetTag::Moveth,
+			),
+			ChainId::TWO,
+		)
+			.into()
+	}
+
+	#[test]
+	fn test_every_emitted_action_follows_the_canonical_ordering() {
+		let new_runtime = || {
+			Runtime::new(
+				RefundPolicy::default(),
+				Some(NudgePolicy { delay_secs: 0, max_attempts: 1 }),
+				StuckPolicy::default(),
+			)
+		};
+
+		// Happy path: lock, reveal, complete, nudge, done.
+		let transfer_id = BridgeTransferId([20; 32]);
+		let mut runtime = new_runtime();
+		let lock = runtime.process_event(initiated_event(transfer_id)).unwrap();
+		assert_legal(&runtime, &lock, 50);
+		let locked = runtime.process_event(locked_event(transfer_id)).unwrap();
+		assert_legal(&runtime, &locked, 50);
+		let complete = runtime.process_event(counterpart_completed(transfer_id)).unwrap();
+		assert_legal(&runtime, &complete, 60);
+		for nudge in runtime.process_nudges(unix_now()) {
+			assert_legal(&runtime, &nudge, 60);
+		}
+		let done: TransferEvent<Vec<u8>> =
+			(BridgeContractEvent::InitialtorCompleted(transfer_id, AssetTag::Moveth), ChainId::ONE)
+				.into();
+		let done = runtime.process_event(done).unwrap();
+		assert_legal(&runtime, &done, 70);
+		// Completing or refunding a done transfer is never allowed.
+		assert!(runtime.authorize(complete.clone(), 70).is_none());
+		assert!(runtime.process_expired_transfers(200).is_empty());
+
+		// Refund path: no refund before the time lock, no lock after it.
+		let transfer_id = BridgeTransferId([21; 32]);
+		let mut runtime = new_runtime();
+		let lock = runtime.process_event(initiated_event(transfer_id)).unwrap();
+		assert!(runtime.authorize(lock.clone(), 100).is_none());
+		runtime.process_event(locked_event(transfer_id)).unwrap();
+		assert!(runtime.process_expired_transfers(99).is_empty());
+		let refunds = runtime.process_expired_transfers(100);
+		assert_eq!(refunds.len(), 1);
+		assert_legal(&runtime, &refunds[0], 100);
+		assert!(runtime.authorize(refunds[0].clone(), 99).is_none());
+
+		// Reveal after the refund, e.g. a reveal reorged out then back in: it's rejected and
+		// no completion is emitted.
+		assert!(runtime.process_event(counterpart_completed(transfer_id)).is_err());
+		let complete = TransferAction {
+			chain: ChainId::ONE,
+			transfer_id,
+			kind: TransferActionType::WaitAndCompleteInitiator(0, HashLockPreImage([4; 32])),
+		};
+		assert!(runtime.authorize(complete, 100).is_none());
+		assert!(runtime.process_nudges(unix_now()).is_empty());
+
+		// Completion revert: the completion is abandoned, never turned into a refund.
+		let transfer_id = BridgeTransferId([22; 32]);
+		let (mut runtime, complete) = runtime_with_secret_received(transfer_id, None);
+		for _ in 0..=5 {
+			let revert = BridgeContractError::TransactionReverted("execution reverted".to_string());
+			if let Some(retry) =
+				runtime.process_action_exec_error(ActionExecError(complete.clone(), revert))
+			{
+				assert_legal(&runtime, &retry, 200);
+			}
+		}
+		assert!(runtime.process_expired_transfers(200).is_empty());
+		let refund = TransferAction {
+			chain: ChainId::ONE,
+			transfer_id,
+			kind: TransferActionType::RefundInitiator,
+		};
+		assert!(runtime.authorize(refund, 200).is_none());
+	}
+
+	#[test]
+	fn test_reverted_completion_is_not_retried_and_not_completed() {
+		let transfer_id = BridgeTransferId([5; 32]);
+		let (mut runtime, action) = runtime_with_secret_received(transfer_id, None);
+
+		// A revert fails again, the transfer stays pending to be reconciled.
+		let revert = BridgeContractError::TransactionReverted("execution reverted".to_string());
+		assert!(runtime
+			.process_action_exec_error(ActionExecError(action.clone(), revert))
+			.is_none());
+		assert_eq!(
+			runtime.swap_state_map.get(&transfer_id).unwrap().state,
+			TransferStateType::SecretReceived
+		);
+	}
+
+	#[test]
+	fn test_timed_out_completion_is_retried() {
+		let transfer_id = BridgeTransferId([7; 32]);
+		let (mut runtime, action) = runtime_with_secret_received(transfer_id, None);
+
+		let timeout = BridgeContractError::RpcTimeout("request timed out".to_string());
+		let retry = runtime.process_action_exec_error(ActionExecError(action.clone(), timeout));
+		assert!(matches!(
+			retry.map(|action| action.kind),
+			Some(TransferActionType::WaitAndCompleteInitiator(..))
+		));
+		assert_eq!(
+			runtime.swap_state_map.get(&transfer_id).unwrap().state,
+			TransferStateType::SecretReceived
+		);
+	}
+
+	#[test]
+	fn test_completion_failure_keeps_transfer_pending_after_max_retry() {
+		let transfer_id = BridgeTransferId([6; 32]);
+		let (mut runtime, action) = runtime_with_secret_received(transfer_id, None);
+
+		let timeout = || BridgeContractError::RpcTimeout("request timed out".to_string());
+		for retry in 1..=5 {
+			assert!(
+				runtime
+					.process_action_exec_error(ActionExecError(action.clone(), timeout()))
+					.is_some(),
+				"retry {retry}"
+			);
+		}
+		assert_eq!(runtime.swap_state_map.get(&transfer_id).unwrap().retry_on_error, 5);
+
+		// The sixth failure gives up, the transfer stays pending.
+		assert!(runtime
+			.process_action_exec_error(ActionExecError(action.clone(), timeout()))
+			.is_none());
+		let state = runtime.swap_state_map.get(&transfer_id).unwrap();
+		assert_eq!(state.retry_on_error, 6);
+		assert_eq!(state.state, TransferStateType::SecretReceived);
+	}
+
+	#[test]
+	fn test_unobserved_completion_is_nudged() {
+		let transfer_id = BridgeTransferId([10; 32]);
+		let policy = NudgePolicy { delay_secs: 60, max_attempts: 2 };
+		let (mut runtime, _) = runtime_with_secret_received(transfer_id, Some(policy));
+		let nudge_at = runtime.swap_state_map.get(&transfer_id).unwrap().nudge_at.unwrap();
+
+		// Not stalled yet.
+		assert!(runtime.process_nudges(nudge_at - 1).is_empty());
+
+		let actions = runtime.process_nudges(nudge_at);
+		assert_eq!(actions.len(), 1);
+		assert_eq!(actions[0].chain, ChainId::ONE);
+		assert!(matches!(
+			actions[0].kind,
+			TransferActionType::NudgeCompleteInitiator(secret) if secret == HashLockPreImage([4; 32])
+		));
+		assert_eq!(runtime.process_nudges(nudge_at + 60).len(), 1);
+		// Max attempts reached, the transfer is left to the refund.
+		assert!(runtime.process_nudges(nudge_at + 120).is_empty());
+		assert!(runtime.process_nudges(nudge_at + 180).is_empty());
+
+		// The completion finally observed ends the transfer.
+		let completed: TransferEvent<Vec<u8>> =
+			(BridgeContractEvent::InitialtorCompleted(transfer_id, AssetTag::Moveth), ChainId::ONE)
+				.into();
+		runtime.process_event(completed).unwrap();
+		assert_eq!(
+			runtime.swap_state_map.get(&transfer_id).unwrap().state,
+			TransferStateType::Done
+		);
+		assert!(runtime.process_nudges(u64::MAX).is_empty());
+	}
+
+	#[test]
+	fn test_completion_is_not_nudged_when_disabled() {
+		let transfer_id = BridgeTransferId([11; 32]);
+		let (mut runtime, _) = runtime_with_secret_received(transfer_id, None);
+		assert!(runtime.process_nudges(u64::MAX).is_empty());
+	}
+
+	#[test]
+	fn test_transfer_is_flagged_stuck_after_its_grace_window() {
+		let one = BridgeTransferId([12; 32]);
+		let two = BridgeTransferId([13; 32]);
+		let mut runtime = Runtime::new(
+			RefundPolicy::default(),
+			None,
+			StuckPolicy { grace_secs_one: 600, grace_secs_two: 1200 },
+		);
+		runtime.process_event(initiated_event(one)).unwrap();
+		let mut movement_init = initiated_event(two);
+		movement_init.chain = ChainId::TWO;
+		runtime.process_event(movement_init).unwrap();
+		let initiated_at = runtime.swap_state_map.get(&one).unwrap().initiated_at;
+		runtime.swap_state_map.get_mut(&two).unwrap().initiated_at = initiated_at;
+
+		// Within the grace window.
+		assert!(runtime.process_stuck_transfers(initiated_at + 599).is_empty());
+		// Past the window of its direction, alerted once.
+		assert_eq!(runtime.process_stuck_transfers(initiated_at + 600), vec![one]);
+		assert!(runtime.process_stuck_transfers(initiated_at + 900).is_empty());
+		assert_eq!(runtime.process_stuck_transfers(initiated_at + 1200), vec![two]);
+		assert!(runtime.swap_state_map.values().all(|state| state.stuck));
+	}
+
+	#[test]
+	fn test_erc20_transfer_is_locked_with_its_token() {
+		let transfer_id = BridgeTransferId([14; 32]);
+		let token_address = EthAddress(alloy::primitives::Address::repeat_byte(0xee));
+		let asset: AssetTag = format!("erc20:{}", token_address.0).parse().unwrap();
+		assert_eq!(asset, AssetTag::Erc20(token_address));
+		assert_eq!(asset.to_string().parse::<AssetTag>().unwrap(), asset);
+
+		let mut runtime = Runtime::new(RefundPolicy::default(), None, StuckPolicy::default());
+		let mut initiated = initiated_event(transfer_id);
+		if let BridgeContractEvent::Initiated(details) = &mut initiated.contract_event {
+			details.amount = asset.amount(500);
+		}
+		let action = runtime.process_event(initiated).unwrap();
+		assert_eq!(action.chain, ChainId::TWO);
+		let TransferActionType::LockBridgeTransfer { amount, .. } = action.kind else {
+			panic!("Unexpected action {action}");
+		};
+		assert_eq!(amount, Amount(AssetType::Erc20 { token_address, amount: 500 }));
+		assert_eq!((amount.value(), amount.token_address()), (500, Some(token_address)));
+	}
+}
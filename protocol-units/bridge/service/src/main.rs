@@ -1,10 +1,21 @@
 use anyhow::Result;
 use bridge_config::Config;
+use bridge_service::balance::{run_balance_monitoring, BalanceThresholds, DegradedChains};
 use bridge_service::chains::ethereum::client::EthClient;
 use bridge_service::chains::ethereum::event_monitoring::EthMonitoring;
 use bridge_service::chains::movement::client::MovementClient;
 use bridge_service::chains::movement::event_monitoring::MovementMonitoring;
+use bridge_service::confirmation::ConfirmationPolicy;
+use bridge_service::metrics::{run_metrics, RelayerMetrics};
+use bridge_service::self_check::self_check;
+use bridge_service::slo::CompletionSlo;
+use bridge_service::store::build_store;
+use bridge_service::{ChainAssets, HashAlgorithms, NudgePolicy, RelayerOptions, StuckPolicy};
 use godfig::{backend::config_file::ConfigFile, Godfig};
+use prometheus::Registry;
+use std::time::Duration;
+#[cfg(unix)]
+use tokio::signal::unix::{signal, SignalKind};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -27,17 +38,137 @@ async fn main() -> Result<()> {
 	// get a matching godfig object
 	let godfig: Godfig<Config, ConfigFile> = Godfig::new(ConfigFile::new(config_file), vec![]);
 	let bridge_config: Config = godfig.try_wait_for_ready().await?;
-	tracing::info!("Bridge config loaded: {bridge_config:?}");
+	tracing::info!("Bridge config loaded: {:?}", bridge_config.redacted());
 
-	let one_stream = EthMonitoring::build(&bridge_config.eth).await.unwrap();
+	// Served on the metrics endpoint, if it's configured.
+	let registry = Registry::new();
+	let relayer_metrics = RelayerMetrics::register(&registry)?;
+
+	let one_stream = EthMonitoring::build_with_metrics(&bridge_config.eth, relayer_metrics.clone())
+		.await
+		.unwrap();
 
 	let one_client = EthClient::new(&bridge_config.eth).await.unwrap();
 
 	let two_client = MovementClient::new(&bridge_config.movement).await.unwrap();
 
-	let two_stream = MovementMonitoring::build(&bridge_config.movement).await.unwrap();
+	let two_stream =
+		MovementMonitoring::build_with_metrics(&bridge_config.movement, relayer_metrics.clone())
+			.await
+			.unwrap();
+
+	// Refuse to start if the relayer can't work.
+	let store = build_store(&bridge_config.store).await?;
+	let report = self_check(&one_client, &two_client, store.as_ref()).await?;
+	tracing::info!("Bridge self-check passed:\n{report}");
+
+	// Alert and pause the completions when a signer runs low.
+	let degraded_chains = DegradedChains::default();
+	tokio::spawn({
+		let (one_client, two_client) = (one_client.clone(), two_client.clone());
+		let degraded_chains = degraded_chains.clone();
+		let registry = registry.clone();
+		let balance_config = bridge_config.balance.clone();
+		async move {
+			if let Err(err) = run_balance_monitoring(
+				one_client,
+				BalanceThresholds::eth(&balance_config),
+				two_client,
+				BalanceThresholds::movement(&balance_config),
+				degraded_chains,
+				&registry,
+				Duration::from_secs(balance_config.balance_check_interval_secs),
+			)
+			.await
+			{
+				tracing::error!("Bridge balance monitoring stopped:{err}");
+			}
+		}
+	});
+
+	// Alert when the transfers complete too slowly.
+	let completion_slo = CompletionSlo::register(&registry, (&bridge_config.slo).into())?;
+
+	let metrics_address = bridge_config.metrics.metrics_listen_address.clone();
+	if !metrics_address.is_empty() {
+		let store = store.clone();
+		tokio::spawn(async move {
+			if let Err(err) = run_metrics(&metrics_address, registry, store).await {
+				tracing::error!("Bridge metrics endpoint stopped:{err}");
+			}
+		});
+	}
+
+	// Both directions wait for the Eth confirmations before the transfer can complete.
+	let eth_confirmations =
+		ConfirmationPolicy::new(bridge_config.eth.eth_confirmation_tiers.clone())
+			.with_asset_overrides(&bridge_config.eth.eth_asset_confirmations)?
+			.with_min_confirmations(bridge_config.eth.eth_confirmation_blocks)
+			.max_confirmations();
+
+	// A transfer is only locked if both chains hash its lock the same way.
+	let hash_algorithms = HashAlgorithms::from_config(
+		&bridge_config.eth.eth_hash_algorithm,
+		&bridge_config.movement.movement_hash_algorithm,
+	)?;
+
+	// The counterparty locks carry the value in the asset of their chain.
+	let assets = ChainAssets {
+		one: bridge_config.eth.eth_asset.parse()?,
+		two: bridge_config.movement.movement_asset.parse()?,
+	};
+
+	// Listen before the loop starts so that an early stop signal isn't lost.
+	let shutdown = shutdown_signal()?;
 
 	tracing::info!("Bridge Eth and Movement Inited. Starting bridge loop.");
-	bridge_service::run_bridge(one_client, one_stream, two_client, two_stream).await?;
+	bridge_service::run_bridge(
+		one_client,
+		one_stream,
+		two_client,
+		two_stream,
+		RelayerOptions {
+			refund_policy: (&bridge_config.refund).into(),
+			nudge_policy: NudgePolicy::from_config(&bridge_config.nudge),
+			stuck_policy: StuckPolicy::from_config(&bridge_config.stuck, eth_confirmations),
+			degraded_chains,
+			completion_slo: Some(completion_slo),
+			metrics: Some(relayer_metrics),
+			store,
+			hash_algorithms,
+			rate_limits: (&bridge_config.rate_limit).into(),
+			assets: Some(assets),
+		},
+		shutdown,
+	)
+	.await?;
 	Ok(())
 }
+
+/// Resolves on the first SIGINT or SIGTERM, the signals sent to stop the relayer.
+#[cfg(unix)]
+fn shutdown_signal() -> Result<impl std::future::Future<Output = ()>> {
+	let mut sigterm = signal(SignalKind::terminate())?;
+	let mut sigint = signal(SignalKind::interrupt())?;
+	Ok(async move {
+		tokio::select! {
+			_ = sigterm.recv() => tracing::info!("Received SIGTERM"),
+			_ = sigint.recv() => tracing::info!("Received SIGINT"),
+		}
+	})
+}
+
+/// Resolves on Ctrl-C, the only stop signal outside unix.
+#[cfg(not(unix))]
+fn shutdown_signal() -> Result<impl std::future::Future<Output = ()>> {
+	Ok(async {
+		match tokio::signal::ctrl_c().await {
+			Ok(()) => tracing::info!("Received Ctrl-C"),
+			// Keep relaying rather than stopping on a signal that was never sent.
+			Err(err) => {
+				tracing::error!("Failed to listen for Ctrl-C:{err}");
+				std::future::pending::<()>().await
+			}
+		}
+	})
+}
@@ -0,0 +1,264 @@
+use crate::chains::bridge_contracts::{BridgeContractEvent, BridgeContractEventType};
+use crate::store::{StoreError, TransferRecord, TransferStore};
+use crate::types::ChainId;
+use crate::unix_now;
+use poem::listener::TcpListener;
+use poem::web::Data;
+use poem::{get, handler, EndpointExt, Route, Server};
+use prometheus::{
+	Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Interval between two refreshes of the metrics computed from the store.
+const METRICS_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+/// Buckets in seconds of the initiate to complete latency.
+const COMPLETION_LATENCY_BUCKETS: [f64; 9] =
+	[30.0, 60.0, 120.0, 300.0, 600.0, 1200.0, 1800.0, 3600.0, 7200.0];
+
+/// Counters of the relayer activity: events observed by the monitorings, completed and
+/// refunded transfers, monitoring reconnections, and the latency of the completed transfers.
+#[derive(Clone)]
+pub struct RelayerMetrics {
+	events_observed: IntCounterVec,
+	transfers_completed: IntCounter,
+	transfers_refunded: IntCounter,
+	monitor_reconnects: IntCounterVec,
+	completion_latency: Histogram,
+}
+
+impl RelayerMetrics {
+	pub fn register(registry: &Registry) -> prometheus::Result<Self> {
+		let events_observed = IntCounterVec::new(
+			Opts::new(
+				"bridge_events_observed_total",
+				"Contract events observed by the monitorings",
+			),
+			&["chain", "event"],
+		)?;
+		registry.register(Box::new(events_observed.clone()))?;
+		let transfers_completed = IntCounter::new(
+			"bridge_transfers_completed_total",
+			"Transfers completed on both chains",
+		)?;
+		registry.register(Box::new(transfers_completed.clone()))?;
+		let transfers_refunded = IntCounter::new(
+			"bridge_transfers_refunded_total",
+			"Transfers refunded to the initiator",
+		)?;
+		registry.register(Box::new(transfers_refunded.clone()))?;
+		let monitor_reconnects = IntCounterVec::new(
+			Opts::new(
+				"bridge_monitor_reconnects_total",
+				"Event sources of the monitorings reconnected after they dropped",
+			),
+			&["chain"],
+		)?;
+		registry.register(Box::new(monitor_reconnects.clone()))?;
+		let completion_latency = Histogram::with_opts(
+			HistogramOpts::new(
+				"bridge_transfer_completion_seconds",
+				"Duration from the initiated event to the completion of a transfer",
+			)
+			.buckets(COMPLETION_LATENCY_BUCKETS.to_vec()),
+		)?;
+		registry.register(Box::new(completion_latency.clone()))?;
+		Ok(RelayerMetrics {
+			events_observed,
+			transfers_completed,
+			transfers_refunded,
+			monitor_reconnects,
+			completion_latency,
+		})
+	}
+
+	/// Count an event observed by the monitoring of `chain`.
+	pub fn observe_event<A>(&self, chain: &str, event: &BridgeContractEvent<A>) {
+		self.events_observed
+			.with_label_values(&[chain, event_label(event.event_type())])
+			.inc();
+	}
+
+	/// Counter of the reconnections of the monitoring of `chain`.
+	pub fn monitor_reconnects(&self, chain: &str) -> IntCounter {
+		self.monitor_reconnects.with_label_values(&[chain])
+	}
+
+	/// Count a completed transfer, `latency_secs` after it was initiated.
+	pub fn transfer_completed(&self, latency_secs: u64) {
+		self.transfers_completed.inc();
+		self.completion_latency.observe(latency_secs as f64);
+	}
+
+	pub fn transfer_refunded(&self) {
+		self.transfers_refunded.inc();
+	}
+}
+
+fn event_label(event_type: BridgeContractEventType) -> &'static str {
+	match event_type {
+		BridgeContractEventType::Initiated => "initiated",
+		BridgeContractEventType::Locked => "locked",
+		BridgeContractEventType::InitialtorCompleted => "initiator_completed",
+		BridgeContractEventType::CounterPartCompleted => "counterpart_completed",
+		BridgeContractEventType::Cancelled => "cancelled",
+		BridgeContractEventType::Refunded => "refunded",
+	}
+}
+
+/// Gauges computed from the transfers of the store.
+#[derive(Clone)]
+pub struct TransferMetrics {
+	oldest_pending_age: IntGaugeVec,
+}
+
+impl TransferMetrics {
+	pub fn register(registry: &Registry) -> prometheus::Result<Self> {
+		let oldest_pending_age = IntGaugeVec::new(
+			Opts::new(
+				"bridge_oldest_pending_transfer_age_seconds",
+				"Age in seconds of the oldest pending transfer, 0 if there is none",
+			),
+			&["direction"],
+		)?;
+		registry.register(Box::new(oldest_pending_age.clone()))?;
+		Ok(TransferMetrics { oldest_pending_age })
+	}
+
+	/// Recompute the gauges from the pending transfers of the store.
+	pub async fn refresh(&self, store: &dyn TransferStore, now: u64) -> Result<(), StoreError> {
+		let pending = store.pending().await?;
+		for init_chain in [ChainId::ONE, ChainId::TWO] {
+			let age = pending
+				.iter()
+				.filter(|record| record.init_chain == init_chain)
+				.map(TransferRecord::pending_since)
+				.min()
+				.map_or(0, |since| now.saturating_sub(since));
+			self.oldest_pending_age
+				.with_label_values(&[direction(init_chain)])
+				.set(age as i64);
+		}
+		Ok(())
+	}
+}
+
+// Chain ONE is Eth, chain TWO is Movement.
+fn direction(init_chain: ChainId) -> &'static str {
+	match init_chain {
+		ChainId::ONE => "eth_to_movement",
+		ChainId::TWO => "movement_to_eth",
+	}
+}
+
+/// Serve the metrics of `registry` on `listen_address`, along with the transfer metrics
+/// kept up to date with the store.
+pub async fn run_metrics(
+	listen_address: &str,
+	registry: Registry,
+	store: Arc<dyn TransferStore>,
+) -> Result<(), anyhow::Error> {
+	let metrics = TransferMetrics::register(&registry)?;
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(METRICS_REFRESH_INTERVAL);
+		loop {
+			interval.tick().await;
+			if let Err(err) = metrics.refresh(store.as_ref(), unix_now()).await {
+				tracing::warn!("Failed to refresh the transfer metrics:{err}");
+			}
+		}
+	});
+
+	let app = Route::new().at("/metrics", get(metrics_endpoint)).data(registry);
+	Server::new(TcpListener::bind(listen_address)).run(app).await?;
+	Ok(())
+}
+
+#[handler]
+fn metrics_endpoint(Data(registry): Data<&Registry>) -> poem::Result<String> {
+	TextEncoder::new()
+		.encode_to_string(&registry.gather())
+		.map_err(poem::error::InternalServerError)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::states::TransferStateType;
+	use crate::store::tests::record;
+	use crate::store::MemoryStore;
+	use crate::types::{AssetTag, BridgeTransferId};
+
+	fn oldest_pending_age(metrics: &TransferMetrics, init_chain: ChainId) -> i64 {
+		metrics.oldest_pending_age.with_label_values(&[direction(init_chain)]).get()
+	}
+
+	#[test]
+	fn test_relayer_metrics_are_exported() {
+		let registry = Registry::new();
+		let metrics = RelayerMetrics::register(&registry).unwrap();
+		let transfer_id = BridgeTransferId([1; 32]);
+		metrics.observe_event::<Vec<u8>>(
+			"Eth",
+			&BridgeContractEvent::InitialtorCompleted(transfer_id, AssetTag::Moveth),
+		);
+		metrics.observe_event::<Vec<u8>>(
+			"Eth",
+			&BridgeContractEvent::InitialtorCompleted(transfer_id, AssetTag::Moveth),
+		);
+		metrics.observe_event::<Vec<u8>>(
+			"Movement",
+			&BridgeContractEvent::Refunded(transfer_id, AssetTag::Moveth),
+		);
+		metrics.monitor_reconnects("Eth").inc();
+		metrics.transfer_completed(90);
+		metrics.transfer_refunded();
+
+		assert_eq!(
+			metrics.events_observed.with_label_values(&["Eth", "initiator_completed"]).get(),
+			2
+		);
+		assert_eq!(metrics.events_observed.with_label_values(&["Movement", "refunded"]).get(), 1);
+		assert_eq!(metrics.monitor_reconnects("Eth").get(), 1);
+		assert_eq!(metrics.completion_latency.get_sample_count(), 1);
+		assert_eq!(metrics.completion_latency.get_sample_sum(), 90.0);
+
+		let exported = TextEncoder::new().encode_to_string(&registry.gather()).unwrap();
+		assert!(exported.contains("bridge_transfers_completed_total 1"));
+		assert!(exported.contains("bridge_transfers_refunded_total 1"));
+		assert!(exported.contains("bridge_transfer_completion_seconds_bucket{le=\"120\"} 1"));
+	}
+
+	#[tokio::test]
+	async fn test_oldest_pending_age_follows_time_and_completion() {
+		let store = MemoryStore::default();
+		let metrics = TransferMetrics::register(&Registry::new()).unwrap();
+		let pending = record(1, TransferStateType::Locked);
+		let created_at = pending.created_at;
+		store.save(&pending).await.unwrap();
+		// A newer transfer doesn't change the oldest age.
+		store
+			.save(&TransferRecord {
+				created_at: created_at + 60,
+				..record(2, TransferStateType::Initialized)
+			})
+			.await
+			.unwrap();
+
+		metrics.refresh(&store, created_at + 120).await.unwrap();
+		assert_eq!(oldest_pending_age(&metrics, ChainId::ONE), 120);
+		assert_eq!(oldest_pending_age(&metrics, ChainId::TWO), 0);
+
+		metrics.refresh(&store, created_at + 300).await.unwrap();
+		assert_eq!(oldest_pending_age(&metrics, ChainId::ONE), 300);
+
+		// The next oldest pending transfer is reported once the first one is done.
+		store
+			.save(&TransferRecord { state: TransferStateType::Done, ..pending })
+			.await
+			.unwrap();
+		metrics.refresh(&store, created_at + 300).await.unwrap();
+		assert_eq!(oldest_pending_age(&metrics, ChainId::ONE), 240);
+	}
+}
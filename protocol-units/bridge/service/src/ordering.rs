@@ -0,0 +1,177 @@
+use crate::actions::TransferActionType;
+use crate::states::{TransferState, TransferStateType};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum IllegalActionError {
+	#[error("{0} is not allowed in state {1:?}")]
+	WrongState(String, TransferStateType),
+	#[error("Lock after the time lock expired")]
+	LockAfterExpiry,
+	#[error("Completion with a secret that wasn't revealed")]
+	UnrevealedSecret,
+	#[error("Refund before the time lock expired")]
+	RefundBeforeExpiry,
+	#[error("Refund after the secret was revealed")]
+	RefundAfterReveal,
+}
+
+/// Check that `action` can be sent at `now` for a transfer in `state`, the state being the one
+/// the relayer moved the transfer to when it decided the action.
+///
+/// A transfer goes through:
+/// 1. initiate: the initiator locks its funds on the init chain with a hash lock and a time lock,
+/// 2. confirm initiate: the monitoring only delivers the Initiated event once confirmed,
+/// 3. lock: the relayer locks the same amount on the counterparty chain,
+/// 4. confirm lock: the Locked event is delivered once confirmed,
+/// 5. reveal/complete: the recipient completes on the counterparty chain, revealing the secret,
+///    then the relayer completes the initiator with it,
+/// 6. confirm complete: the InitialtorCompleted event closes the transfer.
+///
+/// The events are ordered by the transitions of [`TransferStateType`], the actions by this check:
+/// - a lock is only sent for an initialized transfer whose time lock hasn't expired, else the
+///   initiator could be refunded and still complete the lock with its secret,
+/// - a completion is only sent with the secret revealed on the counterparty chain,
+/// - a refund is only sent once the time lock expired and never after the secret was revealed,
+///   else the initiator would be refunded while the recipient was paid.
+pub fn check_action(
+	state: &TransferState,
+	action: &TransferActionType,
+	now: u64,
+) -> Result<(), IllegalActionError> {
+	let expired = state.time_lock.0 <= now;
+	let wrong_state = || IllegalActionError::WrongState(action.to_string(), state.state);
+	match action {
+		TransferActionType::NoAction | TransferActionType::TransferDone => Ok(()),
+		TransferActionType::LockBridgeTransfer { .. } => {
+			if state.state != TransferStateType::Initialized {
+				return Err(wrong_state());
+			}
+			if expired {
+				return Err(IllegalActionError::LockAfterExpiry);
+			}
+			Ok(())
+		}
+		TransferActionType::WaitAndCompleteInitiator(_, secret)
+		| TransferActionType::NudgeCompleteInitiator(secret) => {
+			if !matches!(
+				state.state,
+				TransferStateType::SecretReceived | TransferStateType::CompletedIntiator
+			) {
+				return Err(wrong_state());
+			}
+			if state.secret != Some(*secret) {
+				return Err(IllegalActionError::UnrevealedSecret);
+			}
+			Ok(())
+		}
+		TransferActionType::RefundInitiator => {
+			if state.state != TransferStateType::Refund {
+				return Err(wrong_state());
+			}
+			if state.secret.is_some() {
+				return Err(IllegalActionError::RefundAfterReveal);
+			}
+			if !expired {
+				return Err(IllegalActionError::RefundBeforeExpiry);
+			}
+			Ok(())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::store::TransferRecord;
+	use crate::types::{
+		Amount, AssetType, BridgeAddress, BridgeTransferId, ChainId, HashAlgorithm, HashLock,
+		HashLockPreImage, TimeLock,
+	};
+	use TransferStateType::*;
+
+	const TIME_LOCK: u64 = 1_000;
+	const SECRET: HashLockPreImage = HashLockPreImage([4; 32]);
+
+	fn state(state: TransferStateType, secret: Option<HashLockPreImage>) -> TransferState {
+		TransferState::from_record(&TransferRecord {
+			transfer_id: BridgeTransferId([1; 32]),
+			init_chain: ChainId::ONE,
+			state,
+			initiator_address: vec![1; 20],
+			recipient_address: vec![2; 32],
+			hash_lock: HashLock([3; 32]),
+			time_lock: TimeLock(TIME_LOCK),
+			amount: Amount(AssetType::Moveth(10)),
+			created_at: 0,
+			updated_at: 0,
+			secret,
+			hash_algorithm: HashAlgorithm::Keccak256,
+		})
+	}
+
+	fn actions() -> Vec<TransferActionType> {
+		vec![
+			TransferActionType::LockBridgeTransfer {
+				bridge_transfer_id: BridgeTransferId([1; 32]),
+				hash_lock: HashLock([3; 32]),
+				initiator: BridgeAddress(vec![1; 20]),
+				recipient: BridgeAddress(vec![2; 32]),
+				amount: Amount(AssetType::Moveth(10)),
+			},
+			TransferActionType::WaitAndCompleteInitiator(0, SECRET),
+			TransferActionType::NudgeCompleteInitiator(SECRET),
+			TransferActionType::RefundInitiator,
+			TransferActionType::TransferDone,
+			TransferActionType::NoAction,
+		]
+	}
+
+	#[test]
+	fn test_only_the_canonical_actions_are_allowed() {
+		let states =
+			[Initialized, Locked, SecretReceived, CompletedIntiator, Done, Refund, RefundRequired];
+		for transfer_state in states {
+			for secret in [None, Some(SECRET), Some(HashLockPreImage([5; 32]))] {
+				for now in [TIME_LOCK - 1, TIME_LOCK] {
+					let state = state(transfer_state, secret);
+					for action in actions() {
+						let expected = match &action {
+							TransferActionType::NoAction | TransferActionType::TransferDone => true,
+							TransferActionType::LockBridgeTransfer { .. } => {
+								transfer_state == Initialized && now < TIME_LOCK
+							}
+							TransferActionType::WaitAndCompleteInitiator(..)
+							| TransferActionType::NudgeCompleteInitiator(..) => {
+								matches!(transfer_state, SecretReceived | CompletedIntiator)
+									&& secret == Some(SECRET)
+							}
+							TransferActionType::RefundInitiator => {
+								transfer_state == Refund && secret.is_none() && now >= TIME_LOCK
+							}
+						};
+						assert_eq!(
+							check_action(&state, &action, now).is_ok(),
+							expected,
+							"{action} in {transfer_state:?} with secret {secret:?} at {now}"
+						);
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_refund_is_never_sent_once_revealed() {
+		let mut revealed = state(Refund, Some(SECRET));
+		assert_eq!(
+			check_action(&revealed, &TransferActionType::RefundInitiator, TIME_LOCK),
+			Err(IllegalActionError::RefundAfterReveal)
+		);
+		revealed.state = SecretReceived;
+		assert!(matches!(
+			check_action(&revealed, &TransferActionType::RefundInitiator, TIME_LOCK),
+			Err(IllegalActionError::WrongState(_, SecretReceived))
+		));
+	}
+}
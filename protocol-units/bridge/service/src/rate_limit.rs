@@ -0,0 +1,169 @@
+use crate::actions::{TransferAction, TransferActionType};
+use crate::slo::WindowedHistogram;
+use crate::types::ChainId;
+use bridge_config::common::rate_limit::RateLimitConfig;
+
+/// Caps the submissions to `limit` over a rolling window.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+	limit: usize,
+	submits: WindowedHistogram,
+}
+
+impl RateLimiter {
+	pub fn new(limit: usize, window_secs: u64) -> Self {
+		RateLimiter { limit, submits: WindowedHistogram::new(window_secs) }
+	}
+
+	/// Record a submission at `now` if the window has room for it.
+	/// Returns false, recording nothing, once `limit` submissions were made in the window.
+	pub fn try_acquire(&mut self, now: u64) -> bool {
+		if self.available(now) == 0 {
+			return false;
+		}
+		self.submits.record(0, now);
+		true
+	}
+
+	/// Number of submissions the window has room for at `now`.
+	pub fn available(&mut self, now: u64) -> usize {
+		self.limit.saturating_sub(self.submits.count(now))
+	}
+}
+
+/// Most completions submitted per chain over the window, 0 for no limit.
+// Chain ONE is Eth, chain TWO is Movement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompletionRateLimits {
+	pub one: u64,
+	pub two: u64,
+	pub window_secs: u64,
+}
+
+impl From<&RateLimitConfig> for CompletionRateLimits {
+	fn from(config: &RateLimitConfig) -> Self {
+		CompletionRateLimits {
+			one: config.eth_completion_rate_limit,
+			two: config.movement_completion_rate_limit,
+			window_secs: config.completion_rate_window_secs,
+		}
+	}
+}
+
+/// Queues the completions over the rate limit of their chain until the window has room.
+pub(crate) struct CompletionThrottle {
+	one: Option<RateLimiter>,
+	two: Option<RateLimiter>,
+	queued: Vec<TransferAction>,
+}
+
+impl CompletionThrottle {
+	pub fn new(limits: CompletionRateLimits) -> Self {
+		let limiter =
+			|limit: u64| (limit > 0).then(|| RateLimiter::new(limit as usize, limits.window_secs));
+		CompletionThrottle {
+			one: limiter(limits.one),
+			two: limiter(limits.two),
+			queued: Vec::new(),
+		}
+	}
+
+	fn limiter(&mut self, chain: ChainId) -> Option<&mut RateLimiter> {
+		match chain {
+			ChainId::ONE => self.one.as_mut(),
+			ChainId::TWO => self.two.as_mut(),
+		}
+	}
+
+	/// Return the action if it can be submitted at `now`, else queue it until the window
+	/// of its chain has room. Only the completions are limited.
+	pub fn hold(&mut self, action: TransferAction, now: u64) -> Option<TransferAction> {
+		let completion = matches!(
+			action.kind,
+			TransferActionType::WaitAndCompleteInitiator(..)
+				| TransferActionType::NudgeCompleteInitiator(..)
+		);
+		if !completion {
+			return Some(action);
+		}
+		let limited = self.limiter(action.chain).is_some_and(|limiter| !limiter.try_acquire(now));
+		if limited {
+			tracing::warn!("Action {action} delayed, the completion rate limit is reached");
+			self.queued.push(action);
+			return None;
+		}
+		Some(action)
+	}
+
+	/// The queued completions the windows have room for at `now`, oldest first.
+	/// They are submitted through `hold`, which records them.
+	pub fn release(&mut self, now: u64) -> Vec<TransferAction> {
+		let mut available = [ChainId::ONE, ChainId::TWO]
+			.map(|chain| self.limiter(chain).map_or(usize::MAX, |limiter| limiter.available(now)));
+		let (released, queued) = self.queued.drain(..).partition(|action| {
+			let available = &mut available[(action.chain == ChainId::TWO) as usize];
+			let fits = *available > 0;
+			*available = available.saturating_sub(1);
+			fits
+		});
+		self.queued = queued;
+		released
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::types::{BridgeTransferId, HashLockPreImage};
+
+	fn complete(chain: ChainId, id: u8) -> TransferAction {
+		TransferAction {
+			chain,
+			transfer_id: BridgeTransferId([id; 32]),
+			kind: TransferActionType::WaitAndCompleteInitiator(0, HashLockPreImage([id; 32])),
+		}
+	}
+
+	#[test]
+	fn test_rate_limiter_frees_up_with_the_window() {
+		let mut limiter = RateLimiter::new(2, 60);
+		assert!(limiter.try_acquire(1_000));
+		assert!(limiter.try_acquire(1_010));
+		assert!(!limiter.try_acquire(1_020));
+		assert_eq!(limiter.available(1_020), 0);
+		// The first submission left the window.
+		assert!(limiter.try_acquire(1_060));
+		assert!(!limiter.try_acquire(1_060));
+	}
+
+	#[test]
+	fn test_completions_over_the_limit_are_delayed() {
+		let mut throttle =
+			CompletionThrottle::new(CompletionRateLimits { one: 2, two: 0, window_secs: 60 });
+
+		// A burst of completions on Eth, only the limit is submitted.
+		let submitted: Vec<_> = (1..=5)
+			.filter_map(|id| throttle.hold(complete(ChainId::ONE, id), 1_000))
+			.collect();
+		assert_eq!(submitted.len(), 2);
+		// The other chain and the other actions aren't limited.
+		assert!(throttle.hold(complete(ChainId::TWO, 6), 1_000).is_some());
+		let refund = TransferAction {
+			chain: ChainId::ONE,
+			transfer_id: BridgeTransferId([7; 32]),
+			kind: TransferActionType::RefundInitiator,
+		};
+		assert!(throttle.hold(refund, 1_000).is_some());
+		assert!(throttle.release(1_030).is_empty());
+
+		// Once the window moved, the oldest queued completions are submitted.
+		let released = throttle.release(1_060);
+		let ids: Vec<_> = released.iter().map(|action| action.transfer_id).collect();
+		assert_eq!(ids, vec![BridgeTransferId([3; 32]), BridgeTransferId([4; 32])]);
+		for action in released {
+			assert!(throttle.hold(action, 1_060).is_some());
+		}
+		assert!(throttle.release(1_090).is_empty());
+		assert_eq!(throttle.release(1_120).len(), 1);
+	}
+}
@@ -0,0 +1,241 @@
+use crate::states::TransferStateType;
+use crate::store::{TransferRecord, TransferStore};
+use crate::types::{
+	Amount, AssetType, BridgeTransferId, ChainId, HashAlgorithm, HashLock, TimeLock,
+};
+use std::fmt;
+use thiserror::Error;
+
+/// Id of the record written to verify the store, no transfer can have it.
+const STORE_PROBE_ID: BridgeTransferId = BridgeTransferId([0xff; 32]);
+
+/// Chain side of the startup self-check.
+#[async_trait::async_trait]
+pub trait ChainHealthCheck: Send + Sync {
+	/// Name of the chain in the report.
+	fn chain_name(&self) -> &'static str;
+
+	/// Verify the chain RPC answers.
+	async fn check_rpc(&self) -> Result<(), String>;
+
+	/// Bridge contracts used by the relayer, as (name, address).
+	fn contracts(&self) -> Vec<(&'static str, String)>;
+
+	/// Return true if some code is deployed at the contract address.
+	async fn has_code(&self, address: &str) -> Result<bool, String>;
+
+	fn signer_address(&self) -> String;
+
+	/// Gas token balance of the signer account.
+	async fn signer_balance(&self) -> Result<u128, String>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckStatus {
+	Pass,
+	Fail(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckItem {
+	pub name: String,
+	pub status: CheckStatus,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelfCheckReport {
+	pub items: Vec<CheckItem>,
+}
+
+impl SelfCheckReport {
+	fn push(&mut self, name: String, result: Result<(), String>) {
+		let status = match result {
+			Ok(()) => CheckStatus::Pass,
+			Err(reason) => CheckStatus::Fail(reason),
+		};
+		self.items.push(CheckItem { name, status });
+	}
+
+	pub fn failures(&self) -> impl Iterator<Item = &CheckItem> {
+		self.items.iter().filter(|item| item.status != CheckStatus::Pass)
+	}
+
+	pub fn is_ok(&self) -> bool {
+		self.failures().next().is_none()
+	}
+}
+
+impl fmt::Display for SelfCheckReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for item in &self.items {
+			match &item.status {
+				CheckStatus::Pass => writeln!(f, "[PASS] {}", item.name)?,
+				CheckStatus::Fail(reason) => writeln!(f, "[FAIL] {}: {reason}", item.name)?,
+			}
+		}
+		Ok(())
+	}
+}
+
+#[derive(Debug, Error)]
+pub enum SelfCheckError {
+	#[error("Bridge self-check failed:\n{0}")]
+	Failed(SelfCheckReport),
+}
+
+/// Verify the relayer can run before processing any event: both chains are reachable,
+/// their bridge contracts are deployed, the signers can pay gas and the store is writable.
+/// Return an error holding the whole report if any check fails.
+pub async fn self_check(
+	one: &dyn ChainHealthCheck,
+	two: &dyn ChainHealthCheck,
+	store: &dyn TransferStore,
+) -> Result<SelfCheckReport, SelfCheckError> {
+	let mut report = SelfCheckReport::default();
+	check_chain(one, &mut report).await;
+	check_chain(two, &mut report).await;
+	report.push("store writable".to_string(), check_store(store).await);
+
+	if report.is_ok() {
+		Ok(report)
+	} else {
+		Err(SelfCheckError::Failed(report))
+	}
+}
+
+async fn check_chain(chain: &dyn ChainHealthCheck, report: &mut SelfCheckReport) {
+	let name = chain.chain_name();
+	let rpc = chain.check_rpc().await;
+	let rpc_ok = rpc.is_ok();
+	report.push(format!("{name} rpc"), rpc.map_err(|err| format!("rpc unreachable: {err}")));
+	// Other checks need the rpc.
+	if !rpc_ok {
+		return;
+	}
+
+	for (contract, address) in chain.contracts() {
+		let result = match chain.has_code(&address).await {
+			Ok(true) => Ok(()),
+			Ok(false) => Err(format!("no contract code at {address}")),
+			Err(err) => Err(format!("contract code at {address} could not be verified: {err}")),
+		};
+		report.push(format!("{name} {contract} contract"), result);
+	}
+
+	let signer = chain.signer_address();
+	let result = match chain.signer_balance().await {
+		Ok(0) => Err(format!("signer {signer} has no gas balance")),
+		Ok(_) => Ok(()),
+		Err(err) => Err(format!("signer {signer} balance could not be read: {err}")),
+	};
+	report.push(format!("{name} signer balance"), result);
+}
+
+// Write then remove a probe record.
+async fn check_store(store: &dyn TransferStore) -> Result<(), String> {
+	let probe = TransferRecord {
+		transfer_id: STORE_PROBE_ID,
+		init_chain: ChainId::ONE,
+		state: TransferStateType::Done,
+		initiator_address: vec![],
+		recipient_address: vec![],
+		hash_lock: HashLock([0; 32]),
+		time_lock: TimeLock(0),
+		amount: Amount(AssetType::Moveth(0)),
+		created_at: 0,
+		updated_at: 0,
+		secret: None,
+		hash_algorithm: HashAlgorithm::Keccak256,
+	};
+	store.save(&probe).await.map_err(|err| format!("store not writable: {err}"))?;
+	store
+		.remove(STORE_PROBE_ID)
+		.await
+		.map_err(|err| format!("store not writable: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::store::MemoryStore;
+	use std::collections::HashSet;
+
+	struct StubChain {
+		name: &'static str,
+		contracts: Vec<(&'static str, String)>,
+		deployed: HashSet<String>,
+		balance: u128,
+	}
+
+	impl StubChain {
+		fn healthy(name: &'static str, contract_address: &str) -> Self {
+			StubChain {
+				name,
+				contracts: vec![("initiator", contract_address.to_string())],
+				deployed: HashSet::from([contract_address.to_string()]),
+				balance: 1_000,
+			}
+		}
+	}
+
+	#[async_trait::async_trait]
+	impl ChainHealthCheck for StubChain {
+		fn chain_name(&self) -> &'static str {
+			self.name
+		}
+
+		async fn check_rpc(&self) -> Result<(), String> {
+			Ok(())
+		}
+
+		fn contracts(&self) -> Vec<(&'static str, String)> {
+			self.contracts.clone()
+		}
+
+		async fn has_code(&self, address: &str) -> Result<bool, String> {
+			Ok(self.deployed.contains(address))
+		}
+
+		fn signer_address(&self) -> String {
+			"0xsigner".to_string()
+		}
+
+		async fn signer_balance(&self) -> Result<u128, String> {
+			Ok(self.balance)
+		}
+	}
+
+	#[tokio::test]
+	async fn test_self_check_passes() {
+		let eth = StubChain::healthy("Eth", "0x01");
+		let movement = StubChain::healthy("Movement", "0x02");
+		let report = self_check(&eth, &movement, &MemoryStore::default()).await.unwrap();
+		assert_eq!(report.items.len(), 7);
+		assert!(report.is_ok());
+	}
+
+	#[tokio::test]
+	async fn test_self_check_fails_on_misconfigured_contract_address() {
+		let mut eth = StubChain::healthy("Eth", "0x01");
+		// The configured address isn't the deployed one.
+		eth.contracts = vec![("initiator", "0xdead".to_string())];
+		let movement = StubChain::healthy("Movement", "0x02");
+
+		let Err(SelfCheckError::Failed(report)) =
+			self_check(&eth, &movement, &MemoryStore::default()).await
+		else {
+			panic!("self-check should fail");
+		};
+		let failures: Vec<_> = report.failures().collect();
+		assert_eq!(
+			failures,
+			vec![&CheckItem {
+				name: "Eth initiator contract".to_string(),
+				status: CheckStatus::Fail("no contract code at 0xdead".to_string()),
+			}]
+		);
+		assert!(report
+			.to_string()
+			.contains("[FAIL] Eth initiator contract: no contract code at 0xdead"));
+	}
+}
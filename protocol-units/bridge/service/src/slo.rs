@@ -0,0 +1,179 @@
+use bridge_config::common::slo::SloConfig;
+use prometheus::{Gauge, IntGauge, Opts, Registry};
+use std::collections::VecDeque;
+
+/// Values recorded over a rolling time window, the older ones are forgotten.
+#[derive(Debug, Clone)]
+pub struct WindowedHistogram {
+	window_secs: u64,
+	// (unix timestamp in seconds, value), by ascending timestamp.
+	samples: VecDeque<(u64, u64)>,
+}
+
+impl WindowedHistogram {
+	pub fn new(window_secs: u64) -> Self {
+		WindowedHistogram { window_secs, samples: VecDeque::new() }
+	}
+
+	pub fn record(&mut self, value: u64, now: u64) {
+		self.expire(now);
+		self.samples.push_back((now, value));
+	}
+
+	/// Number of values recorded in the window ending at `now`.
+	pub fn count(&mut self, now: u64) -> usize {
+		self.expire(now);
+		self.samples.len()
+	}
+
+	/// Number of values at most `max` recorded in the window ending at `now`.
+	pub fn count_at_most(&mut self, max: u64, now: u64) -> usize {
+		self.expire(now);
+		self.samples.iter().filter(|(_, value)| *value <= max).count()
+	}
+
+	fn expire(&mut self, now: u64) {
+		let start = now.saturating_sub(self.window_secs);
+		while self.samples.front().is_some_and(|(at, _)| *at <= start) {
+			self.samples.pop_front();
+		}
+	}
+}
+
+/// Share of the transfers completed within `target_secs` of their lock, expected to stay
+/// above `objective_percent` over the rolling window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SloPolicy {
+	pub target_secs: u64,
+	pub objective_percent: f64,
+	pub window_secs: u64,
+}
+
+impl From<&SloConfig> for SloPolicy {
+	fn from(config: &SloConfig) -> Self {
+		SloPolicy {
+			target_secs: config.slo_completion_target_secs,
+			objective_percent: config.slo_objective_percent,
+			window_secs: config.slo_window_secs,
+		}
+	}
+}
+
+/// Tracks the lock to completion durations of the transfers against the SLO.
+pub struct CompletionSlo {
+	policy: SloPolicy,
+	durations: WindowedHistogram,
+	breached: bool,
+	compliance_gauge: Gauge,
+	breached_gauge: IntGauge,
+}
+
+impl CompletionSlo {
+	pub fn register(registry: &Registry, policy: SloPolicy) -> prometheus::Result<Self> {
+		let compliance_gauge = Gauge::with_opts(Opts::new(
+			"bridge_completion_slo_compliance_percent",
+			"Percentage of the transfers completed within the SLO target over the window",
+		))?;
+		registry.register(Box::new(compliance_gauge.clone()))?;
+		let breached_gauge = IntGauge::with_opts(Opts::new(
+			"bridge_completion_slo_breached",
+			"1 if the completion SLO is breached over the window, 0 otherwise",
+		))?;
+		registry.register(Box::new(breached_gauge.clone()))?;
+		// Nothing completed yet, nothing late.
+		compliance_gauge.set(100.0);
+		Ok(CompletionSlo {
+			policy,
+			durations: WindowedHistogram::new(policy.window_secs),
+			breached: false,
+			compliance_gauge,
+			breached_gauge,
+		})
+	}
+
+	/// Record a transfer completed `duration_secs` after its lock.
+	pub fn record(&mut self, duration_secs: u64, now: u64) {
+		self.durations.record(duration_secs, now);
+	}
+
+	/// Percentage of the transfers of the window completed within the target,
+	/// None if none completed.
+	pub fn compliance(&mut self, now: u64) -> Option<f64> {
+		let count = self.durations.count(now);
+		if count == 0 {
+			return None;
+		}
+		let on_time = self.durations.count_at_most(self.policy.target_secs, now);
+		Some(on_time as f64 * 100.0 / count as f64)
+	}
+
+	/// Update the gauges with the compliance at `now`, alerting when the SLO gets breached
+	/// or recovers. Returns true while it's breached.
+	pub fn check(&mut self, now: u64) -> bool {
+		let compliance = self.compliance(now).unwrap_or(100.0);
+		self.compliance_gauge.set(compliance);
+		let breached = compliance < self.policy.objective_percent;
+		if breached != self.breached {
+			if breached {
+				tracing::error!(
+					"Completion SLO breached: {compliance:.2}% of the transfers of the last {}s completed within {}s, the objective is {}%",
+					self.policy.window_secs,
+					self.policy.target_secs,
+					self.policy.objective_percent
+				);
+			} else {
+				tracing::info!(
+					"Completion SLO met again: {compliance:.2}% of the transfers on time"
+				);
+			}
+			self.breached = breached;
+		}
+		self.breached_gauge.set(breached as i64);
+		breached
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn completion_slo() -> CompletionSlo {
+		let policy = SloPolicy { target_secs: 600, objective_percent: 95.0, window_secs: 3600 };
+		CompletionSlo::register(&Registry::new(), policy).unwrap()
+	}
+
+	#[test]
+	fn test_compliance_is_the_share_of_transfers_on_time() {
+		let mut slo = completion_slo();
+		assert_eq!(slo.compliance(1_000), None);
+		assert!(!slo.check(1_000));
+
+		// 19 transfers on time, including one right at the target, and one late.
+		for duration in (0..18).map(|i| i * 30).chain([600, 601]) {
+			slo.record(duration, 1_000);
+		}
+		assert_eq!(slo.compliance(1_000), Some(95.0));
+		assert!(!slo.check(1_000));
+
+		// A second late transfer breaches the objective.
+		slo.record(900, 1_100);
+		assert!(slo.compliance(1_100).unwrap() < 95.0);
+		assert!(slo.check(1_100));
+		assert_eq!(slo.breached_gauge.get(), 1);
+	}
+
+	#[test]
+	fn test_late_transfers_leave_the_window() {
+		let mut slo = completion_slo();
+		slo.record(1_200, 1_000);
+		slo.record(60, 2_000);
+		assert_eq!(slo.compliance(2_000), Some(50.0));
+		assert!(slo.check(2_000));
+
+		// Only the transfer on time is still in the window.
+		assert_eq!(slo.compliance(4_600), Some(100.0));
+		assert!(!slo.check(4_600));
+		assert_eq!(slo.compliance_gauge.get(), 100.0);
+		assert_eq!(slo.breached_gauge.get(), 0);
+	}
+}
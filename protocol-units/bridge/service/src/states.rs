@@ -1,13 +1,16 @@
 use crate::chains::bridge_contracts::BridgeContractEvent;
 use crate::events::{InvalidEventError, TransferEvent};
+use crate::store::TransferRecord;
 use crate::types::Amount;
 use crate::types::BridgeAddress;
 use crate::types::BridgeTransferDetails;
+use crate::types::HashAlgorithm;
 use crate::types::HashLockPreImage;
 use crate::types::LockDetails;
 use crate::types::{BridgeTransferId, ChainId, HashLock, TimeLock};
 use crate::TransferAction;
 use crate::TransferActionType;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TransferAddress(Vec<u8>);
@@ -25,7 +28,7 @@ impl<A: From<Vec<u8>>> From<TransferAddress> for BridgeAddress<A> {
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum TransferStateType {
 	Initialized,
 	Locked,
@@ -33,6 +36,45 @@ pub enum TransferStateType {
 	CompletedIntiator,
 	Done,
 	Refund,
+	// Time lock expired but auto refund is disabled, waiting for an operator refund.
+	RefundRequired,
+}
+
+impl TransferStateType {
+	/// Transitions allowed by the transfer state machine.
+	pub fn can_transition_to(self, next: TransferStateType) -> bool {
+		use TransferStateType::*;
+		matches!(
+			(self, next),
+			(Initialized, Locked | Refund | RefundRequired)
+				| (Locked, SecretReceived | Refund | RefundRequired)
+				| (SecretReceived, CompletedIntiator | Done)
+				| (CompletedIntiator, Done)
+				// The refunded event follows the refund sent by the relayer.
+				| (Refund, Refund)
+				| (RefundRequired, Refund)
+		)
+	}
+
+	pub fn check_transition(self, next: TransferStateType) -> Result<(), InvalidEventError> {
+		self.can_transition_to(next)
+			.then_some(())
+			.ok_or(InvalidEventError::IllegalTransition(self, next))
+	}
+
+	/// State a transfer moves to on `event`, None for the events that don't change it.
+	pub fn after_event<A>(event: &BridgeContractEvent<A>) -> Option<TransferStateType> {
+		match event {
+			BridgeContractEvent::Locked(_) => Some(TransferStateType::Locked),
+			BridgeContractEvent::CounterPartCompleted(..) => {
+				Some(TransferStateType::SecretReceived)
+			}
+			BridgeContractEvent::InitialtorCompleted(..) => Some(TransferStateType::Done),
+			BridgeContractEvent::Refunded(..) => Some(TransferStateType::Refund),
+			// The abort only refunds an expired transfer, see `transition_from_cancelled`.
+			BridgeContractEvent::Initiated(_) | BridgeContractEvent::Cancelled(..) => None,
+		}
+	}
 }
 
 #[allow(dead_code)]
@@ -45,17 +87,54 @@ pub struct TransferState {
 	pub hash_lock: HashLock,
 	pub time_lock: TimeLock,
 	pub amount: Amount,
+	// Amount of the counterparty lock, in the asset of the counterparty chain.
+	pub locked_amount: Amount,
 	pub contract_state: u8,
 	//Max number time action are retry for the whole transfer.
 	pub retry_on_error: usize,
+	// Revealed by the counterparty completion.
+	pub secret: Option<HashLockPreImage>,
+	// Unix timestamp in seconds of the next nudge, if any.
+	pub nudge_at: Option<u64>,
+	pub nudge_attempts: u32,
+	// Unix timestamp in seconds of the initiated event.
+	pub initiated_at: u64,
+	// Unix timestamp in seconds of the locked event, if observed.
+	pub locked_at: Option<u64>,
+	// Already alerted as stuck.
+	pub stuck: bool,
+	// Hash function of the hash lock, the one of the init chain.
+	pub hash_algorithm: HashAlgorithm,
 }
 
 impl TransferState {
-	pub fn validate_event<A>(&self, event: &TransferEvent<A>) -> Result<(), InvalidEventError> {
+	pub fn validate_event<A: Into<Vec<u8>> + Clone>(
+		&self,
+		event: &TransferEvent<A>,
+	) -> Result<(), InvalidEventError> {
 		match (&event.contract_event, &self.state) {
-			(BridgeContractEvent::Initiated(_), _) => {
-				// already present invalid
-				Err(InvalidEventError::InitAnAlreadyExist)
+			(BridgeContractEvent::Initiated(details), _) => {
+				if self.amount == details.amount
+					&& self.is_same_transfer(
+						&details.hash_lock,
+						&details.initiator_address,
+						&details.recipient_address,
+					) {
+					// already present invalid
+					Err(InvalidEventError::InitAnAlreadyExist)
+				} else {
+					Err(InvalidEventError::TransferIdCollision)
+				}
+			}
+			(BridgeContractEvent::Locked(details), _)
+				if self.locked_amount != details.amount
+					|| !self.is_same_transfer(
+						&details.hash_lock,
+						&details.initiator_address,
+						&details.recipient_address,
+					) =>
+			{
+				Err(InvalidEventError::TransferIdCollision)
 			}
 			// Lock event must on on the counter part chain.
 			(BridgeContractEvent::Locked(_), TransferStateType::Initialized) => (event.chain
@@ -64,14 +143,63 @@ impl TransferState {
 				.ok_or(InvalidEventError::BadChain),
 			// Mint event is only applied on Initialized swap state
 			(BridgeContractEvent::Locked(_), _) => Err(InvalidEventError::BadEvent),
-			//TODO
-			(BridgeContractEvent::InitialtorCompleted(_), _) => todo!(),
-			(BridgeContractEvent::CounterPartCompleted(_, _), _) => todo!(),
-			(BridgeContractEvent::Refunded(_), _) => todo!(),
-			(&BridgeContractEvent::Cancelled(_), _) => todo!(),
+			// The secret is revealed on the counter part chain once locked.
+			(
+				BridgeContractEvent::CounterPartCompleted(_, secret, _),
+				TransferStateType::Locked,
+			) => {
+				if event.chain == self.init_chain {
+					return Err(InvalidEventError::BadChain);
+				}
+				secret
+					.verify(&self.hash_lock, self.hash_algorithm)
+					.then_some(())
+					.ok_or(InvalidEventError::InvalidSecret(self.hash_algorithm))
+			}
+			(BridgeContractEvent::CounterPartCompleted(..), _) => Err(InvalidEventError::BadEvent),
+			// The initiator completes on the init chain with the received secret.
+			(BridgeContractEvent::InitialtorCompleted(..), TransferStateType::SecretReceived) => {
+				(event.chain == self.init_chain)
+					.then_some(())
+					.ok_or(InvalidEventError::BadChain)
+			}
+			(BridgeContractEvent::InitialtorCompleted(..), _) => Err(InvalidEventError::BadEvent),
+			// The refund happens on the init chain, by the relayer or an operator.
+			(
+				BridgeContractEvent::Refunded(..),
+				TransferStateType::Initialized
+				| TransferStateType::Locked
+				| TransferStateType::Refund
+				| TransferStateType::RefundRequired,
+			) => (event.chain == self.init_chain)
+				.then_some(())
+				.ok_or(InvalidEventError::BadChain),
+			(BridgeContractEvent::Refunded(..), _) => Err(InvalidEventError::BadEvent),
+			// The lock is aborted on the counter part chain, before the secret is revealed.
+			(
+				BridgeContractEvent::Cancelled(..),
+				TransferStateType::Locked
+				| TransferStateType::Refund
+				| TransferStateType::RefundRequired,
+			) => (event.chain != self.init_chain)
+				.then_some(())
+				.ok_or(InvalidEventError::BadChain),
+			(BridgeContractEvent::Cancelled(..), _) => Err(InvalidEventError::BadEvent),
 		}
 	}
 
+	// An event with this transfer id but other parameters doesn't belong to this transfer.
+	fn is_same_transfer<I: Into<Vec<u8>> + Clone, R: Into<Vec<u8>> + Clone>(
+		&self,
+		hash_lock: &HashLock,
+		initiator: &BridgeAddress<I>,
+		recipient: &BridgeAddress<R>,
+	) -> bool {
+		self.hash_lock == *hash_lock
+			&& self.intiator_address == TransferAddress::from(initiator.clone())
+			&& self.counter_part_address == TransferAddress::from(recipient.clone())
+	}
+
 	pub fn transition_from_initiated<A: Into<Vec<u8>> + Clone>(
 		chain_id: ChainId,
 		transfer_id: BridgeTransferId,
@@ -86,8 +214,16 @@ impl TransferState {
 			hash_lock: detail.hash_lock,
 			time_lock: detail.time_lock,
 			amount: detail.amount,
+			locked_amount: detail.amount,
 			contract_state: detail.state,
 			retry_on_error: 0,
+			secret: None,
+			nudge_at: None,
+			nudge_attempts: 0,
+			initiated_at: crate::unix_now(),
+			locked_at: None,
+			stuck: false,
+			hash_algorithm: HashAlgorithm::default(),
 		};
 
 		let action_type = TransferActionType::LockBridgeTransfer {
@@ -117,6 +253,7 @@ impl TransferState {
 		secret: HashLockPreImage,
 	) -> (Self, TransferActionType) {
 		self.state = TransferStateType::SecretReceived;
+		self.secret = Some(secret);
 		let action_type = TransferActionType::WaitAndCompleteInitiator(0, secret);
 		(self, action_type)
 	}
@@ -130,7 +267,174 @@ impl TransferState {
 		(self, action_type)
 	}
 
+	pub fn transition_from_refunded(
+		mut self,
+		_transfer_id: BridgeTransferId,
+	) -> (Self, TransferActionType) {
+		self.state = TransferStateType::Refund;
+		(self, TransferActionType::NoAction)
+	}
+
+	/// The counterparty lock was aborted, the recipient can't reveal the secret anymore.
+	/// The initiator is refunded once its time lock expired, by the relayer if `auto_refund`.
+	pub fn transition_from_cancelled(
+		mut self,
+		_transfer_id: BridgeTransferId,
+		now: u64,
+		auto_refund: bool,
+	) -> (Self, TransferActionType) {
+		// Not expired yet, the expiry check refunds it later.
+		if self.state != TransferStateType::Locked || !self.is_expired(now) {
+			return (self, TransferActionType::NoAction);
+		}
+		if auto_refund {
+			let (new_state, action_type) = self.transition_to_refund();
+			self.state = new_state;
+			(self, action_type)
+		} else {
+			self.state = TransferStateType::RefundRequired;
+			(self, TransferActionType::NoAction)
+		}
+	}
+
 	pub fn transition_to_refund(&self) -> (TransferStateType, TransferActionType) {
 		(TransferStateType::Refund, TransferActionType::RefundInitiator)
 	}
+
+	/// Rebuild the state of a transfer persisted before a restart.
+	pub fn from_record(record: &TransferRecord) -> Self {
+		TransferState {
+			state: record.state,
+			init_chain: record.init_chain,
+			transfer_id: record.transfer_id,
+			intiator_address: BridgeAddress(record.initiator_address.clone()).into(),
+			counter_part_address: BridgeAddress(record.recipient_address.clone()).into(),
+			hash_lock: record.hash_lock,
+			time_lock: record.time_lock,
+			amount: record.amount,
+			locked_amount: record.amount,
+			contract_state: 0,
+			retry_on_error: 0,
+			secret: record.secret,
+			nudge_at: None,
+			nudge_attempts: 0,
+			initiated_at: record.pending_since(),
+			locked_at: None,
+			stuck: false,
+			hash_algorithm: record.hash_algorithm,
+		}
+	}
+
+	/// Snapshot of the transfer to persist.
+	pub fn to_record(&self) -> TransferRecord {
+		TransferRecord {
+			transfer_id: self.transfer_id,
+			init_chain: self.init_chain,
+			state: self.state,
+			initiator_address: self.intiator_address.0.clone(),
+			recipient_address: self.counter_part_address.0.clone(),
+			hash_lock: self.hash_lock,
+			time_lock: self.time_lock,
+			amount: self.amount,
+			created_at: self.initiated_at,
+			updated_at: crate::unix_now(),
+			secret: self.secret,
+			hash_algorithm: self.hash_algorithm,
+		}
+	}
+
+	/// Chain action a restarted relayer must send again: the lock of an initialized transfer
+	/// or the initiator completion of a transfer whose secret was received.
+	/// A lock that already went through is rejected by the counterparty contract.
+	pub fn resume_action(&self) -> Option<TransferAction> {
+		let (chain, kind) = match (self.state, self.secret) {
+			(TransferStateType::Initialized, _) => (
+				self.init_chain.other(),
+				TransferActionType::LockBridgeTransfer {
+					bridge_transfer_id: self.transfer_id,
+					hash_lock: self.hash_lock,
+					initiator: self.intiator_address.clone().into(),
+					recipient: self.counter_part_address.clone().into(),
+					amount: self.amount,
+				},
+			),
+			(TransferStateType::SecretReceived, Some(secret)) => {
+				(self.init_chain, TransferActionType::WaitAndCompleteInitiator(0, secret))
+			}
+			_ => return None,
+		};
+		Some(TransferAction { chain, transfer_id: self.transfer_id, kind })
+	}
+
+	/// Time lock expired before the secret was revealed, the initiator must be refunded.
+	pub fn is_expired(&self, now: u64) -> bool {
+		matches!(self.state, TransferStateType::Initialized | TransferStateType::Locked)
+			&& self.time_lock.0 <= now
+	}
+
+	/// Still not completed `grace_secs` after its initiation.
+	pub fn is_stuck(&self, now: u64, grace_secs: u64) -> bool {
+		matches!(
+			self.state,
+			TransferStateType::Initialized
+				| TransferStateType::Locked
+				| TransferStateType::SecretReceived
+		) && self.initiated_at.saturating_add(grace_secs) <= now
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use TransferStateType::*;
+
+	const STATES: [TransferStateType; 7] =
+		[Initialized, Locked, SecretReceived, CompletedIntiator, Done, Refund, RefundRequired];
+
+	#[test]
+	fn test_legal_transitions_are_allowed() {
+		let legal = [
+			(Initialized, Locked),
+			(Initialized, Refund),
+			(Initialized, RefundRequired),
+			(Locked, SecretReceived),
+			(Locked, Refund),
+			(Locked, RefundRequired),
+			(SecretReceived, CompletedIntiator),
+			(SecretReceived, Done),
+			(CompletedIntiator, Done),
+			(Refund, Refund),
+			(RefundRequired, Refund),
+		];
+		for from in STATES {
+			for to in STATES {
+				assert_eq!(
+					from.check_transition(to).is_ok(),
+					legal.contains(&(from, to)),
+					"{from:?} -> {to:?}"
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn test_illegal_transitions_are_rejected() {
+		for (from, to) in [
+			// Completing an already refunded transfer.
+			(Refund, Done),
+			(Refund, SecretReceived),
+			// Refunding once the secret is revealed.
+			(SecretReceived, Refund),
+			(Done, Refund),
+			// Going back or skipping the lock.
+			(Locked, Initialized),
+			(Initialized, SecretReceived),
+			(Done, Done),
+		] {
+			assert!(matches!(
+				from.check_transition(to),
+				Err(InvalidEventError::IllegalTransition(f, t)) if f == from && t == to
+			));
+		}
+	}
 }
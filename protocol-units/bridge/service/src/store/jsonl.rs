@@ -0,0 +1,118 @@
+use super::{StoreError, TransferRecord, TransferStore};
+use crate::types::BridgeTransferId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+// One line of the file. The last line of a transfer id gives its current record.
+#[derive(Serialize, Deserialize)]
+enum JsonlEntry {
+	Saved(TransferRecord),
+	Removed(BridgeTransferId),
+}
+
+/// Append only JSON lines file store. The whole file is replayed in memory on open.
+pub struct JsonlStore {
+	path: PathBuf,
+	records: Mutex<HashMap<BridgeTransferId, TransferRecord>>,
+}
+
+impl JsonlStore {
+	pub async fn open(path: impl Into<PathBuf>) -> Result<Self, StoreError> {
+		let path = path.into();
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent).await?;
+		}
+		let mut records = HashMap::new();
+		if fs::try_exists(&path).await? {
+			let content = fs::read_to_string(&path).await?;
+			for line in content.lines().filter(|line| !line.trim().is_empty()) {
+				match serde_json::from_str(line)? {
+					JsonlEntry::Saved(record) => {
+						records.insert(record.transfer_id, record);
+					}
+					JsonlEntry::Removed(transfer_id) => {
+						records.remove(&transfer_id);
+					}
+				}
+			}
+		}
+		Ok(JsonlStore { path, records: Mutex::new(records) })
+	}
+
+	async fn append(&self, entry: &JsonlEntry) -> Result<(), StoreError> {
+		let mut line = serde_json::to_string(entry)?;
+		line.push('\n');
+		let mut file = OpenOptions::new().create(true).append(true).open(&self.path).await?;
+		file.write_all(line.as_bytes()).await?;
+		file.sync_data().await?;
+		Ok(())
+	}
+}
+
+#[async_trait::async_trait]
+impl TransferStore for JsonlStore {
+	async fn save(&self, record: &TransferRecord) -> Result<(), StoreError> {
+		let mut records = self.records.lock().await;
+		self.append(&JsonlEntry::Saved(record.clone())).await?;
+		records.insert(record.transfer_id, record.clone());
+		Ok(())
+	}
+
+	async fn get(
+		&self,
+		transfer_id: BridgeTransferId,
+	) -> Result<Option<TransferRecord>, StoreError> {
+		Ok(self.records.lock().await.get(&transfer_id).cloned())
+	}
+
+	async fn pending(&self) -> Result<Vec<TransferRecord>, StoreError> {
+		Ok(self.records.lock().await.values().filter(|r| r.is_pending()).cloned().collect())
+	}
+
+	async fn remove(&self, transfer_id: BridgeTransferId) -> Result<(), StoreError> {
+		let mut records = self.records.lock().await;
+		self.append(&JsonlEntry::Removed(transfer_id)).await?;
+		records.remove(&transfer_id);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::states::TransferStateType;
+	use crate::store::tests::{record, store_contract_suite};
+
+	fn temp_store_path(name: &str) -> PathBuf {
+		std::env::temp_dir().join(format!("bridge-store-{}-{}.jsonl", name, std::process::id()))
+	}
+
+	#[tokio::test]
+	async fn test_jsonl_store_contract() {
+		let path = temp_store_path("contract");
+		let _ = fs::remove_file(&path).await;
+		store_contract_suite(&JsonlStore::open(&path).await.unwrap()).await;
+		fs::remove_file(&path).await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn test_jsonl_store_reopen() {
+		let path = temp_store_path("reopen");
+		let _ = fs::remove_file(&path).await;
+		let locked = record(1, TransferStateType::Locked);
+		{
+			let store = JsonlStore::open(&path).await.unwrap();
+			store.save(&record(1, TransferStateType::Initialized)).await.unwrap();
+			store.save(&locked).await.unwrap();
+			store.save(&record(2, TransferStateType::Initialized)).await.unwrap();
+			store.remove(BridgeTransferId([2; 32])).await.unwrap();
+		}
+		let store = JsonlStore::open(&path).await.unwrap();
+		assert_eq!(store.pending().await.unwrap(), vec![locked]);
+		fs::remove_file(&path).await.unwrap();
+	}
+}
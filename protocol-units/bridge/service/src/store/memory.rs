@@ -0,0 +1,45 @@
+use super::{StoreError, TransferRecord, TransferStore};
+use crate::types::BridgeTransferId;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// Non persistent store, for tests and relayers that don't need to survive a restart.
+#[derive(Default)]
+pub struct MemoryStore {
+	records: RwLock<HashMap<BridgeTransferId, TransferRecord>>,
+}
+
+#[async_trait::async_trait]
+impl TransferStore for MemoryStore {
+	async fn save(&self, record: &TransferRecord) -> Result<(), StoreError> {
+		self.records.write().await.insert(record.transfer_id, record.clone());
+		Ok(())
+	}
+
+	async fn get(
+		&self,
+		transfer_id: BridgeTransferId,
+	) -> Result<Option<TransferRecord>, StoreError> {
+		Ok(self.records.read().await.get(&transfer_id).cloned())
+	}
+
+	async fn pending(&self) -> Result<Vec<TransferRecord>, StoreError> {
+		Ok(self.records.read().await.values().filter(|r| r.is_pending()).cloned().collect())
+	}
+
+	async fn remove(&self, transfer_id: BridgeTransferId) -> Result<(), StoreError> {
+		self.records.write().await.remove(&transfer_id);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::store::tests::store_contract_suite;
+
+	#[tokio::test]
+	async fn test_memory_store_contract() {
+		store_contract_suite(&MemoryStore::default()).await;
+	}
+}
@@ -0,0 +1,173 @@
+use crate::states::TransferStateType;
+use crate::types::{
+	Amount, BridgeTransferId, ChainId, HashAlgorithm, HashLock, HashLockPreImage, TimeLock,
+};
+use bridge_config::common::store::StoreConfig;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use thiserror::Error;
+
+mod jsonl;
+mod memory;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub use jsonl::JsonlStore;
+pub use memory::MemoryStore;
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresStore;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStore;
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+	#[error("Store io error: {0}")]
+	Io(#[from] std::io::Error),
+	#[error("Store serialization error: {0}")]
+	Serialization(#[from] serde_json::Error),
+	#[error("Store backend error: {0}")]
+	Backend(String),
+	#[error("Unknown or not compiled store backend: {0}")]
+	UnknownBackend(String),
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+impl From<sqlx::Error> for StoreError {
+	fn from(err: sqlx::Error) -> Self {
+		StoreError::Backend(err.to_string())
+	}
+}
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+impl From<sqlx::migrate::MigrateError> for StoreError {
+	fn from(err: sqlx::migrate::MigrateError) -> Self {
+		StoreError::Backend(format!("migration failed: {err}"))
+	}
+}
+
+/// Persisted snapshot of an in-flight transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferRecord {
+	pub transfer_id: BridgeTransferId,
+	pub init_chain: ChainId,
+	pub state: TransferStateType,
+	pub initiator_address: Vec<u8>,
+	pub recipient_address: Vec<u8>,
+	pub hash_lock: HashLock,
+	pub time_lock: TimeLock,
+	pub amount: Amount,
+	/// Unix timestamp in seconds of the first save, 0 for the records saved without it.
+	#[serde(default)]
+	pub created_at: u64,
+	/// Unix timestamp in seconds of the last update.
+	pub updated_at: u64,
+	/// Secret revealed by the counterparty completion, needed to complete the initiator.
+	#[serde(default)]
+	pub secret: Option<HashLockPreImage>,
+	/// Hash function of the hash lock, keccak256 for the records saved without it.
+	#[serde(default)]
+	pub hash_algorithm: HashAlgorithm,
+}
+
+impl TransferRecord {
+	/// A transfer is pending until it's done or refunded.
+	pub fn is_pending(&self) -> bool {
+		!matches!(self.state, TransferStateType::Done | TransferStateType::Refund)
+	}
+
+	/// Unix timestamp in seconds since the transfer is pending.
+	pub fn pending_since(&self) -> u64 {
+		if self.created_at == 0 {
+			self.updated_at
+		} else {
+			self.created_at
+		}
+	}
+}
+
+/// Persistence of the transfers handled by the relayer.
+#[async_trait::async_trait]
+pub trait TransferStore: Send + Sync {
+	/// Insert the record or replace the existing one with the same transfer id.
+	async fn save(&self, record: &TransferRecord) -> Result<(), StoreError>;
+
+	async fn get(
+		&self,
+		transfer_id: BridgeTransferId,
+	) -> Result<Option<TransferRecord>, StoreError>;
+
+	/// All the records that are still pending.
+	async fn pending(&self) -> Result<Vec<TransferRecord>, StoreError>;
+
+	async fn remove(&self, transfer_id: BridgeTransferId) -> Result<(), StoreError>;
+}
+
+/// Build the store selected by the configuration.
+pub async fn build_store(config: &StoreConfig) -> Result<Arc<dyn TransferStore>, StoreError> {
+	match config.store_backend.as_str() {
+		"memory" => Ok(Arc::new(MemoryStore::default())),
+		"jsonl" => Ok(Arc::new(JsonlStore::open(&config.store_url).await?)),
+		#[cfg(feature = "sqlite")]
+		"sqlite" => Ok(Arc::new(SqliteStore::connect(&config.store_url).await?)),
+		#[cfg(feature = "postgres")]
+		"postgres" => Ok(Arc::new(PostgresStore::connect(&config.store_url).await?)),
+		backend => Err(StoreError::UnknownBackend(backend.to_string())),
+	}
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use crate::types::AssetType;
+
+	pub(crate) fn record(id: u8, state: TransferStateType) -> TransferRecord {
+		TransferRecord {
+			transfer_id: BridgeTransferId([id; 32]),
+			init_chain: ChainId::ONE,
+			state,
+			initiator_address: vec![1; 20],
+			recipient_address: vec![2; 32],
+			hash_lock: HashLock([3; 32]),
+			time_lock: TimeLock(3600),
+			amount: Amount(AssetType::EthAndWeth((100, 0))),
+			created_at: 1_700_000_000,
+			updated_at: 1_700_000_000,
+			secret: None,
+			hash_algorithm: HashAlgorithm::Keccak256,
+		}
+	}
+
+	/// Behaviour every store backend must follow.
+	pub(crate) async fn store_contract_suite(store: &dyn TransferStore) {
+		let initialized = record(1, TransferStateType::Initialized);
+		let done = record(2, TransferStateType::Done);
+		assert_eq!(store.get(initialized.transfer_id).await.unwrap(), None);
+
+		store.save(&initialized).await.unwrap();
+		store.save(&done).await.unwrap();
+		assert_eq!(store.get(initialized.transfer_id).await.unwrap(), Some(initialized.clone()));
+		assert_eq!(store.pending().await.unwrap(), vec![initialized.clone()]);
+
+		// Saving again replaces the record.
+		let locked = TransferRecord { state: TransferStateType::Locked, ..initialized.clone() };
+		store.save(&locked).await.unwrap();
+		assert_eq!(store.get(locked.transfer_id).await.unwrap(), Some(locked.clone()));
+		assert_eq!(store.pending().await.unwrap(), vec![locked.clone()]);
+
+		store.remove(locked.transfer_id).await.unwrap();
+		assert_eq!(store.get(locked.transfer_id).await.unwrap(), None);
+		assert!(store.pending().await.unwrap().is_empty());
+		assert_eq!(store.get(done.transfer_id).await.unwrap(), Some(done));
+	}
+
+	#[tokio::test]
+	async fn test_unknown_backend_is_rejected() {
+		let config = StoreConfig { store_backend: "rocks".to_string(), store_url: String::new() };
+		assert!(matches!(build_store(&config).await, Err(StoreError::UnknownBackend(_))));
+	}
+}
@@ -0,0 +1,86 @@
+use super::{StoreError, TransferRecord, TransferStore, MIGRATOR};
+use crate::types::BridgeTransferId;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+
+/// Postgres store, for highly available deployments.
+pub struct PostgresStore {
+	pool: PgPool,
+}
+
+impl PostgresStore {
+	/// Connect to the database at `url` (e.g. `postgres://user@host/bridge`)
+	/// and apply the pending migrations.
+	pub async fn connect(url: &str) -> Result<Self, StoreError> {
+		let pool = PgPoolOptions::new().connect(url).await?;
+		MIGRATOR.run(&pool).await?;
+		Ok(PostgresStore { pool })
+	}
+}
+
+#[async_trait::async_trait]
+impl TransferStore for PostgresStore {
+	async fn save(&self, record: &TransferRecord) -> Result<(), StoreError> {
+		sqlx::query(
+			"INSERT INTO bridge_transfers (transfer_id, pending, record, updated_at) \
+			VALUES ($1, $2, $3, $4) \
+			ON CONFLICT (transfer_id) DO UPDATE SET \
+			pending = excluded.pending, record = excluded.record, updated_at = excluded.updated_at",
+		)
+		.bind(hex::encode(record.transfer_id.0))
+		.bind(record.is_pending())
+		.bind(serde_json::to_string(record)?)
+		.bind(record.updated_at as i64)
+		.execute(&self.pool)
+		.await?;
+		Ok(())
+	}
+
+	async fn get(
+		&self,
+		transfer_id: BridgeTransferId,
+	) -> Result<Option<TransferRecord>, StoreError> {
+		let record: Option<String> =
+			sqlx::query_scalar("SELECT record FROM bridge_transfers WHERE transfer_id = $1")
+				.bind(hex::encode(transfer_id.0))
+				.fetch_optional(&self.pool)
+				.await?;
+		Ok(record.map(|record| serde_json::from_str(&record)).transpose()?)
+	}
+
+	async fn pending(&self) -> Result<Vec<TransferRecord>, StoreError> {
+		let records: Vec<String> =
+			sqlx::query_scalar("SELECT record FROM bridge_transfers WHERE pending")
+				.fetch_all(&self.pool)
+				.await?;
+		Ok(records
+			.iter()
+			.map(|record| serde_json::from_str(record))
+			.collect::<Result<_, _>>()?)
+	}
+
+	async fn remove(&self, transfer_id: BridgeTransferId) -> Result<(), StoreError> {
+		sqlx::query("DELETE FROM bridge_transfers WHERE transfer_id = $1")
+			.bind(hex::encode(transfer_id.0))
+			.execute(&self.pool)
+			.await?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::store::tests::store_contract_suite;
+
+	// Needs a database, set BRIDGE_STORE_TEST_POSTGRES_URL to run it.
+	#[tokio::test]
+	async fn test_postgres_store_contract() {
+		let Ok(url) = std::env::var("BRIDGE_STORE_TEST_POSTGRES_URL") else {
+			tracing::warn!("BRIDGE_STORE_TEST_POSTGRES_URL not set, skipping postgres store test");
+			return;
+		};
+		let store = PostgresStore::connect(&url).await.unwrap();
+		sqlx::query("DELETE FROM bridge_transfers").execute(&store.pool).await.unwrap();
+		store_contract_suite(&store).await;
+	}
+}
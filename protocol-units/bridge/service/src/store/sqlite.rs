@@ -0,0 +1,83 @@
+use super::{StoreError, TransferRecord, TransferStore, MIGRATOR};
+use crate::types::BridgeTransferId;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::str::FromStr;
+
+/// Sqlite store, for single node deployments.
+pub struct SqliteStore {
+	pool: SqlitePool,
+}
+
+impl SqliteStore {
+	/// Open the database at `url` (e.g. `sqlite://bridge.db`), creating it if needed,
+	/// and apply the pending migrations.
+	pub async fn connect(url: &str) -> Result<Self, StoreError> {
+		let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+		// Sqlite has a single writer, one connection also keeps in memory databases shared.
+		let pool = SqlitePoolOptions::new().max_connections(1).connect_with(options).await?;
+		MIGRATOR.run(&pool).await?;
+		Ok(SqliteStore { pool })
+	}
+}
+
+#[async_trait::async_trait]
+impl TransferStore for SqliteStore {
+	async fn save(&self, record: &TransferRecord) -> Result<(), StoreError> {
+		sqlx::query(
+			"INSERT INTO bridge_transfers (transfer_id, pending, record, updated_at) \
+			VALUES (?, ?, ?, ?) \
+			ON CONFLICT (transfer_id) DO UPDATE SET \
+			pending = excluded.pending, record = excluded.record, updated_at = excluded.updated_at",
+		)
+		.bind(hex::encode(record.transfer_id.0))
+		.bind(record.is_pending())
+		.bind(serde_json::to_string(record)?)
+		.bind(record.updated_at as i64)
+		.execute(&self.pool)
+		.await?;
+		Ok(())
+	}
+
+	async fn get(
+		&self,
+		transfer_id: BridgeTransferId,
+	) -> Result<Option<TransferRecord>, StoreError> {
+		let record: Option<String> =
+			sqlx::query_scalar("SELECT record FROM bridge_transfers WHERE transfer_id = ?")
+				.bind(hex::encode(transfer_id.0))
+				.fetch_optional(&self.pool)
+				.await?;
+		Ok(record.map(|record| serde_json::from_str(&record)).transpose()?)
+	}
+
+	async fn pending(&self) -> Result<Vec<TransferRecord>, StoreError> {
+		let records: Vec<String> =
+			sqlx::query_scalar("SELECT record FROM bridge_transfers WHERE pending")
+				.fetch_all(&self.pool)
+				.await?;
+		Ok(records
+			.iter()
+			.map(|record| serde_json::from_str(record))
+			.collect::<Result<_, _>>()?)
+	}
+
+	async fn remove(&self, transfer_id: BridgeTransferId) -> Result<(), StoreError> {
+		sqlx::query("DELETE FROM bridge_transfers WHERE transfer_id = ?")
+			.bind(hex::encode(transfer_id.0))
+			.execute(&self.pool)
+			.await?;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::store::tests::store_contract_suite;
+
+	#[tokio::test]
+	async fn test_sqlite_store_contract() {
+		let store = SqliteStore::connect("sqlite::memory:").await.unwrap();
+		store_contract_suite(&store).await;
+	}
+}
@@ -1,16 +1,19 @@
+use crate::chains::ethereum::types::EthAddress;
 use alloy::primitives::Uint;
 use derive_more::{Deref, DerefMut};
 use hex::{self, FromHexError};
 use rand::Rng;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::convert::TryFrom;
 use std::fmt;
+use std::str::FromStr;
 use std::{fmt::Debug, hash::Hash};
 use thiserror::Error;
 
 pub type BridgeHash = [u8; 32];
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ChainId {
 	ONE,
 	TWO,
@@ -35,7 +38,7 @@ impl fmt::Display for ChainId {
 	}
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct BridgeTransferId(pub BridgeHash);
 
 impl BridgeTransferId {
@@ -86,7 +89,7 @@ impl From<String> for BridgeAddress<Vec<u8>> {
 // 	}
 // }
 
-#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct HashLock(pub [u8; 32]);
 
 impl HashLock {
@@ -105,7 +108,7 @@ impl HashLock {
 	}
 }
 
-#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HashLockPreImage(pub [u8; 32]);
 
 impl AsRef<[u8]> for HashLockPreImage {
@@ -122,9 +125,57 @@ impl HashLockPreImage {
 		rng.fill(&mut secret);
 		HashLockPreImage(secret)
 	}
+
+	/// Hash lock of this secret with `algorithm`.
+	pub fn hash_lock(&self, algorithm: HashAlgorithm) -> HashLock {
+		algorithm.hash(&self.0)
+	}
+
+	/// Returns true if this secret unlocks `hash_lock`, hashed with `algorithm`.
+	pub fn verify(&self, hash_lock: &HashLock, algorithm: HashAlgorithm) -> bool {
+		self.hash_lock(algorithm) == *hash_lock
+	}
+}
+
+/// Hash function of the hash locks, both chains of a transfer must use the same.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HashAlgorithm {
+	#[default]
+	Keccak256,
+	Sha256,
+}
+
+impl HashAlgorithm {
+	pub fn hash(&self, data: &[u8]) -> HashLock {
+		match self {
+			HashAlgorithm::Keccak256 => HashLock(keccak_hash::keccak(data).0),
+			HashAlgorithm::Sha256 => HashLock(Sha256::digest(data).into()),
+		}
+	}
 }
 
-#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+impl FromStr for HashAlgorithm {
+	type Err = ConversionError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"keccak256" => Ok(HashAlgorithm::Keccak256),
+			"sha256" => Ok(HashAlgorithm::Sha256),
+			_ => Err(ConversionError::UnknownHashAlgorithm(s.to_string())),
+		}
+	}
+}
+
+impl fmt::Display for HashAlgorithm {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			HashAlgorithm::Keccak256 => write!(f, "keccak256"),
+			HashAlgorithm::Sha256 => write!(f, "sha256"),
+		}
+	}
+}
+
+#[derive(Deref, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TimeLock(pub u64);
 
 impl From<Uint<256, 4>> for TimeLock {
@@ -135,20 +186,22 @@ impl From<Uint<256, 4>> for TimeLock {
 	}
 }
 
-#[derive(Deref, DerefMut, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Deref, DerefMut, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Amount(pub AssetType);
 
 impl Amount {
-	// pub fn value(&self) -> u64 {
-	// 	match self.0 {
-	// 		AssetType::EthAndWeth((weth_value, eth_value)) => weth_value + eth_value,
-	// 		AssetType::Moveth(value) => value,
-	// 	}
-	// }
+	/// Total transferred value, whatever the asset.
+	pub fn value(&self) -> u64 {
+		match self.0 {
+			AssetType::EthAndWeth((eth_value, weth_value)) => eth_value.saturating_add(weth_value),
+			AssetType::Moveth(value) => value,
+			AssetType::Erc20 { amount, .. } => amount,
+		}
+	}
 
 	pub fn moveth_value(&self) -> u64 {
 		match self.0 {
-			AssetType::EthAndWeth((_, _)) => 0,
+			AssetType::EthAndWeth((_, _)) | AssetType::Erc20 { .. } => 0,
 			AssetType::Moveth(value) => value,
 		}
 	}
@@ -156,14 +209,44 @@ impl Amount {
 	pub fn eth_value(&self) -> u64 {
 		match self.0 {
 			AssetType::EthAndWeth((eth_value, _)) => eth_value,
-			AssetType::Moveth(_) => 0,
+			AssetType::Moveth(_) | AssetType::Erc20 { .. } => 0,
 		}
 	}
 
 	pub fn weth_value(&self) -> u64 {
 		match self.0 {
 			AssetType::EthAndWeth((_, weth_value)) => weth_value,
-			AssetType::Moveth(_) => 0,
+			AssetType::Moveth(_) | AssetType::Erc20 { .. } => 0,
+		}
+	}
+
+	/// Value of the ERC20 token transferred through the bridge contract: the WETH or the token
+	/// of an `Erc20` amount.
+	pub fn token_value(&self) -> u64 {
+		match self.0 {
+			AssetType::Erc20 { amount, .. } => amount,
+			_ => self.weth_value(),
+		}
+	}
+
+	/// Address of the token contract, only known for an `Erc20` amount.
+	pub fn token_address(&self) -> Option<EthAddress> {
+		match self.0 {
+			AssetType::Erc20 { token_address, .. } => Some(token_address),
+			_ => None,
+		}
+	}
+
+	/// Same asset with `value` as token value, the native eth value is kept.
+	pub fn with_token_value(&self, value: u64) -> Amount {
+		match self.0 {
+			AssetType::EthAndWeth((eth_value, _)) => {
+				Amount(AssetType::EthAndWeth((eth_value, value)))
+			}
+			AssetType::Moveth(_) => Amount(AssetType::Moveth(value)),
+			AssetType::Erc20 { token_address, .. } => {
+				Amount(AssetType::Erc20 { token_address, amount: value })
+			}
 		}
 	}
 }
@@ -176,12 +259,75 @@ impl From<Uint<256, 4>> for Amount {
 }
 
 /// The type of Asset being used
-#[derive(Clone, Debug, PartialEq, Eq, Copy, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Copy, Serialize, Deserialize)]
 pub enum AssetType {
 	/// Where the first tuple value is `Eth` and the second tuple value is `Weth`  
 	//TODO eth and weth can be mixed during creation. Use type def to avoid that.
 	EthAndWeth((u64, u64)),
 	Moveth(u64),
+	/// Any ERC20 token locked by the Eth bridge contract.
+	Erc20 {
+		token_address: EthAddress,
+		amount: u64,
+	},
+}
+
+impl AssetType {
+	pub fn tag(&self) -> AssetTag {
+		match self {
+			AssetType::EthAndWeth(_) => AssetTag::EthAndWeth,
+			AssetType::Moveth(_) => AssetTag::Moveth,
+			AssetType::Erc20 { token_address, .. } => AssetTag::Erc20(*token_address),
+		}
+	}
+}
+
+/// Identifies the asset of an event, independently of any amount.
+#[derive(Clone, Debug, PartialEq, Eq, Copy, Hash, Serialize, Deserialize)]
+pub enum AssetTag {
+	EthAndWeth,
+	Moveth,
+	Erc20(EthAddress),
+}
+
+impl AssetTag {
+	/// Amount of this asset for an on chain value.
+	pub fn amount(&self, value: u64) -> Amount {
+		match self {
+			// Wrapped and native eth can't be told apart from the value, count it as weth.
+			AssetTag::EthAndWeth => Amount(AssetType::EthAndWeth((0, value))),
+			AssetTag::Moveth => Amount(AssetType::Moveth(value)),
+			AssetTag::Erc20(token_address) => {
+				Amount(AssetType::Erc20 { token_address: *token_address, amount: value })
+			}
+		}
+	}
+}
+
+impl FromStr for AssetTag {
+	type Err = ConversionError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"eth_and_weth" => Ok(AssetTag::EthAndWeth),
+			"moveth" => Ok(AssetTag::Moveth),
+			// The token address follows the prefix: `erc20:0x...`.
+			_ => match s.strip_prefix("erc20:").map(str::parse) {
+				Some(Ok(token_address)) => Ok(AssetTag::Erc20(EthAddress(token_address))),
+				_ => Err(ConversionError::UnknownAsset(s.to_string())),
+			},
+		}
+	}
+}
+
+impl fmt::Display for AssetTag {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			AssetTag::EthAndWeth => write!(f, "eth_and_weth"),
+			AssetTag::Moveth => write!(f, "moveth"),
+			AssetTag::Erc20(token_address) => write!(f, "erc20:{}", token_address.0),
+		}
+	}
 }
 
 impl From<Uint<256, 4>> for AssetType {
@@ -196,6 +342,10 @@ impl From<Uint<256, 4>> for AssetType {
 pub enum ConversionError {
 	#[error("Invalid conversion from AssetType to Uint")]
 	InvalidConversion,
+	#[error("Unknown asset: {0}")]
+	UnknownAsset(String),
+	#[error("Unknown hash algorithm: {0}")]
+	UnknownHashAlgorithm(String),
 }
 
 impl TryFrom<AssetType> for Uint<256, 4> {
@@ -209,6 +359,7 @@ impl TryFrom<AssetType> for Uint<256, 4> {
 				Ok(Uint::from(combined_value))
 			}
 			AssetType::Moveth(value) => Ok(Uint::from(value as u128)),
+			AssetType::Erc20 { amount, .. } => Ok(Uint::from(amount as u128)),
 		}
 	}
 }
@@ -233,3 +384,36 @@ pub struct LockDetails<A> {
 	pub time_lock: TimeLock,
 	pub amount: Amount,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_secret_only_verifies_with_its_algorithm() {
+		let secret = HashLockPreImage([0; 32]);
+		let sha256_lock = secret.hash_lock(HashAlgorithm::Sha256);
+		assert_eq!(
+			hex::encode(sha256_lock.0),
+			"66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925"
+		);
+		assert!(secret.verify(&sha256_lock, HashAlgorithm::Sha256));
+		assert!(!secret.verify(&sha256_lock, HashAlgorithm::Keccak256));
+		assert!(!HashLockPreImage([1; 32]).verify(&sha256_lock, HashAlgorithm::Sha256));
+
+		let keccak_lock = secret.hash_lock(HashAlgorithm::Keccak256);
+		assert_eq!(
+			hex::encode(keccak_lock.0),
+			"290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563"
+		);
+		assert!(secret.verify(&keccak_lock, HashAlgorithm::Keccak256));
+	}
+
+	#[test]
+	fn test_hash_algorithm_round_trips_through_its_name() {
+		for algorithm in [HashAlgorithm::Keccak256, HashAlgorithm::Sha256] {
+			assert_eq!(algorithm.to_string().parse::<HashAlgorithm>().unwrap(), algorithm);
+		}
+		assert!("md5".parse::<HashAlgorithm>().is_err());
+	}
+}
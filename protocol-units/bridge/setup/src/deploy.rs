@@ -6,6 +6,7 @@ use alloy_primitives::U256;
 use bridge_config::common::eth::EthConfig;
 use bridge_config::common::movement::MovementConfig;
 use bridge_config::Config as BridgeConfig;
+use bridge_service::chains::ethereum::gas_price::GasPricing;
 use bridge_service::chains::ethereum::types::AtomicBridgeCounterparty;
 use bridge_service::chains::ethereum::types::AtomicBridgeInitiator;
 use bridge_service::chains::ethereum::types::EthAddress;
@@ -54,6 +55,7 @@ pub async fn setup_local_ethereum(config: &mut EthConfig) -> Result<(), anyhow::
 		*TimeLock(1),
 		config.gas_limit,
 		config.transaction_send_retries,
+		&GasPricing::try_from(&*config)?,
 	)
 	.await?;
 	Ok(())
@@ -115,6 +117,7 @@ async fn initialize_initiator_contract(
 	timelock: u64,
 	gas_limit: u64,
 	transaction_send_retries: u32,
+	gas_pricing: &GasPricing,
 ) -> Result<(), anyhow::Error> {
 	let rpc_provider = ProviderBuilder::new()
 		.with_recommended_fillers()
@@ -126,9 +129,15 @@ async fn initialize_initiator_contract(
 		AtomicBridgeInitiator::new(initiator_contract_address.parse()?, rpc_provider);
 
 	let call = initiator_contract.initialize(weth.0, owner.0, U256::from(timelock));
-	send_transaction(call, &send_transaction_rules(), transaction_send_retries, gas_limit.into())
-		.await
-		.expect("Failed to send transaction");
+	send_transaction(
+		call,
+		&send_transaction_rules(),
+		transaction_send_retries,
+		gas_limit.into(),
+		gas_pricing,
+	)
+	.await
+	.expect("Failed to send transaction");
 	Ok(())
 }
 
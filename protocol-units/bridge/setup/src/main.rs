@@ -99,10 +99,10 @@ async fn main() -> Result<(), anyhow::Error> {
 			// Use custom as movement node in init.
 			config.movement.mvt_init_network = "custom".to_string();
 
-			tracing::info!("Bridge Config before setup: {:?}", config);
+			tracing::info!("Bridge Config before setup: {:?}", config.redacted());
 
 			let config = bridge_setup::process_compose_setup(config).await?;
-			tracing::info!("Bridge Config after setup: {:?}", config);
+			tracing::info!("Bridge Config after setup: {:?}", config.redacted());
 
 			Ok(Some(config))
 		})